@@ -1,6 +1,6 @@
 // TODO: support other value types (maybe strings for localization changes? idk)
 
-use std::{borrow::Cow, collections::HashMap, io::Cursor, ops::DerefMut};
+use std::{borrow::Cow, collections::HashMap, io::Cursor};
 use noumenon::format::game::exd::Field;
 use serde::{Deserialize, Serialize};
 use crate::modman::OptionOrStatic;
@@ -61,16 +61,16 @@ impl super::Composite for Exd {
 		let header_path = crate::modman::Path::Game(format!("{}.exh", &self.path[0..self.path.len() - 6]));
 		let header = noumenon::format::game::Exh::read(&mut Cursor::new(file_resolver(&header_path).ok_or(CompositeError::NoFileResolverReturnExh)?.as_ref()))?;
 		let sheet_path = crate::modman::Path::Game(self.path.clone());
-		let mut sheet = noumenon::format::game::Exd::read(&mut Cursor::new(file_resolver(&sheet_path).ok_or(CompositeError::NoFileResolverReturnExd)?.as_ref()))?;
+		let mut sheet = noumenon::format::game::Exd::read(&mut Cursor::new(file_resolver(&sheet_path).ok_or(CompositeError::NoFileResolverReturnExd)?.as_ref()), &header)?;
 		
 		for (row, columns) in &self.rows {
 			for (column, val) in columns {
-				let mut fields = sheet.get_fields_mut(*row, 0, &header).ok_or(CompositeError::InvalidRow{row: *row})?;
+				let fields = sheet.get_fields_mut(*row, 0).ok_or(CompositeError::InvalidRow{row: *row})?;
 				let val = val.resolve(meta, settings).ok_or(CompositeError::ValueResolveFailure)?;
 				
 				match fields.get_mut(*column as usize).ok_or(CompositeError::InvalidColumn{column: *column})? {
 					Field::U32(v) => {
-						*v.deref_mut() =
+						*v =
 							(((val[0] * 255.0).clamp(0.0, 255.0) as u32) << 24) +
 							(((val[1] * 255.0).clamp(0.0, 255.0) as u32) << 16) +
 							(((val[2] * 255.0).clamp(0.0, 255.0) as u32) << 8) +
@@ -83,7 +83,7 @@ impl super::Composite for Exd {
 		}
 		
 		let mut data = Vec::new();
-		sheet.write(&mut Cursor::new(&mut data))?;
+		sheet.write(&mut Cursor::new(&mut data), &header)?;
 		Ok(data)
 	}
 }
\ No newline at end of file
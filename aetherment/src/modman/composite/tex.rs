@@ -49,7 +49,7 @@ impl Tex {
 		let layer = layers.next().ok_or(CompositeError::NoFirstLayer)?;
 		let tex = &textures_handler(&layer.path).map_err(|err| CompositeError::NoFileResolverReturn{path: layer.path.clone(), layer: 0, err})?;
 		let (width, height) = (tex.width as u32, tex.height as u32);
-		let mut data = tex.slice(0, 0).pixels.to_vec();
+		let mut data = tex.slice(0, 0, 0).pixels.to_vec();
 		
 		let apply_modifiers = |layer: &Layer, data: &mut [u8]| -> Result<(), ModifierError> {
 			let modifiers = layer.modifiers.iter().rev();
@@ -264,10 +264,10 @@ impl super::Composite for Tex {
 
 fn get_resized(tex: &noumenon::format::game::Tex, width: u32, height: u32, target_width: u32, target_height: u32) -> Vec<u8> {
 	if width != target_width || height != target_height {
-		let img: image::ImageBuffer<image::Rgba<u8>, _> = image::ImageBuffer::from_vec(width, height, tex.slice(0, 0).pixels.to_vec()).unwrap();
+		let img: image::ImageBuffer<image::Rgba<u8>, _> = image::ImageBuffer::from_vec(width, height, tex.slice(0, 0, 0).pixels.to_vec()).unwrap();
 		image::imageops::resize(&img, target_width, target_height, image::imageops::FilterType::Nearest).into_vec()
 	} else {
-		tex.slice(0, 0).pixels.to_vec()
+		tex.slice(0, 0, 0).pixels.to_vec()
 	}
 }
 
@@ -0,0 +1,194 @@
+use std::{collections::HashMap, io::Cursor};
+use noumenon::format::{external::Bytes, game::{imc::ImcEntry, gmp::GmpEntry, Cmp, Eqdp, Eqp, Est, Gmp, Imc}};
+use super::Manipulation;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ManipulationError {
+	#[error("Invalid {field} {value:?}")]
+	InvalidValue{field: &'static str, value: String},
+	
+	#[error("Game file {0:?} could not be read")]
+	NoFileResolverReturn(String),
+	
+	#[error("Manipulation can't be applied to {0:?}")]
+	MismatchedFile(String),
+	
+	#[error("{0}")]
+	Noumenon(#[from] noumenon::Error),
+}
+
+enum MetaFile {
+	Imc(Imc),
+	Eqdp(Eqdp),
+	Eqp(Eqp),
+	Est(Est),
+	Gmp(Gmp),
+	Cmp(Cmp),
+}
+
+impl MetaFile {
+	fn read(path: &str, data: Vec<u8>) -> Result<Self, noumenon::Error> {
+		let mut data = Cursor::new(data);
+		Ok(match path.rsplit('.').next().unwrap_or_default() {
+			"imc" => Self::Imc(Imc::read(&mut data)?),
+			"eqdp" => Self::Eqdp(Eqdp::read(&mut data)?),
+			"eqp" => Self::Eqp(Eqp::read(&mut data)?),
+			"est" => Self::Est(Est::read(&mut data)?),
+			"gmp" => Self::Gmp(Gmp::read(&mut data)?),
+			"cmp" => Self::Cmp(Cmp::read(&mut data)?),
+			_ => return Err(noumenon::Error::InvalidFormatFrom(path.to_string())),
+		})
+	}
+	
+	fn write(&self) -> Result<Vec<u8>, noumenon::Error> {
+		let mut data = Cursor::new(Vec::new());
+		match self {
+			Self::Imc(v) => v.write(&mut data)?,
+			Self::Eqdp(v) => v.write(&mut data)?,
+			Self::Eqp(v) => v.write(&mut data)?,
+			Self::Est(v) => v.write(&mut data)?,
+			Self::Gmp(v) => v.write(&mut data)?,
+			Self::Cmp(v) => v.write(&mut data)?,
+		}
+		
+		Ok(data.into_inner())
+	}
+}
+
+impl Manipulation {
+	/// The game file the manipulation changes
+	pub fn path(&self) -> Result<String, ManipulationError> {
+		Ok(match self {
+			Self::Imc{primary_id, secondary_id, object_type, ..} => {
+				let primary = int::<u16>("primary id", *primary_id)?;
+				let secondary = int::<u16>("secondary id", *secondary_id)?;
+				match object_type.as_str() {
+					"Equipment" => format!("chara/equipment/e{primary:04}/e{primary:04}.imc"),
+					"Accessory" => format!("chara/accessory/a{primary:04}/a{primary:04}.imc"),
+					"Weapon" => format!("chara/weapon/w{primary:04}/obj/body/b{secondary:04}/b{secondary:04}.imc"),
+					"Monster" => format!("chara/monster/m{primary:04}/obj/body/b{secondary:04}/b{secondary:04}.imc"),
+					"DemiHuman" => format!("chara/demihuman/d{primary:04}/obj/equipment/e{secondary:04}/e{secondary:04}.imc"),
+					_ => return Err(invalid("object type", object_type)),
+				}
+			}
+			
+			Self::Eqdp{slot, race, gender, ..} => {
+				let (accessory, _) = Eqdp::slot_mask(slot).ok_or_else(|| invalid("slot", slot))?;
+				Eqdp::path(race_code(race, gender)?, accessory)
+			}
+			
+			Self::Eqp{..} => Eqp::PATH.to_string(),
+			Self::Est{slot, ..} => Est::path(slot).ok_or_else(|| invalid("slot", slot))?.to_string(),
+			Self::Gmp{..} => Gmp::PATH.to_string(),
+			Self::Rsp{..} => Cmp::PATH.to_string(),
+		})
+	}
+	
+	fn apply(&self, file: &mut MetaFile) -> Result<(), ManipulationError> {
+		match (self, file) {
+			(Self::Imc{material_id, decal_id, vfx_id, material_animation_id, attribute_mask, sound_id, variant, object_type, equip_slot, ..}, MetaFile::Imc(imc)) => {
+				let part = match object_type.as_str() {
+					"Equipment" | "Accessory" | "DemiHuman" => Imc::part_index(equip_slot).ok_or_else(|| invalid("equip slot", equip_slot))?,
+					_ => 0,
+				};
+				
+				// the combined attribute_and_sound isn't always set, the seperate values are
+				let entry = ImcEntry {
+					material_id: int("material id", *material_id)?,
+					decal_id: int("decal id", *decal_id)?,
+					attribute_and_sound: bits("attribute mask", *attribute_mask, 10)? | bits("sound id", *sound_id, 6)? << 10,
+					vfx_id: int("vfx id", *vfx_id)?,
+					material_animation_id: int("material animation id", *material_animation_id)?,
+				};
+				
+				imc.set_entry(int("variant", *variant)?, part, entry)?;
+			}
+			
+			(Self::Eqdp{entry, set_id, slot, ..}, MetaFile::Eqdp(eqdp)) => {
+				let (_, mask) = Eqdp::slot_mask(slot).ok_or_else(|| invalid("slot", slot))?;
+				let set_id = int("set id", *set_id)?;
+				eqdp.set(set_id, eqdp.get(set_id) & !mask | *entry as u16 & mask)?;
+			}
+			
+			(Self::Eqp{entry, set_id, slot}, MetaFile::Eqp(eqp)) => {
+				let mask = Eqp::slot_mask(slot).ok_or_else(|| invalid("slot", slot))?;
+				let set_id = int("set id", *set_id)?;
+				eqp.set(set_id, eqp.get(set_id) & !mask | *entry & mask)?;
+			}
+			
+			(Self::Est{entry, set_id, race, gender, ..}, MetaFile::Est(est)) => {
+				est.set(int("set id", *set_id)?, race_code(race, gender)?, int("entry", *entry)?);
+			}
+			
+			(Self::Gmp{enabled, animated, rotation_a, rotation_b, rotation_c, unknown_a, unknown_b, set_id, ..}, MetaFile::Gmp(gmp)) => {
+				gmp.set(int("set id", *set_id)?, GmpEntry {
+					enabled: *enabled,
+					animated: *animated,
+					rotation_a: int("rotation a", *rotation_a)?,
+					rotation_b: int("rotation b", *rotation_b)?,
+					rotation_c: int("rotation c", *rotation_c)?,
+					unknown_a: int("unknown a", *unknown_a)?,
+					unknown_b: int("unknown b", *unknown_b)?,
+				})?;
+			}
+			
+			(Self::Rsp{entry, sub_race, attribute}, MetaFile::Cmp(cmp)) => {
+				let index = Cmp::sub_race_index(sub_race).ok_or_else(|| invalid("sub race", sub_race))?;
+				let scaling = cmp.racial_scaling.get_mut(index).ok_or_else(|| invalid("sub race", sub_race))?;
+				*scaling.attribute_mut(attribute).ok_or_else(|| invalid("attribute", attribute))? = *entry;
+			}
+			
+			// path() decides the file type, this only happens if the file was read as the wrong type
+			_ => return Err(ManipulationError::MismatchedFile(self.path()?)),
+		}
+		
+		Ok(())
+	}
+}
+
+/// Applies the manipulations on top of the game files, later manipulations overwrite earlier ones.
+/// Returns the changed files by their game path
+pub fn apply_manipulations<'a>(manipulations: impl IntoIterator<Item = &'a Manipulation>, file_reader: impl Fn(&str) -> Option<Vec<u8>>) -> Result<HashMap<String, Vec<u8>>, ManipulationError> {
+	let mut files = HashMap::new();
+	for manipulation in manipulations {
+		let path = manipulation.path()?;
+		let file = match files.entry(path) {
+			std::collections::hash_map::Entry::Occupied(v) => v.into_mut(),
+			std::collections::hash_map::Entry::Vacant(v) => {
+				let data = file_reader(v.key()).ok_or_else(|| ManipulationError::NoFileResolverReturn(v.key().clone()))?;
+				let file = MetaFile::read(v.key(), data)?;
+				v.insert(file)
+			}
+		};
+		
+		manipulation.apply(file)?;
+	}
+	
+	files
+		.into_iter()
+		.map(|(path, file)| Ok((path, file.write()?)))
+		.collect()
+}
+
+fn race_code(race: &str, gender: &str) -> Result<u16, ManipulationError> {
+	Eqdp::race_code(race, gender).ok_or_else(|| invalid("race", &format!("{race} {gender}")))
+}
+
+fn invalid(field: &'static str, value: &str) -> ManipulationError {
+	ManipulationError::InvalidValue{field, value: value.to_string()}
+}
+
+fn int<T: TryFrom<i128>>(field: &'static str, value: impl Into<i128>) -> Result<T, ManipulationError> {
+	let value = value.into();
+	T::try_from(value).map_err(|_| ManipulationError::InvalidValue{field, value: value.to_string()})
+}
+
+// values packed into a bitfield, anything that doesn't fit is rejected instead of cut off
+fn bits(field: &'static str, value: impl Into<i128>, count: u32) -> Result<u16, ManipulationError> {
+	let value = value.into();
+	if !(0..1 << count).contains(&value) {
+		return Err(ManipulationError::InvalidValue{field, value: value.to_string()});
+	}
+	
+	Ok(value as u16)
+}
\ No newline at end of file
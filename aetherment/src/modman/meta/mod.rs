@@ -3,6 +3,8 @@ use serde::{de::Visitor, Deserialize, Serialize};
 use crate::EnumTools;
 
 pub mod dalamud;
+mod manipulation;
+pub use manipulation::*;
 
 // TODO: add option_sync or smth so that a submod can sync its options with a master mod (having rounded corners mod for mui needed to be adjusted seperatly is dumb)
 #[derive(Debug, Clone, Deserialize, Serialize)]
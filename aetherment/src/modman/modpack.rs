@@ -72,6 +72,8 @@ pub fn game_files_hashes(files: HashSet<&str>) -> HashMap<String, String> {
 pub struct ModCreationSettings {
 	/// Used to be able to check changes the game has made to files this mod overrides, useful for ui
 	pub current_game_files_hash: bool,
+	/// Re-encode all textures in the pack, None stores them as they are
+	pub texture_encoding: Option<noumenon::format::game::tex::EncodeOptions>,
 }
 
 pub struct ModPack<W: Write + Seek> {
@@ -112,6 +114,26 @@ impl<W: Write + Seek> ModPack<W> {
 	}
 	
 	pub fn add_file(&mut self, path: &str, data: &[u8]) -> Result<(), crate::resource_loader::BacktraceError> {
+		if let Some(options) = self.settings.texture_encoding {
+			let ext = path.split(".").last().unwrap_or("");
+			if noumenon::format::game::tex::EXT.contains(&ext) {
+				return self.add_texture(path, data, &options);
+			}
+		}
+		
+		self.write_file(path, data)
+	}
+	
+	/// Adds a texture re-encoded with the options, ignoring the encoding of the settings
+	pub fn add_texture(&mut self, path: &str, data: &[u8], options: &noumenon::format::game::tex::EncodeOptions) -> Result<(), crate::resource_loader::BacktraceError> {
+		let tex = <noumenon::format::game::Tex as noumenon::format::external::Bytes>::read(&mut std::io::Cursor::new(data))?;
+		let mut buf = std::io::Cursor::new(Vec::new());
+		tex.write_tex(&mut buf, options)?;
+		
+		self.write_file(path, &buf.into_inner())
+	}
+	
+	fn write_file(&mut self, path: &str, data: &[u8]) -> Result<(), crate::resource_loader::BacktraceError> {
 		let hash = blake3::hash(data);
 		let hash_str = crate::hash_str(hash);
 		let filename = path.split("/").last().unwrap();
@@ -46,10 +46,10 @@ impl ImporterDialog {
 				let path = self.dialog.path().ok_or(Error::NoPath)?;
 				let file = std::fs::File::open(&path)?;
 				let ext = path.extension().map(|v| v.to_string_lossy().to_string()).ok_or(Error::MissingExtension)?;
-				let converter = noumenon::Convert::from_ext(&ext, &mut std::io::BufReader::new(file))?;
+				let converter = noumenon::Convert::from_ext_with_base(&ext, &mut std::io::BufReader::new(file), path.parent())?;
 				
 				let mut buf = Vec::new();
-				converter.convert(&self.target_ext, &mut std::io::Cursor::new(&mut buf), None, None::<fn(&str) -> Option<Vec<u8>>>)?;
+				converter.convert(&self.target_ext, &mut std::io::Cursor::new(&mut buf), None, None::<fn(&str) -> Option<Vec<u8>>>, &Default::default())?;
 				
 				Ok(DialogResult::Success(buf))
 			}
@@ -380,7 +380,7 @@ impl super::View for Explorer {
 				let mut writer = std::io::Cursor::new(Vec::new());
 				match resource.resource.export() {
 					resource::Export::Converter(converter) =>
-						if let Err(_) = converter.convert(&ext, &mut writer, None, None::<fn(&str) -> Option<Vec<u8>>>) {break 'o},
+						if let Err(_) = converter.convert(&ext, &mut writer, None, None::<fn(&str) -> Option<Vec<u8>>>, &Default::default()) {break 'o},
 					
 					resource::Export::Bytes(bytes) =>
 						if let Err(_) = writer.write_all(&bytes) {break 'o},
@@ -471,7 +471,7 @@ impl super::View for Explorer {
 										crate::noumenon_instance().unwrap().file::<Vec<u8>>(path).ok()
 									}
 									
-									if let Err(e) = converter.convert(&ext, &mut writer, Some(game_path), Some(file_reader)) {
+									if let Err(e) = converter.convert(&ext, &mut writer, Some(game_path), Some(file_reader), &Default::default()) {
 										break 'x format!("Failed converting file to requested output format\n{e:#?}");
 									};
 								}
@@ -58,15 +58,11 @@ impl super::ResourceView for MdlView {
 						// merge all shape indices so that we can correctly calculate tangents once
 						let mut all_indices = submesh.indices.clone();
 						for shape in &submesh.shapes {
-							let mut indices = submesh.indices.clone();
-							for v in &shape.values {
-								indices[v.index as usize] = v.new_vertex;
-							}
-							all_indices.extend_from_slice(&indices);
+							all_indices.extend(submesh.shape_indices(|v| v == shape.name));
 						}
 						renderer::calculate_tangents(&mut vertices, &all_indices);
 						
-						let id = scene.add_object(Box::new(renderer::Mesh::new(renderer, &vertices, &create_indices(submesh, &self.shapes))));
+						let id = scene.add_object(Box::new(renderer::Mesh::new(renderer, &vertices, &submesh.shape_indices(|v| shape_enabled(&self.shapes, v)))));
 						let obj = scene.get_object_mut(id).unwrap();
 						
 						if let Some((diffuse, normal)) = textures.get(&mesh.material) {
@@ -153,7 +149,7 @@ impl super::ResourceView for MdlView {
 					for ((lod_index, mesh_index, submesh_index), obj_id) in &self.objects {
 						let submesh = &self.mdl.lods[*lod_index].meshes[*mesh_index].submeshes[*submesh_index];
 						let obj = scene.get_object_mut(*obj_id).unwrap().as_any_mut().downcast_mut::<renderer::Mesh>().unwrap();
-						obj.set_indices(renderer, &create_indices(submesh, &self.shapes));
+						obj.set_indices(renderer, &submesh.shape_indices(|v| shape_enabled(&self.shapes, v)));
 					}
 				}
 				
@@ -235,14 +231,6 @@ impl super::ResourceView for MdlView {
 	}
 }
 
-fn create_indices(submesh: &noumenon::format::game::mdl::Submesh, shape_states: &[(String, bool)]) -> Vec<u16> {
-	let mut indices = submesh.indices.to_vec();
-	for shape in &submesh.shapes {
-		if !shape_states.iter().find_map(|v| if v.0 == shape.name {Some(v.1)} else {None}).unwrap_or(false) {continue}
-		for val in &shape.values {
-			indices[val.index as usize] = val.new_vertex;
-		}
-	}
-	
-	indices
+fn shape_enabled(shape_states: &[(String, bool)], name: &str) -> bool {
+	shape_states.iter().any(|(shape, state)| shape == name && *state)
 }
\ No newline at end of file
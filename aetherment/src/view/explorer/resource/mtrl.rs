@@ -1,4 +1,4 @@
-use noumenon::format::{external::Bytes, game::{mtrl::{self, AddressMode}, Mtrl}};
+use noumenon::format::{external::Bytes, game::{mtrl::{self, AddressMode}, Mtrl, Shpk}};
 use crate::{ui_ext::UiExt, view::explorer::Action, EnumTools};
 
 impl EnumTools for AddressMode {
@@ -25,15 +25,18 @@ impl EnumTools for AddressMode {
 
 pub struct MtrlView {
 	mtrl: Mtrl,
+	shpk: Option<Shpk>,
 	view_row: usize,
 }
 
 impl MtrlView {
 	pub fn new(path: &super::Path) -> Result<Self, crate::resource_loader::BacktraceError> {
 		let data = super::read_file(path)?;
+		let mtrl = Mtrl::read(&mut std::io::Cursor::new(&data))?;
 		
 		Ok(Self {
-			mtrl: Mtrl::read(&mut std::io::Cursor::new(&data))?,
+			shpk: load_shpk(&mtrl.shader),
+			mtrl,
 			view_row: 0,
 		})
 	}
@@ -51,12 +54,22 @@ impl super::ResourceView for MtrlView {
 		egui::ScrollArea::both().auto_shrink(false).show(ui, |ui| {
 			ui.label("Shader");
 			ui.combo(self.mtrl.shader.to_string(), "", |ui| {
+				let mut shader_changed = false;
 				for v in mtrl::USED_SHADERS {
-					changed |= ui.selectable_value(&mut self.mtrl.shader, v.to_string(), v).changed();
+					shader_changed |= ui.selectable_value(&mut self.mtrl.shader, v.to_string(), v).changed();
+				}
+				shader_changed |= ui.text_edit_singleline(&mut self.mtrl.shader).changed();
+				
+				if shader_changed {
+					self.shpk = load_shpk(&self.mtrl.shader);
+					changed = true;
 				}
-				changed |= ui.text_edit_singleline(&mut self.mtrl.shader).changed();
 			});
 			
+			if self.shpk.is_none() {
+				ui.label(egui::RichText::new("Failed to load the shader, parameters are guessed").color(egui::Color32::RED));
+			}
+			
 			ui.spacer();
 			ui.label("Uv Sets");
 			for name in &mut self.mtrl.uvsets {
@@ -66,16 +79,20 @@ impl super::ResourceView for MtrlView {
 			for colorset in &mut self.mtrl.colorsets {
 				ui.spacer();
 				ui.collapsing(format!("Colorset {}", colorset.name), |ui| {
+					// legacy colorsets only have 16 rows
+					let columns = if colorset.legacy {8} else {16};
+					self.view_row = self.view_row.min(columns * 2 - 1);
+					
 					{
 						let size = ui.spacing().interact_size.y;
-						let (rect, resp) = ui.allocate_exact_size(egui::vec2(size * 16.0 + 17.0, size * 2.0 + 3.0), egui::Sense::click());
+						let (rect, resp) = ui.allocate_exact_size(egui::vec2(size * columns as f32 + columns as f32 + 1.0, size * 2.0 + 3.0), egui::Sense::click());
 						let painter = ui.painter_at(rect);
 						let style = ui.style().interact(&resp);
 						
 						painter.rect_filled(rect, style.corner_radius, style.bg_fill); // border
 						
 						for y in 0..2 {
-							for x in 0..16 {
+							for x in 0..columns {
 								let row_id = x * 2 + y;
 								let offset = if self.view_row == row_id {size * 0.2} else {0.0};
 								let clr = colorset.regular[row_id].diffuse;
@@ -87,8 +104,8 @@ impl super::ResourceView for MtrlView {
 									),
 									if x == 0 && y == 0 {egui::CornerRadius{nw: style.corner_radius.nw, ..Default::default()}}
 										else if x == 0 && y == 1 {egui::CornerRadius{sw: style.corner_radius.sw, ..Default::default()}}
-										else if x == 15 && y == 0 {egui::CornerRadius{ne: style.corner_radius.ne, ..Default::default()}}
-										else if x == 15 && y == 1 {egui::CornerRadius{se: style.corner_radius.se, ..Default::default()}}
+										else if x == columns - 1 && y == 0 {egui::CornerRadius{ne: style.corner_radius.ne, ..Default::default()}}
+										else if x == columns - 1 && y == 1 {egui::CornerRadius{se: style.corner_radius.se, ..Default::default()}}
 										else {egui::CornerRadius::ZERO},
 									egui::Color32::from_rgb((clr.x * 255.0) as u8, (clr.y * 255.0) as u8, (clr.z * 255.0) as u8),
 								);
@@ -97,7 +114,7 @@ impl super::ResourceView for MtrlView {
 						
 						if let Some(pos) = resp.interact_pointer_pos() {
 							let pos = pos - rect.min;
-							self.view_row = ((pos.x / (size + 1.0)) as usize).min(15) * 2 + ((pos.y / (size + 1.0)) as usize).min(1);
+							self.view_row = ((pos.x / (size + 1.0)) as usize).min(columns - 1) * 2 + ((pos.y / (size + 1.0)) as usize).min(1);
 						}
 					}
 					
@@ -107,6 +124,41 @@ impl super::ResourceView for MtrlView {
 					egui::Grid::new("row").show(ui, |ui| {
 						let row = &mut colorset.regular[self.view_row];
 						
+						if colorset.legacy {
+							ui.label("Diffuse");
+							changed |= ui.color_edit(&mut row.diffuse).changed();
+							ui.end_row();
+							
+							ui.label("Specular");
+							changed |= ui.color_edit(&mut row.specular).changed();
+							changed |= ui.num_edit(&mut row._diffuse_alpha, "")
+								.on_hover_text("Strength").changed();
+							ui.end_row();
+							
+							ui.label("Gloss");
+							changed |= ui.num_edit(&mut row._specular_alpha, "").changed();
+							ui.end_row();
+							
+							ui.label("Emmisive");
+							changed |= ui.color_edit(&mut row.emmisive).changed();
+							ui.end_row();
+							
+							ui.label("Tilemap");
+							changed |= ui.num_edit(&mut row.tile_index, "")
+								.on_hover_text("Index").changed();
+							changed |= ui.num_edit(&mut row.tile_transform.x_axis.x, "")
+								.on_hover_text("Transformation XX").changed();
+							changed |= ui.num_edit(&mut row.tile_transform.x_axis.y, "")
+								.on_hover_text("Transformation XY").changed();
+							changed |= ui.num_edit(&mut row.tile_transform.y_axis.x, "")
+								.on_hover_text("Transformation YX").changed();
+							changed |= ui.num_edit(&mut row.tile_transform.y_axis.y, "")
+								.on_hover_text("Transformation YY").changed();
+							ui.end_row();
+							
+							return;
+						}
+						
 						ui.label("Diffuse");
 						changed |= ui.color_edit(&mut row.diffuse).changed();
 						changed |= ui.num_edit(&mut row._diffuse_alpha, "").changed();
@@ -195,6 +247,31 @@ impl super::ResourceView for MtrlView {
 							changed |= ui.num_edit(&mut row.template, "").changed();
 							ui.end_row();
 							
+							if colorset.legacy {
+								ui.label("Diffuse");
+								changed |= ui.checkbox(&mut row.diffuse, "").changed();
+								ui.end_row();
+								
+								ui.label("Specular");
+								ui.horizontal(|ui| {
+									changed |= ui.checkbox(&mut row.specular, "")
+										.on_hover_text("Color").changed();
+									changed |= ui.checkbox(&mut row.scalar3, "")
+										.on_hover_text("Strength").changed();
+								});
+								ui.end_row();
+								
+								ui.label("Gloss");
+								changed |= ui.checkbox(&mut row.scalar7, "").changed();
+								ui.end_row();
+								
+								ui.label("Emmisive");
+								changed |= ui.checkbox(&mut row.emmisive, "").changed();
+								ui.end_row();
+								
+								return;
+							}
+							
 							ui.label("Channel");
 							changed |= ui.num_edit(&mut row.channel, "").changed();
 							ui.end_row();
@@ -254,14 +331,26 @@ impl super::ResourceView for MtrlView {
 			ui.spacer();
 			ui.collapsing("Textures", |ui| {
 				let mut delete = None;
+				let shpk = self.shpk.as_ref();
+				let sampler_name = |id: u32| shpk.and_then(|v| v.sampler_name(id)).map_or_else(|| shader_param_name(id), |v| v.to_string());
 				for (i, sampler) in self.mtrl.samplers.iter_mut().enumerate() {
-					ui.combo_id(shader_param_name(sampler.id), i, |ui| {
-						for v in mtrl::USED_SAMPLERS {
-							changed |= ui.selectable_value(&mut sampler.id, v, shader_param_name(v)).changed();
+					ui.combo_id(sampler_name(sampler.id), i, |ui| {
+						match shpk {
+							Some(shpk) => for v in &shpk.samplers {
+								changed |= ui.selectable_value(&mut sampler.id, v.id, &v.name).changed();
+							}
+							
+							None => for v in mtrl::USED_SAMPLERS {
+								changed |= ui.selectable_value(&mut sampler.id, v, shader_param_name(v)).changed();
+							}
 						}
 					});
 					
 					let valid = 'v: {
+						if let Some(shpk) = shpk {
+							break 'v shpk.samplers.iter().any(|v| v.id == sampler.id);
+						}
+						
 						let Some((_, samplers)) = mtrl::USED_SHADER_SAMPLERS.iter().find(|v| v.0 == self.mtrl.shader) else {break 'v false};
 						samplers.contains(&sampler.id)
 					};
@@ -299,20 +388,27 @@ impl super::ResourceView for MtrlView {
 				
 				if ui.button("➕ Add new texture").clicked() {
 					self.mtrl.samplers.push(mtrl::Sampler {
-						id: mtrl::USED_SAMPLERS[0],
+						id: shpk.and_then(|v| v.samplers.first()).map_or(mtrl::USED_SAMPLERS[0], |v| v.id),
 						texture: String::new(),
 						u_address_mode: AddressMode::Wrap,
 						v_address_mode: AddressMode::Wrap,
 						lod_bias: 0.5,
 						min_lod: 0,
+						_flags: 0,
+						_texture_flags: 0,
 					});
 				}
 			});
 			
 			ui.spacer();
 			ui.collapsing("Constants", |ui| {
-				for constant in &mut self.mtrl.constants {
+				let mut delete = None;
+				for (i, constant) in self.mtrl.constants.iter_mut().enumerate() {
 					ui.horizontal(|ui| {
+						if self.shpk.is_some() && ui.button("🗑").on_hover_text("Use the shader default").clicked() {
+							delete = Some(i);
+						}
+						
 						if constant.value.len() % 4 == 0 {
 							changed |= ui.num_multi_edit(constant.value_as::<f32>(), "").changed();
 						} else {
@@ -320,6 +416,34 @@ impl super::ResourceView for MtrlView {
 						}
 						ui.label(shader_param_name(constant.id));
 					});
+					
+					if let Some(shpk) = &self.shpk && shpk.material_param(constant.id).is_none() {
+						ui.label(egui::RichText::new("The shader does not use this constant.").color(egui::Color32::RED));
+					}
+				}
+				
+				if let Some(i) = delete {
+					self.mtrl.constants.remove(i);
+					changed = true;
+				}
+				
+				// parameters the material doesn't set itself, these use the default of the shader
+				let Some(shpk) = &self.shpk else {return};
+				for param in &shpk.material_params {
+					if self.mtrl.constants.iter().any(|v| v.id == param.id) {continue}
+					
+					ui.horizontal(|ui| {
+						if ui.button("➕").on_hover_text("Override the shader default").clicked() {
+							self.mtrl.constants.push(mtrl::Constant {
+								id: param.id,
+								value: param.default.iter().flat_map(|v| v.to_le_bytes()).collect(),
+							});
+							changed = true;
+						}
+						
+						ui.label(format!("{:?}", param.default));
+						ui.label(egui::RichText::new(shader_param_name(param.id)).weak());
+					});
 				}
 			});
 			
@@ -328,7 +452,29 @@ impl super::ResourceView for MtrlView {
 				for (k, v) in &mut self.mtrl.shader_keys {
 					ui.horizontal(|ui| {
 						changed |= ui.num_edit(k, "").changed();
-						changed |= ui.num_edit(v, "").changed();
+						match self.shpk.as_ref().map(|shpk| shpk.material_key_values(*k)).filter(|v| v.len() > 0) {
+							Some(values) => ui.combo_id(v.to_string(), *k, |ui| {
+								for value in values {
+									changed |= ui.selectable_value(v, value, value.to_string()).changed();
+								}
+							}),
+							None => changed |= ui.num_edit(v, "").changed(),
+						}
+					});
+				}
+				
+				// keys the material doesn't set itself, these use the default of the shader
+				let Some(shpk) = &self.shpk else {return};
+				for key in &shpk.material_keys {
+					if self.mtrl.shader_keys.iter().any(|v| v.0 == key.id) {continue}
+					
+					ui.horizontal(|ui| {
+						if ui.button("➕").on_hover_text("Override the shader default").clicked() {
+							self.mtrl.shader_keys.push((key.id, key.default));
+							changed = true;
+						}
+						
+						ui.label(egui::RichText::new(format!("{} {}", key.id, key.default)).weak());
 					});
 				}
 			});
@@ -352,4 +498,9 @@ impl super::ResourceView for MtrlView {
 
 fn shader_param_name(id: u32) -> String {
 	mtrl::shader_param_name(id).unwrap_or_else(|| format!("unknown.{}", id))
+}
+
+fn load_shpk(shader: &str) -> Option<Shpk> {
+	let data = super::read_file(&super::Path::Game(format!("shader/sm5/shpk/{shader}"))).ok()?;
+	Shpk::read(&mut std::io::Cursor::new(&data)).ok()
 }
\ No newline at end of file
@@ -0,0 +1,140 @@
+use noumenon::format::{external::Bytes, game::{pap::SampledAnimation, Pap, Sklb}};
+use crate::{ui_ext::{InteractableScene, UiExt}, view::explorer::Action};
+
+pub struct PapView {
+	pap: Pap,
+	sklb: Option<Sklb>,
+	sampled: Vec<Option<SampledAnimation>>,
+	scene: Option<InteractableScene>,
+	bone_objects: Vec<usize>,
+	animation: usize,
+	time: f32,
+	playing: bool,
+}
+
+impl PapView {
+	pub fn new(path: &super::Path) -> Result<Self, crate::resource_loader::BacktraceError> {
+		let data = super::read_file(path)?;
+		let pap = Pap::read(&mut std::io::Cursor::new(&data))?;
+		let sklb = pap.skeleton_path()
+			.and_then(|v| super::read_file(&super::Path::Game(v)).ok())
+			.and_then(|v| Sklb::read(&mut std::io::Cursor::new(&v)).ok());
+		
+		Ok(Self {
+			sampled: pap.sample(),
+			pap,
+			sklb,
+			scene: None,
+			bone_objects: Vec::new(),
+			animation: 0,
+			time: 0.0,
+			playing: true,
+		})
+	}
+	
+	fn update_pose(&mut self) {
+		let (Some(sklb), Some(scene)) = (&self.sklb, &mut self.scene) else {return};
+		let sampled = self.sampled.get(self.animation).and_then(|v| v.as_ref());
+		
+		let mut local = sklb.bones
+			.iter()
+			.map(|v| (v.translation, v.rotation, v.scale))
+			.collect::<Vec<_>>();
+		
+		if let Some(sampled) = sampled {
+			let frame = if sampled.duration > 0.0 {
+				((self.time / sampled.duration) * (sampled.frame_count - 1) as f32).round() as usize
+			} else {0}.min(sampled.frame_count - 1);
+			
+			for track in &sampled.tracks {
+				let Some(v) = local.get_mut(track.bone) else {continue};
+				*v = (track.translations[frame], track.rotations[frame], track.scales[frame]);
+			}
+		}
+		
+		let mut global_matrixes = Vec::<glam::Mat4>::new();
+		for (i, bone) in sklb.bones.iter().enumerate() {
+			let (translation, rotation, scale) = local[i];
+			let mut matrix = glam::Mat4::from_scale_rotation_translation(scale, rotation, translation);
+			if bone.parent >= 0 {
+				matrix = global_matrixes[bone.parent as usize] * matrix;
+			}
+			global_matrixes.push(matrix);
+			
+			let obj = scene.get_object_mut(self.bone_objects[i]).unwrap();
+			*obj.get_matrix_mut() = matrix;
+			obj.set_scale(glam::vec3(0.01, 0.1, 0.01));
+		}
+	}
+}
+
+impl super::ResourceView for PapView {
+	fn title(&self) -> String {
+		"Animation".to_string()
+	}
+	
+	fn ui(&mut self, ui: &mut egui::Ui, renderer: &renderer::Renderer) -> Action {
+		if self.scene.is_none() {
+			let mut scene = InteractableScene::new(renderer);
+			scene.add_object(Box::new(renderer::Skybox::simple(renderer)));
+			
+			if let Some(sklb) = &self.sklb {
+				let (vertices_buf, indices_buf) = super::sklb::bone_buffers(renderer);
+				for _ in &sklb.bones {
+					self.bone_objects.push(scene.add_object(Box::new(renderer::Mesh::new_buffer(renderer, vertices_buf.clone(), indices_buf.clone()))));
+				}
+			}
+			
+			self.scene = Some(scene);
+		}
+		
+		let duration = self.sampled.get(self.animation).and_then(|v| v.as_ref()).map_or(0.0, |v| v.duration);
+		if self.playing && duration > 0.0 {
+			self.time = (self.time + ui.input(|v| v.stable_dt)) % duration;
+			ui.ctx().request_repaint();
+		}
+		self.update_pose();
+		
+		ui.splitter("splitter", crate::ui_ext::SplitterAxis::Horizontal, 0.8, |ui_left, ui_right| {
+			let ui = ui_left;
+			if self.sklb.is_none() {
+				ui.label("Failed to load the skeleton of this animation, it can't be previewed");
+			} else {
+				let size = ui.available_size();
+				self.scene.as_mut().unwrap().render(renderer, size.x as usize, size.y as usize, ui);
+			}
+			
+			egui::ScrollArea::vertical().auto_shrink(false).show(ui_right, |ui| {
+				let name = |i: usize| self.pap.animations.get(i).map_or(String::new(), |v| v.name.clone());
+				ui.combo(name(self.animation), "", |ui| {
+					for i in 0..self.pap.animations.len() {
+						if ui.selectable_label(self.animation == i, name(i)).clicked() {
+							self.animation = i;
+							self.time = 0.0;
+						}
+					}
+				});
+				
+				ui.spacer();
+				
+				ui.checkbox(&mut self.playing, "Play");
+				ui.slider(&mut self.time, 0.0..=duration, "Time");
+				
+				let Some(animation) = self.pap.animations.get(self.animation) else {return};
+				ui.spacer();
+				ui.label(format!("Havok index: {}", animation.havok_index));
+				ui.label(format!("Face: {}", animation.face));
+				if let Some(Some(sampled)) = self.sampled.get(self.animation) {
+					ui.label(format!("Frames: {}", sampled.frame_count));
+					ui.label(format!("Duration: {:.2}s", sampled.duration));
+				}
+			});
+		});
+		
+		Action::None
+	}
+	
+	fn export(&self) -> super::Export {
+		super::Export::Converter(noumenon::Convert::Pap(self.pap.clone()))
+	}
+}
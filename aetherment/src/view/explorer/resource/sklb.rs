@@ -29,46 +29,7 @@ impl super::ResourceView for SklbView {
 			
 			scene.add_object(Box::new(renderer::Skybox::simple(renderer)));
 			
-			let vertices_buf;
-			let indices_buf;
-			{
-				let vertices = &mut [
-					vertex(glam::vec3( 1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ONE),
-					vertex(glam::vec3(-1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::Y),
-					vertex(glam::vec3(-1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ZERO),
-					vertex(glam::vec3( 1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::X),
-					
-					vertex(glam::vec3(-1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ZERO),
-					vertex(glam::vec3( 1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::X),
-					vertex(glam::vec3( 0.0, 1.0,  0.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::Y),
-					
-					vertex(glam::vec3(-1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ZERO),
-					vertex(glam::vec3(-1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::X),
-					vertex(glam::vec3( 0.0, 1.0,  0.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::Y),
-					
-					vertex(glam::vec3( 1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ZERO),
-					vertex(glam::vec3(-1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::X),
-					vertex(glam::vec3( 0.0, 1.0,  0.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::Y),
-					
-					vertex(glam::vec3( 1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ZERO),
-					vertex(glam::vec3( 1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::X),
-					vertex(glam::vec3( 0.0, 1.0,  0.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::Y),
-				];
-				
-				let indices = &[
-					0, 1, 2, 2, 3, 0,
-					4, 5, 6,
-					7, 8, 9,
-					10, 11, 12,
-					13, 14, 15,
-				];
-				
-				renderer::calculate_normals(vertices, indices);
-				renderer::calculate_tangents(vertices, indices);
-				
-				vertices_buf = renderer::mesh::create_vertex_buffer(renderer, vertices);
-				indices_buf = renderer::mesh::create_index_buffer(renderer, indices);
-			}
+			let (vertices_buf, indices_buf) = bone_buffers(renderer);
 			
 			let mut global_matrixes = Vec::<glam::Mat4>::new();
 			for bone in &self.sklb.bones {
@@ -96,4 +57,43 @@ impl super::ResourceView for SklbView {
 	fn export(&self) -> super::Export {
 		super::Export::Invalid
 	}
+}
+
+// a small pyramid pointing along the bone
+pub(super) fn bone_buffers(renderer: &renderer::Renderer) -> (renderer::renderer::Buffer, renderer::renderer::Buffer) {
+	let vertices = &mut [
+		vertex(glam::vec3( 1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ONE),
+		vertex(glam::vec3(-1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::Y),
+		vertex(glam::vec3(-1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ZERO),
+		vertex(glam::vec3( 1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::X),
+		
+		vertex(glam::vec3(-1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ZERO),
+		vertex(glam::vec3( 1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::X),
+		vertex(glam::vec3( 0.0, 1.0,  0.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::Y),
+		
+		vertex(glam::vec3(-1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ZERO),
+		vertex(glam::vec3(-1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::X),
+		vertex(glam::vec3( 0.0, 1.0,  0.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::Y),
+		
+		vertex(glam::vec3( 1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ZERO),
+		vertex(glam::vec3(-1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::X),
+		vertex(glam::vec3( 0.0, 1.0,  0.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::Y),
+		
+		vertex(glam::vec3( 1.0, 0.0,  1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::ZERO),
+		vertex(glam::vec3( 1.0, 0.0, -1.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::X),
+		vertex(glam::vec3( 0.0, 1.0,  0.0), glam::Vec3::ZERO, glam::Vec4::ONE, glam::Vec2::Y),
+	];
+	
+	let indices = &[
+		0, 1, 2, 2, 3, 0,
+		4, 5, 6,
+		7, 8, 9,
+		10, 11, 12,
+		13, 14, 15,
+	];
+	
+	renderer::calculate_normals(vertices, indices);
+	renderer::calculate_tangents(vertices, indices);
+	
+	(renderer::mesh::create_vertex_buffer(renderer, vertices), renderer::mesh::create_index_buffer(renderer, indices))
 }
\ No newline at end of file
@@ -10,6 +10,7 @@ pub struct TexView {
 	changed: bool,
 	
 	format: Format,
+	layer: u32,
 	depth: u32,
 	mip: u32,
 	
@@ -56,6 +57,7 @@ impl TexView {
 			changed: false,
 			
 			format,
+			layer: 0,
 			depth: 0,
 			mip: 0,
 			
@@ -102,7 +104,7 @@ impl TexView {
 	fn load_preview(&mut self, ctx: &egui::Context) {
 		match &self.tex {
 			TexType::Tex(tex) => {
-				let slice = tex.slice(self.depth, self.mip);
+				let slice = tex.slice(self.layer, self.depth, self.mip);
 				let data = egui::ColorImage {
 					size: [slice.width as usize, slice.height as usize],
 					pixels: slice.pixels.chunks_exact(4).map(|v| egui::Color32::from_rgba_unmultiplied(v[0], v[1], v[2], v[3])).collect(),
@@ -352,6 +354,27 @@ impl super::ResourceView for TexView {
 			let ui = ui_right;
 			self.changed |= ui.combo_enum(&mut self.format, "Format").changed();
 			
+			if let TexType::Tex(tex) = &self.tex {
+				let mut redraw = false;
+				if tex.mip_levels > 1 {
+					redraw |= ui.slider(&mut self.mip, 0..=tex.mip_levels - 1, "Mip").changed();
+				}
+				
+				if tex.layers > 1 {
+					redraw |= ui.slider(&mut self.layer, 0..=tex.layers - 1, if tex.is_cube() {"Face"} else {"Layer"}).changed();
+				}
+				
+				let depth = tex.mip_size(self.mip).2;
+				self.depth = self.depth.min(depth - 1);
+				if depth > 1 {
+					redraw |= ui.slider(&mut self.depth, 0..=depth - 1, "Depth").changed();
+				}
+				
+				if redraw {
+					self.load_preview(ui.ctx());
+				}
+			}
+			
 			act.or(self.draw_layers(ui));
 			
 			if matches!(self.tex, TexType::Tex(_)) && ui.button("Convert to composite texture").clicked() {
@@ -429,7 +452,7 @@ impl Composite {
 			let data = super::read_file(&file_path)?;
 			let tex = Tex::read(&mut std::io::Cursor::new(&data))?;
 			
-			let slice = tex.slice(0, 0);
+			let slice = tex.slice(0, 0, 0);
 			let preview = ctx.load_texture("explorer::resource::tex.layer", egui::ColorImage {
 				size: [slice.width as usize, slice.height as usize],
 				pixels: slice.pixels.chunks_exact(4).map(|v| egui::Color32::from_rgba_unmultiplied(v[0], v[1], v[2], v[3])).collect(),
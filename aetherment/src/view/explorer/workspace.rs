@@ -43,6 +43,7 @@ impl Workspace {
 		std::thread::spawn(move|| {
 			match crate::modman::modpack::create_mod(&root, crate::modman::modpack::ModCreationSettings {
 				current_game_files_hash: true,
+				texture_encoding: None,
 			}) {
 				Ok(path) => *status.write().unwrap() = PackStatus::Success(format!("Created modpack at {path:?}")),
 				Err(err) => *status.write().unwrap() = PackStatus::Failure(format!("Failed creating modpack\n\n{err:?}")),
@@ -527,7 +528,7 @@ fn get_texture<'a>(ctx: &egui::Context, path: meta::ValuePathPath, root: &std::p
 				let Ok(file) = std::fs::File::open(path) else {return None};
 				let Ok(tex) = noumenon::format::game::Tex::read(&mut std::io::BufReader::new(file)) else {return None};
 				
-				let slice = tex.slice(0, 0);
+				let slice = tex.slice(0, 0, 0);
 				let img = ctx.load_texture("explorer::workshop.optionpath", egui::ColorImage {
 					size: [slice.width as usize, slice.height as usize],
 					pixels: slice.pixels.chunks_exact(4).map(|v| egui::Color32::from_rgba_unmultiplied(v[0], v[1], v[2], v[3])).collect(),
@@ -14,7 +14,7 @@ impl TFile {
 		use image::EncodableLayout;
 		
 		let (w, h) = (tex.width, tex.height);
-		let img: image::ImageBuffer<image::Rgba<u8>, _> = image::ImageBuffer::from_vec(w as u32, h as u32, tex.slice(0, 0).pixels.to_vec()).unwrap();
+		let img: image::ImageBuffer<image::Rgba<u8>, _> = image::ImageBuffer::from_vec(w as u32, h as u32, tex.slice(0, 0, 0).pixels.to_vec()).unwrap();
 		let scale = (512.0 / w as f32).min(512.0 / h as f32);
 		let (w2, h2) = ((w as f32 * scale) as u32, (h as f32 * scale) as u32);
 		let img = image::imageops::resize(&img, w2, h2, image::imageops::FilterType::Nearest);
@@ -294,7 +294,7 @@ mods ontop of one another (multiple tattoos for example).");
 					};
 					
 					let mut buf = Vec::new();
-					if let Err(err) = converter.convert("tex", &mut std::io::Cursor::new(&mut buf), None, None::<fn(&str) -> Option<Vec<u8>>>) {
+					if let Err(err) = converter.convert("tex", &mut std::io::Cursor::new(&mut buf), None, None::<fn(&str) -> Option<Vec<u8>>>, &Default::default()) {
 						log!(err, "Failed importing file ({err:?})");
 						break 'outer;
 					}
@@ -328,6 +328,7 @@ mods ontop of one another (multiple tattoos for example).");
 		
 		let mut modpack = crate::modman::modpack::ModPack::new(std::io::BufWriter::new(modpack_file), crate::modman::modpack::ModCreationSettings {
 			current_game_files_hash: true,
+			texture_encoding: None,
 		});
 		
 		for layer in &self.layers {
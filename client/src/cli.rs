@@ -1,6 +1,7 @@
 use std::{fs::File, io::{BufReader, BufWriter, Cursor, Read, Write}, path::{Path, PathBuf}};
 
-use clap::{value_parser, Arg, ArgAction, Command};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use aetherment::{noumenon::format::game::tex, EnumTools};
 
 pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 	let matches = Command::new("aetherment")
@@ -44,6 +45,12 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 				.required_if_eq("out", "-")
 				.action(ArgAction::Set)
 				.num_args(1))
+			.arg(Arg::new("gamepath")
+				.long("gamepath")
+				.help("Game path of the file, required by formats that depend on other game files such as sheets (exd/item_0_en.exd) or sounds replacing the sound of a scd")
+				.action(ArgAction::Set)
+				.num_args(1))
+			.args(tex_args())
 			.arg(Arg::new("in")
 				.help("The path of the file to convert")
 				.required(true)
@@ -62,6 +69,48 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 				.required(true)
 				.value_parser(value_parser!(PathBuf))
 				.action(ArgAction::Set)
+				.num_args(1))
+			.args(tex_args()))
+		
+		.subcommand(Command::new("render")
+			.about("Render a uld widget or component to an image, textures are loaded from the game")
+			.arg(Arg::new("path")
+				.help("Path of the uld file, if it does not exist it is loaded from the game")
+				.required(true)
+				.action(ArgAction::Set)
+				.num_args(1))
+			.arg(Arg::new("widget")
+				.long("widget")
+				.help("Id of the widget to render, defaults to the first widget")
+				.value_parser(value_parser!(u32))
+				.conflicts_with("component")
+				.action(ArgAction::Set)
+				.num_args(1))
+			.arg(Arg::new("component")
+				.long("component")
+				.help("Id of the component to render")
+				.value_parser(value_parser!(u32))
+				.action(ArgAction::Set)
+				.num_args(1))
+			.arg(Arg::new("frame")
+				.long("frame")
+				.help("Timeline frame to render")
+				.value_parser(value_parser!(f32))
+				.action(ArgAction::Set)
+				.num_args(1))
+			.arg(Arg::new("hr")
+				.long("hr")
+				.help("Use the high resolution textures")
+				.action(ArgAction::SetTrue))
+			.arg(Arg::new("text")
+				.long("text")
+				.help("Text of a text node as <node id>=<text>, \\n starts a new line. Can be used multiple times")
+				.action(ArgAction::Append)
+				.num_args(1))
+			.arg(Arg::new("out")
+				.help("The path of the output image")
+				.required(true)
+				.action(ArgAction::Set)
 				.num_args(1)))
 		
 		.subcommand(Command::new("diff")
@@ -73,6 +122,24 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 				.action(ArgAction::Set)
 				.num_args(1)))
 		
+		.subcommand(Command::new("compare")
+			.about("Show what changed between two versions of a file")
+			.arg(Arg::new("old")
+				.help("Path of the old version of the file")
+				.required(true)
+				.action(ArgAction::Set)
+				.num_args(1))
+			.arg(Arg::new("new")
+				.help("Path of the new version of the file, defaults to the current game file of gamepath")
+				.required_unless_present("gamepath")
+				.action(ArgAction::Set)
+				.num_args(1))
+			.arg(Arg::new("gamepath")
+				.long("gamepath")
+				.help("Game path of the file, required for sheets (exd/item_0_en.exd)")
+				.action(ArgAction::Set)
+				.num_args(1)))
+		
 		// .subcommand(Command::new("game-directory")
 		// 	.about("Sets the game directory used by commands such as extract")
 		// 	.arg(Arg::new("path")
@@ -122,10 +189,10 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 					
 					if out_file == "-" {
 						let mut data = Vec::new();
-						converter.convert(&out_format, &mut BufWriter::new(Cursor::new(&mut data)), Some(gamepath), Some(file_reader))?;
+						converter.convert(&out_format, &mut BufWriter::new(Cursor::new(&mut data)), Some(gamepath), Some(file_reader), &Default::default())?;
 						std::io::stdout().lock().write_all(&data)?;
 					} else {
-						converter.convert(&out_format, &mut BufWriter::new(File::create(&out_file)?), Some(gamepath), Some(file_reader))?;
+						converter.convert(&out_format, &mut BufWriter::new(File::create(&out_file)?), Some(gamepath), Some(file_reader), &Default::default())?;
 					}
 				}
 				
@@ -152,6 +219,11 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 			};
 			
 			let out_file = sub.get_one::<String>("out").map(|v| v.to_string());
+			let gamepath = sub.get_one::<String>("gamepath").map(|v| v.as_str());
+			let tex_options = tex_options(sub)?.unwrap_or_default();
+			fn file_reader(path: &str) -> Option<Vec<u8>> {
+				aetherment::noumenon_instance()?.file::<Vec<u8>>(path).ok()
+			}
 			
 			if in_is_dir {
 				let out_format = match sub.get_one::<String>("outformat") {
@@ -159,7 +231,7 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 					None => default_target_ext(in_format),
 				};
 				
-				fn do_dir(dir: &Path, cur_path: PathBuf, out_path: Option<&str>, in_format: &str, out_format: &str) {
+				fn do_dir(dir: &Path, cur_path: PathBuf, out_path: Option<&str>, in_format: &str, out_format: &str, tex_options: &tex::EncodeOptions) {
 					let Ok(reader) = std::fs::read_dir(dir) else {return};
 					
 					for entry in reader {
@@ -167,14 +239,14 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 						let path = entry.path();
 						
 						if path.is_dir() {
-							do_dir(&path, cur_path.join(path.file_name().unwrap()), out_path, in_format, out_format);
+							do_dir(&path, cur_path.join(path.file_name().unwrap()), out_path, in_format, out_format, tex_options);
 						} else if path.is_file() && path.extension().map(|v| v.to_str()) == Some(Some(in_format)) {
 							let f = match File::open(&path) {
 								Ok(v) => v,
 								Err(err) => {println!("Failed converting {path:?} ({err:?})"); continue}
 							};
 							
-							let converter = match aetherment::noumenon::Convert::from_ext(&in_format, &mut BufReader::new(f)) {
+							let converter = match aetherment::noumenon::Convert::from_ext_with_base(&in_format, &mut BufReader::new(f), path.parent()) {
 								Ok(v) => v,
 								Err(err) => {println!("Failed converting {path:?} ({err:?})"); continue}
 							};
@@ -193,7 +265,7 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 								Err(err) => {println!("Failed converting {path:?} ({err:?})"); continue}
 							};
 							
-							if let Err(err) = converter.convert(&out_format, &mut BufWriter::new(f), None, None::<fn(&str) -> Option<Vec<u8>>>) {
+							if let Err(err) = converter.convert(&out_format, &mut BufWriter::new(f), None, None::<fn(&str) -> Option<Vec<u8>>>, tex_options) {
 								println!("Failed converting {path:?} ({err:?})");
 								continue;
 							}
@@ -203,7 +275,7 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 					}
 				}
 				
-				do_dir(Path::new(in_file), PathBuf::new(), out_file.as_deref(), &in_format, &out_format);
+				do_dir(Path::new(in_file), PathBuf::new(), out_file.as_deref(), &in_format, &out_format, &tex_options);
 			} else {
 				let out_file = match out_file {
 					Some(v) => v.to_string(),
@@ -223,15 +295,15 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 					std::io::stdin().lock().read_to_end(&mut data)?;
 					aetherment::noumenon::Convert::from_ext(&in_format, &mut BufReader::new(Cursor::new(data)))?
 				} else {
-					aetherment::noumenon::Convert::from_ext(&in_format, &mut BufReader::new(File::open(in_file)?))?
+					aetherment::noumenon::Convert::from_ext_with_base(&in_format, &mut BufReader::new(File::open(in_file)?), Path::new(in_file).parent())?
 				};
 				
 				if out_file == "-" {
 					let mut data = Vec::new();
-					converter.convert(&out_format, &mut BufWriter::new(Cursor::new(&mut data)), None, None::<fn(&str) -> Option<Vec<u8>>>)?;
+					converter.convert(&out_format, &mut BufWriter::new(Cursor::new(&mut data)), gamepath, gamepath.map(|_| file_reader), &tex_options)?;
 					std::io::stdout().lock().write_all(&data)?;
 				} else {
-					converter.convert(&out_format, &mut BufWriter::new(File::create(&out_file)?), None, None::<fn(&str) -> Option<Vec<u8>>>)?;
+					converter.convert(&out_format, &mut BufWriter::new(File::create(&out_file)?), gamepath, gamepath.map(|_| file_reader), &tex_options)?;
 				}
 			}
 		}
@@ -241,12 +313,57 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 			
 			match aetherment::modman::modpack::create_mod(path, aetherment::modman::modpack::ModCreationSettings {
 				current_game_files_hash: true,
+				texture_encoding: tex_options(sub)?,
 			}) {
 				Ok(path) => println!("Created modpack at {path:?}"),
 				Err(err) => println!("Failed creating modpack\n\n{err:?}"),
 			};
 		}
 		
+		Some(("render", sub)) => {
+			use aetherment::noumenon::format::{external::Bytes, game::{uld, Uld}};
+			
+			let path = sub.get_one::<String>("path").ok_or("path is required")?;
+			let out_file = sub.get_one::<String>("out").ok_or("out is required")?;
+			fn file_reader(path: &str) -> Option<Vec<u8>> {
+				aetherment::noumenon_instance()?.file::<Vec<u8>>(path).ok()
+			}
+			
+			let data = match std::fs::read(path) {
+				Ok(v) => v,
+				Err(_) => file_reader(path).ok_or("Uld does not exist")?,
+			};
+			let uld = <Uld as Bytes>::read(&mut Cursor::new(data))?;
+			
+			let mut texts = std::collections::HashMap::new();
+			for text in sub.get_many::<String>("text").into_iter().flatten() {
+				let (id, text) = text.split_once('=').ok_or("Text has to be <node id>=<text>")?;
+				texts.insert(id.parse::<u32>()?, text.replace("\\n", "\n"));
+			}
+			
+			let options = uld::RenderOptions {
+				frame: sub.get_one::<f32>("frame").copied().unwrap_or(0.0),
+				resolution: if sub.get_flag("hr") {uld::Resolution::High} else {uld::Resolution::Standard},
+				texts,
+				..Default::default()
+			};
+			
+			let tex = match sub.get_one::<u32>("component") {
+				Some(id) => uld.render_component(*id, &options, file_reader)?,
+				None => {
+					let id = match sub.get_one::<u32>("widget") {
+						Some(v) => *v,
+						None => uld.widgets.first().ok_or("Uld has no widgets")?.id,
+					};
+					uld.render_widget(id, &options, file_reader)?
+				}
+			};
+			
+			let out_format = out_file.split(".").last().unwrap();
+			aetherment::noumenon::Convert::Tex(tex).convert(out_format, &mut BufWriter::new(File::create(out_file)?), None, None::<fn(&str) -> Option<Vec<u8>>>, &Default::default())?;
+			println!("Rendered to {out_file}");
+		}
+		
 		Some(("diff", sub)) => {
 			let path = sub.get_one::<PathBuf>("path").ok_or("path is required")?;
 			let file = File::open(path)?;
@@ -262,6 +379,30 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 			};
 		}
 		
+		Some(("compare", sub)) => {
+			let old_path = sub.get_one::<String>("old").ok_or("old is required")?;
+			let gamepath = sub.get_one::<String>("gamepath");
+			fn file_reader(path: &str) -> Option<Vec<u8>> {
+				aetherment::noumenon_instance()?.file::<Vec<u8>>(path).ok()
+			}
+			
+			let old = std::fs::read(old_path)?;
+			let new = match sub.get_one::<String>("new") {
+				Some(v) => std::fs::read(v)?,
+				None => file_reader(gamepath.ok_or("gamepath is required")?).ok_or("Game file does not exist")?,
+			};
+			
+			let path = gamepath.unwrap_or(old_path);
+			let changes = aetherment::noumenon::diff::diff_files(path, &old, &new, gamepath.map(|_| file_reader))?;
+			if changes.is_empty() {
+				println!("No changes");
+			} else {
+				for change in changes {
+					println!("{change}");
+				}
+			}
+		}
+		
 		// Some(("game-directory", sub)) => {
 		// 	todo!()
 		// }
@@ -272,6 +413,73 @@ pub fn handle_cli() -> Result<(), Box<dyn std::error::Error>> {
 	Ok(())
 }
 
+fn tex_args() -> [Arg; 5] {
+	[
+		Arg::new("texformat")
+			.long("texformat")
+			.help("Format to encode textures as (a8r8g8b8, bc7, ...), defaults to the format of the texture")
+			.action(ArgAction::Set)
+			.num_args(1),
+		Arg::new("quality")
+			.long("quality")
+			.help("Texture encoding quality, slower is better")
+			.value_parser(["fast", "normal", "slow"])
+			.action(ArgAction::Set)
+			.num_args(1),
+		Arg::new("mips")
+			.long("mips")
+			.help("Mips of encoded textures, 'keep' to keep the existing ones, 'full' to generate down to 1x1, or the amount to generate")
+			.action(ArgAction::Set)
+			.num_args(1),
+		Arg::new("mipfilter")
+			.long("mipfilter")
+			.help("Filter used to generate mips")
+			.value_parser(["nearest", "triangle", "catmullrom", "gaussian", "lanczos3"])
+			.action(ArgAction::Set)
+			.num_args(1),
+		Arg::new("srgb")
+			.long("srgb")
+			.help("Textures contain srgb colors, mips get filtered in linear space and dds files use srgb formats")
+			.action(ArgAction::SetTrue),
+	]
+}
+
+// None if none of the texture args are used
+fn tex_options(sub: &ArgMatches) -> Result<Option<tex::EncodeOptions>, Box<dyn std::error::Error>> {
+	if !["texformat", "quality", "mips", "mipfilter", "srgb"].iter().any(|v| sub.value_source(v) == Some(clap::parser::ValueSource::CommandLine)) {
+		return Ok(None);
+	}
+	
+	let mut options = tex::EncodeOptions::default();
+	if let Some(v) = sub.get_one::<String>("texformat") {
+		options.format = Some(tex::Format::iter().find(|f| f.to_str().eq_ignore_ascii_case(v)).ok_or_else(|| format!("Invalid texture format {v}"))?);
+	}
+	
+	options.quality = match sub.get_one::<String>("quality").map(|v| v.as_str()) {
+		Some("fast") => tex::Quality::Fast,
+		Some("slow") => tex::Quality::Slow,
+		_ => tex::Quality::Normal,
+	};
+	
+	options.mips = match sub.get_one::<String>("mips").map(|v| v.as_str()) {
+		None | Some("keep") => tex::Mips::Keep,
+		Some("full") => tex::Mips::Full,
+		Some(v) => tex::Mips::Count(v.parse().map_err(|_| format!("Invalid mip count {v}"))?),
+	};
+	
+	options.mip_filter = match sub.get_one::<String>("mipfilter").map(|v| v.as_str()) {
+		Some("nearest") => tex::MipFilter::Nearest,
+		Some("catmullrom") => tex::MipFilter::CatmullRom,
+		Some("gaussian") => tex::MipFilter::Gaussian,
+		Some("lanczos3") => tex::MipFilter::Lanczos3,
+		_ => tex::MipFilter::Triangle,
+	};
+	
+	options.srgb = sub.get_flag("srgb");
+	
+	Ok(Some(options))
+}
+
 fn default_target_ext(ext: &str) -> &str {
 	match ext {
 		"tex"  => "png",
@@ -280,9 +488,24 @@ fn default_target_ext(ext: &str) -> &str {
 		"tif"  => "tex",
 		"tiff" => "tex",
 		"tga"  => "tex",
+		"jpg"  => "tex",
+		"jpeg" => "tex",
+		"webp" => "tex",
+		"bmp"  => "tex",
+		"exr"  => "tex",
 		"dds"  => "tex",
 		"mdl"  => "gltf",
 		"gltf" => "mdl",
+		"pap"  => "gltf",
+		"exd"  => "csv",
+		"exh"  => "csv",
+		"csv"  => "exd",
+		"uld"  => "json",
+		"phyb" => "json",
+		"atch" => "json",
+		"scd"  => "ogg",
+		"ogg"  => "scd",
+		"wav"  => "scd",
 		_ => ext,
 	}
 }
\ No newline at end of file
@@ -17,24 +17,9 @@ fn main() {
 		if !path.ends_with(".shpk") {continue}
 		
 		// println!("{path}");
-		let Ok(data) = noumenon.file::<Vec<u8>>(path) else {continue};
-		let mut pos = 0;
-		loop {
-			let Some(start) = data[pos..].into_iter().position(|v| *v == b'g') else {break};
-			if data[pos + start + 1] != b'_' {
-				pos += start + 2;
-				continue;
-			}
-			
-			let Some(end) = data[pos + start + 2..].into_iter().position(|v| *v == 0) else {break};
-			if let Ok(v) = str::from_utf8(&data[pos + start..pos + start + 2 + end]) {
-				let v = v.split(".").next().unwrap().to_string();
-				if v.len() > 6 && v.is_ascii() {
-					params.insert(v);
-				}
-			}
-			
-			pos += start + 2 + end + 1;
+		let Ok(shpk) = noumenon.file::<noumenon::format::game::Shpk>(path) else {continue};
+		for name in shpk.resource_names() {
+			params.insert(name.split(".").next().unwrap().to_string());
 		}
 	}
 	
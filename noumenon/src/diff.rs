@@ -0,0 +1,381 @@
+use std::{collections::{BTreeMap, BTreeSet}, io::Cursor};
+use crate::format::{external::Bytes, game::{exd, excel::{field_to_text, Excel}, mtrl, tex, uld, Exd, Exh, Mtrl, Tex, Uld}};
+
+/// A single difference between two versions of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+	/// Location of the value in the file, such as `widgets[id=1].nodes[node_id=4].x` or `row 12.0, column 3`
+	pub path: String,
+	pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+	Added(String),
+	Removed(String),
+	Modified {
+		old: String,
+		new: String,
+	},
+	/// Changes that don't have a single old and new value, such as changed pixel regions
+	Other(String),
+}
+
+impl Change {
+	fn added(path: impl Into<String>, value: impl ToString) -> Self {
+		Self {path: path.into(), kind: ChangeKind::Added(value.to_string())}
+	}
+	
+	fn removed(path: impl Into<String>, value: impl ToString) -> Self {
+		Self {path: path.into(), kind: ChangeKind::Removed(value.to_string())}
+	}
+	
+	fn modified(path: impl Into<String>, old: impl ToString, new: impl ToString) -> Self {
+		Self {path: path.into(), kind: ChangeKind::Modified {old: old.to_string(), new: new.to_string()}}
+	}
+}
+
+impl std::fmt::Display for Change {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.kind {
+			ChangeKind::Added(v) => write!(f, "+ {}: {v}", self.path),
+			ChangeKind::Removed(v) => write!(f, "- {}: {v}", self.path),
+			ChangeKind::Modified {old, new} => write!(f, "~ {}: {old} -> {new}", self.path),
+			ChangeKind::Other(v) => write!(f, "~ {}: {v}", self.path),
+		}
+	}
+}
+
+/// Structured differences between two versions of a file
+pub trait Diff {
+	fn diff(&self, new: &Self) -> Result<Vec<Change>, crate::Error>;
+}
+
+/// Diffs two versions of a file based on the extension of the path.
+/// Formats without a structured diff only report if the data differs.
+/// Exd pages use the header of the `file_reader` since pages can't be read on their own
+pub fn diff_files(path: &str, old: &[u8], new: &[u8], file_reader: Option<impl Fn(&str) -> Option<Vec<u8>>>) -> Result<Vec<Change>, crate::Error> {
+	let ext = path.split('.').last().unwrap_or("");
+	
+	fn read<T: Bytes>(old: &[u8], new: &[u8]) -> Result<(T, T), crate::Error> {
+		Ok((T::read(&mut Cursor::new(old))?, T::read(&mut Cursor::new(new))?))
+	}
+	
+	if uld::EXT.contains(&ext) {
+		let (old, new) = read::<Uld>(old, new)?;
+		return old.diff(&new);
+	}
+	
+	if mtrl::EXT.contains(&ext) {
+		let (old, new) = read::<Mtrl>(old, new)?;
+		return old.diff(&new);
+	}
+	
+	if tex::EXT.contains(&ext) {
+		let (old, new) = read::<Tex>(old, new)?;
+		return old.diff(&new);
+	}
+	
+	if exd::EXT.contains(&ext) {
+		if let Some(file_reader) = file_reader {
+			let (name, _) = Excel::parse_path(path).ok_or_else(|| crate::Error::InvalidData(format!("{path} is not a sheet path")))?;
+			let header_path = format!("exd/{name}.exh");
+			let header_data = file_reader(&header_path).ok_or_else(|| crate::Error::NotFound(header_path))?;
+			let header = <Exh as Bytes>::read(&mut Cursor::new(header_data))?;
+			let old = Exd::read(&mut Cursor::new(old), &header)?;
+			let new = Exd::read(&mut Cursor::new(new), &header)?;
+			return old.diff(&new);
+		}
+	}
+	
+	if old == new {return Ok(Vec::new())}
+	Ok(vec![Change {
+		path: String::new(),
+		kind: ChangeKind::Other(format!("data differs ({} bytes -> {} bytes)", old.len(), new.len())),
+	}])
+}
+
+// ---------------------------------------- //
+
+// uld goes through serde so the diff stays in sync with the json export
+impl Diff for Uld {
+	fn diff(&self, new: &Self) -> Result<Vec<Change>, crate::Error> {
+		let (old, new) = (serde_json::to_value(self)?, serde_json::to_value(new)?);
+		let mut changes = Vec::new();
+		diff_json("", &old, &new, &mut changes);
+		Ok(changes)
+	}
+}
+
+// arrays of objects with an id are matched up by it, so inserting a node doesn't show every following node as changed.
+// the ids have to be unique, otherwise entries would be lost and the array is diffed by index instead
+fn array_key(values: &[serde_json::Value]) -> Option<&'static str> {
+	["node_id", "id"].into_iter().find(|key| {
+		let ids = values.iter().map(|v| v.get(key).and_then(|v| v.as_u64())).collect::<Option<BTreeSet<_>>>();
+		!values.is_empty() && ids.map_or(false, |ids| ids.len() == values.len())
+	})
+}
+
+fn diff_json(path: &str, old: &serde_json::Value, new: &serde_json::Value, changes: &mut Vec<Change>) {
+	use serde_json::Value;
+	
+	let join = |key: &str| if path.is_empty() {key.to_string()} else {format!("{path}.{key}")};
+	match (old, new) {
+		(Value::Object(old), Value::Object(new)) => {
+			for (key, old_value) in old {
+				match new.get(key) {
+					Some(new_value) => diff_json(&join(key), old_value, new_value, changes),
+					None => changes.push(Change::removed(join(key), old_value)),
+				}
+			}
+			
+			for (key, new_value) in new {
+				if !old.contains_key(key) {
+					changes.push(Change::added(join(key), new_value));
+				}
+			}
+		}
+		
+		(Value::Array(old), Value::Array(new)) => {
+			let key = array_key(old).filter(|key| array_key(new).map_or(new.is_empty(), |v| v == *key));
+			if let Some(key) = key {
+				let id = |v: &Value| v[key].as_u64().unwrap_or(0);
+				let new_ids = new.iter().map(|v| (id(v), v)).collect::<BTreeMap<_, _>>();
+				let old_ids = old.iter().map(|v| (id(v), v)).collect::<BTreeMap<_, _>>();
+				for (id, old_value) in &old_ids {
+					let path = format!("{path}[{key}={id}]");
+					match new_ids.get(id) {
+						Some(new_value) => diff_json(&path, old_value, new_value, changes),
+						None => changes.push(Change::removed(path, old_value)),
+					}
+				}
+				
+				for (id, new_value) in &new_ids {
+					if !old_ids.contains_key(id) {
+						changes.push(Change::added(format!("{path}[{key}={id}]"), new_value));
+					}
+				}
+			} else {
+				for (i, old_value) in old.iter().enumerate() {
+					match new.get(i) {
+						Some(new_value) => diff_json(&format!("{path}[{i}]"), old_value, new_value, changes),
+						None => changes.push(Change::removed(format!("{path}[{i}]"), old_value)),
+					}
+				}
+				
+				for (i, new_value) in new.iter().enumerate().skip(old.len()) {
+					changes.push(Change::added(format!("{path}[{i}]"), new_value));
+				}
+			}
+		}
+		
+		(old, new) => if old != new {
+			changes.push(Change::modified(path, old, new));
+		}
+	}
+}
+
+// ---------------------------------------- //
+
+impl Diff for Exd {
+	fn diff(&self, new: &Self) -> Result<Vec<Change>, crate::Error> {
+		let fields = |v: &[exd::Field]| v.iter().map(field_to_text).collect::<Vec<_>>().join(", ");
+		
+		let mut changes = Vec::new();
+		for old_row in &self.rows {
+			let Some(new_row) = new.row(old_row.id) else {
+				changes.push(Change::removed(format!("row {}", old_row.id), format!("{} subrows", old_row.sub_rows.len())));
+				continue;
+			};
+			
+			for old_sub in &old_row.sub_rows {
+				let path = format!("row {}.{}", old_row.id, old_sub.id);
+				let Some(new_sub) = new_row.sub_rows.iter().find(|v| v.id == old_sub.id) else {
+					changes.push(Change::removed(path, fields(&old_sub.fields)));
+					continue;
+				};
+				
+				for (column, old_field) in old_sub.fields.iter().enumerate() {
+					match new_sub.fields.get(column) {
+						Some(new_field) if new_field != old_field => changes.push(Change::modified(format!("{path}, column {column}"), field_to_text(old_field), field_to_text(new_field))),
+						Some(_) => {}
+						None => changes.push(Change::removed(format!("{path}, column {column}"), field_to_text(old_field))),
+					}
+				}
+				
+				for (column, new_field) in new_sub.fields.iter().enumerate().skip(old_sub.fields.len()) {
+					changes.push(Change::added(format!("{path}, column {column}"), field_to_text(new_field)));
+				}
+			}
+			
+			for new_sub in &new_row.sub_rows {
+				if !old_row.sub_rows.iter().any(|v| v.id == new_sub.id) {
+					changes.push(Change::added(format!("row {}.{}", new_row.id, new_sub.id), fields(&new_sub.fields)));
+				}
+			}
+		}
+		
+		for new_row in &new.rows {
+			if self.row(new_row.id).is_none() {
+				for sub in &new_row.sub_rows {
+					changes.push(Change::added(format!("row {}.{}", new_row.id, sub.id), fields(&sub.fields)));
+				}
+			}
+		}
+		
+		Ok(changes)
+	}
+}
+
+// ---------------------------------------- //
+
+impl Diff for Mtrl {
+	fn diff(&self, new: &Self) -> Result<Vec<Change>, crate::Error> {
+		let mut changes = Vec::new();
+		if self.shader != new.shader {
+			changes.push(Change::modified("shader", &self.shader, &new.shader));
+		}
+		
+		if self.shader_flags != new.shader_flags {
+			changes.push(Change::modified("shader_flags", format!("{:#010X}", self.shader_flags), format!("{:#010X}", new.shader_flags)));
+		}
+		
+		diff_list("uvsets", &self.uvsets, &new.uvsets, |v| v.clone(), |v| v.clone(), &mut changes);
+		diff_list("textures", &self._textures, &new._textures, |v| v.0.clone(), |v| format!("flags: {:#X}", v.1), &mut changes);
+		diff_list("shader_keys", &self.shader_keys, &new.shader_keys, |v| v.0, |v| format!("{:#010X}", v.1), &mut changes);
+		diff_list("constants", &self.constants, &new.constants, |v| v.id, |v| {
+			// constants are floats most of the time
+			if v.value.len() % 4 == 0 {
+				format!("{:?}", v.value.chunks_exact(4).map(|v| f32::from_le_bytes([v[0], v[1], v[2], v[3]])).collect::<Vec<_>>())
+			} else {
+				format!("{:?}", v.value)
+			}
+		}, &mut changes);
+		diff_list("samplers", &self.samplers, &new.samplers, |v| v.id, |v| {
+			format!("{} (u: {:?}, v: {:?}, lod bias: {}, min lod: {}, flags: {:#X}, texture flags: {:#X})", v.texture, v.u_address_mode, v.v_address_mode, v.lod_bias, v.min_lod, v._flags, v._texture_flags)
+		}, &mut changes);
+		
+		for (i, old_set) in self.colorsets.iter().enumerate() {
+			let Some(new_set) = new.colorsets.get(i) else {
+				changes.push(Change::removed(format!("colorsets[{i}]"), &old_set.name));
+				continue;
+			};
+			
+			let path = format!("colorsets[{i}]");
+			if old_set.name != new_set.name {
+				changes.push(Change::modified(format!("{path}.name"), &old_set.name, &new_set.name));
+			}
+			
+			if old_set.legacy != new_set.legacy {
+				changes.push(Change::modified(format!("{path}.legacy"), old_set.legacy, new_set.legacy));
+			}
+			
+			for (row, (old_row, new_row)) in old_set.regular.iter().zip(&new_set.regular).enumerate() {
+				let (old_row, new_row) = (format!("{old_row:?}"), format!("{new_row:?}"));
+				if old_row != new_row {
+					changes.push(Change::modified(format!("{path}.rows[{row}]"), old_row, new_row));
+				}
+			}
+			
+			match (&old_set.dyes, &new_set.dyes) {
+				(Some(old_dyes), Some(new_dyes)) => for (row, (old_row, new_row)) in old_dyes.iter().zip(new_dyes).enumerate() {
+					let (old_row, new_row) = (format!("{old_row:?}"), format!("{new_row:?}"));
+					if old_row != new_row {
+						changes.push(Change::modified(format!("{path}.dyes[{row}]"), old_row, new_row));
+					}
+				}
+				
+				(Some(_), None) => changes.push(Change::removed(format!("{path}.dyes"), "dye rows")),
+				(None, Some(_)) => changes.push(Change::added(format!("{path}.dyes"), "dye rows")),
+				(None, None) => {}
+			}
+		}
+		
+		for (i, new_set) in new.colorsets.iter().enumerate().skip(self.colorsets.len()) {
+			changes.push(Change::added(format!("colorsets[{i}]"), &new_set.name));
+		}
+		
+		Ok(changes)
+	}
+}
+
+// lists where entries are matched up by a key, such as the id of a constant
+fn diff_list<T, K: PartialEq + std::fmt::Display>(path: &str, old: &[T], new: &[T], key: impl Fn(&T) -> K, value: impl Fn(&T) -> String, changes: &mut Vec<Change>) {
+	for old_entry in old {
+		let k = key(old_entry);
+		match new.iter().find(|v| key(v) == k) {
+			Some(new_entry) => {
+				let (old_value, new_value) = (value(old_entry), value(new_entry));
+				if old_value != new_value {
+					changes.push(Change::modified(format!("{path}[{k}]"), old_value, new_value));
+				}
+			}
+			
+			None => changes.push(Change::removed(format!("{path}[{k}]"), value(old_entry))),
+		}
+	}
+	
+	for new_entry in new {
+		let k = key(new_entry);
+		if !old.iter().any(|v| key(v) == k) {
+			changes.push(Change::added(format!("{path}[{k}]"), value(new_entry)));
+		}
+	}
+}
+
+// ---------------------------------------- //
+
+impl Diff for Tex {
+	fn diff(&self, new: &Self) -> Result<Vec<Change>, crate::Error> {
+		let mut changes = Vec::new();
+		if self.format != new.format {
+			changes.push(Change::modified("format", format!("{:?}", self.format), format!("{:?}", new.format)));
+		}
+		
+		let (old_size, new_size) = (format!("{}x{}x{}", self.width, self.height, self.depth), format!("{}x{}x{}", new.width, new.height, new.depth));
+		if old_size != new_size {
+			changes.push(Change::modified("size", old_size, new_size));
+		}
+		
+		if self.layers != new.layers {
+			changes.push(Change::modified("layers", self.layers, new.layers));
+		}
+		
+		if self.mip_levels != new.mip_levels {
+			changes.push(Change::modified("mip_levels", self.mip_levels, new.mip_levels));
+		}
+		
+		if self.flags != new.flags {
+			changes.push(Change::modified("flags", format!("{:#010X}", self.flags), format!("{:#010X}", new.flags)));
+		}
+		
+		// pixels can only be compared if the surfaces line up
+		if self.width != new.width || self.height != new.height || self.depth != new.depth {return Ok(changes)}
+		
+		for layer in 0..self.layers.min(new.layers) {
+			for depth in 0..self.depth {
+				let (old, new) = (self.slice(layer, depth, 0), new.slice(layer, depth, 0));
+				let mut count = 0;
+				let mut region = (u32::MAX, u32::MAX, 0, 0);
+				for (i, (a, b)) in old.pixels.chunks_exact(4).zip(new.pixels.chunks_exact(4)).enumerate() {
+					if a == b {continue}
+					let (x, y) = (i as u32 % old.width, i as u32 / old.width);
+					region = (region.0.min(x), region.1.min(y), region.2.max(x + 1), region.3.max(y + 1));
+					count += 1;
+				}
+				
+				if count > 0 {
+					let mut path = String::from("pixels");
+					if self.layers > 1 {path.push_str(&format!(" layer {layer}"))}
+					if self.depth > 1 {path.push_str(&format!(" depth {depth}"))}
+					changes.push(Change {
+						path,
+						kind: ChangeKind::Other(format!("{count} pixels changed within x {}..{}, y {}..{}", region.0, region.2, region.1, region.3)),
+					});
+				}
+			}
+		}
+		
+		Ok(changes)
+	}
+}
\ No newline at end of file
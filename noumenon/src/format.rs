@@ -9,8 +9,24 @@ pub mod external {
 	pub use tga::Tga;
 	pub mod tiff;
 	pub use tiff::Tiff;
+	pub mod jpeg;
+	pub use jpeg::Jpeg;
+	pub mod webp;
+	pub use webp::Webp;
+	pub mod bmp;
+	pub use bmp::Bmp;
+	pub mod exr;
+	pub use exr::Exr;
 	pub mod gltf;
 	pub use gltf::Gltf;
+	pub mod csv;
+	pub use csv::Csv;
+	pub mod json;
+	pub use json::Json;
+	pub mod ogg;
+	pub use ogg::Ogg;
+	pub mod wav;
+	pub use wav::Wav;
 	// pub mod fbx;
 	// pub use fbx::Fbx;
 }
@@ -30,10 +46,38 @@ pub mod game {
 	pub use exd::Exd;
 	pub mod exh;
 	pub use exh::Exh;
+	pub mod excel;
+	pub use excel::Excel;
 	pub mod mdl;
 	pub use mdl::Mdl;
 	pub mod sklb;
 	pub use sklb::Sklb;
+	pub mod pap;
+	pub use pap::Pap;
+	pub mod shpk;
+	pub use shpk::Shpk;
 	pub mod hwc;
 	pub use hwc::Hwc;
+	pub mod imc;
+	pub use imc::Imc;
+	pub mod eqp;
+	pub use eqp::Eqp;
+	pub mod eqdp;
+	pub use eqdp::Eqdp;
+	pub mod est;
+	pub use est::Est;
+	pub mod gmp;
+	pub use gmp::Gmp;
+	pub mod cmp;
+	pub use cmp::Cmp;
+	pub mod avfx;
+	pub use avfx::Avfx;
+	pub mod scd;
+	pub use scd::Scd;
+	pub mod fdt;
+	pub use fdt::Fdt;
+	pub mod phyb;
+	pub use phyb::Phyb;
+	pub mod atch;
+	pub use atch::Atch;
 }
\ No newline at end of file
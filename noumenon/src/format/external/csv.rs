@@ -0,0 +1,75 @@
+use std::io::{Read, Seek, Write};
+
+pub const EXT: &'static [&'static str] = &["csv"];
+
+pub trait Csv {
+	/// Data csv doesn't store but is required to read it, such as the sheet header
+	type Base;
+	
+	fn read<T>(reader: &mut T, base: Self::Base) -> Result<Self, crate::Error> where Self: Sized, T: Read + Seek;
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where T: Write + Seek;
+}
+
+// ----------
+
+pub(crate) fn write_record<T>(writer: &mut T, record: &[String]) -> Result<(), crate::Error> where
+T: Write {
+	let line = record
+		.iter()
+		.map(|v| if v.contains([',', '"', '\n', '\r']) || v.starts_with(' ') || v.ends_with(' ') {
+			format!("\"{}\"", v.replace('"', "\"\""))
+		} else {
+			v.to_owned()
+		})
+		.collect::<Vec<_>>()
+		.join(",");
+	
+	writer.write_all(line.as_bytes())?;
+	writer.write_all(b"\n")?;
+	
+	Ok(())
+}
+
+/// Parses rfc 4180 style csv, quoted values can contain newlines
+pub(crate) fn parse(data: &str) -> Result<Vec<Vec<String>>, crate::Error> {
+	let data = data.strip_prefix('\u{feff}').unwrap_or(data);
+	let mut records = Vec::new();
+	let mut record = Vec::new();
+	let mut value = String::new();
+	let mut quoted = false;
+	let mut chars = data.chars().peekable();
+	
+	while let Some(c) = chars.next() {
+		if quoted {
+			match c {
+				'"' if chars.peek() == Some(&'"') => {chars.next(); value.push('"')}
+				'"' => quoted = false,
+				c => value.push(c),
+			}
+			
+			continue;
+		}
+		
+		match c {
+			'"' if value.is_empty() => quoted = true,
+			',' => record.push(std::mem::take(&mut value)),
+			'\r' if chars.peek() == Some(&'\n') => {}
+			'\n' | '\r' => {
+				record.push(std::mem::take(&mut value));
+				records.push(std::mem::take(&mut record));
+			}
+			c => value.push(c),
+		}
+	}
+	
+	if quoted {
+		return Err(crate::Error::InvalidData("Csv has an unterminated quoted value".to_string()));
+	}
+	
+	if !value.is_empty() || !record.is_empty() {
+		record.push(value);
+		records.push(record);
+	}
+	
+	Ok(records)
+}
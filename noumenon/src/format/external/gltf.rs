@@ -24,7 +24,34 @@ pub struct Bone {
 	pub scale: glam::Vec3,
 }
 
+impl Bone {
+	/// Reads the joints of the first skin, in the order the skin lists them
+	pub fn read_skin<T>(reader: &mut T) -> Result<Vec<Bone>, crate::Error> where
+	T: Read + Seek {
+		let gltf = gltf::Gltf::from_reader(reader)?;
+		let Some(skin) = gltf.document.skins().next() else {return Ok(Vec::new())};
+		let joints = skin.joints().collect::<Vec<_>>();
+		
+		Ok(joints
+			.iter()
+			.map(|joint| {
+				let (translation, rotation, scale) = joint.transform().decomposed();
+				Bone {
+					name: joint.name().unwrap_or("").to_string(),
+					parent: joints
+						.iter()
+						.find(|v| v.children().any(|c| c.index() == joint.index()))
+						.map(|v| v.name().unwrap_or("").to_string()),
+					translation: glam::Vec3::from_array(translation),
+					rotation: glam::Quat::from_array(rotation),
+					scale: glam::Vec3::from_array(scale),
+				}
+			}).collect())
+	}
+}
+
 pub trait Gltf {
-	fn read<T>(reader: &mut T) -> Result<Self, crate::Error> where Self: Sized, T: Read + Seek;
+	/// `base_path` is the directory the gltf is in, buffers stored in seperate files are loaded relative to it
+	fn read<T>(reader: &mut T, base_path: Option<&std::path::Path>) -> Result<Self, crate::Error> where Self: Sized, T: Read + Seek;
 	fn write<T>(&self, writer: &mut T, materials: HashMap<String, MaterialBake>, bones: Vec<Bone>) -> Result<(), crate::Error> where T: Write + Seek;
 }
\ No newline at end of file
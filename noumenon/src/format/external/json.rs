@@ -0,0 +1,11 @@
+use std::io::{Read, Seek, Write};
+
+pub const EXT: &'static [&'static str] = &["json"];
+
+pub trait Json {
+	/// Data the json doesn't store but is required to read it, such as the sheet header
+	type Base;
+	
+	fn read<T>(reader: &mut T, base: Self::Base) -> Result<Self, crate::Error> where Self: Sized, T: Read + Seek;
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where T: Write + Seek;
+}
@@ -0,0 +1,183 @@
+use std::{collections::HashMap, io::{Read, Seek, Write}};
+use binrw::{BinRead, BinWrite};
+use serde::{Deserialize, Serialize};
+use crate::{NullReader, NullWriter};
+
+pub const EXT: &'static [&'static str] = &["atch"];
+
+pub type Error = binrw::Error;
+
+/// Attach offsets, where weapons and such are attached to the skeleton for each state (eg. sheathed and drawn)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Atch {
+	/// Every point has to have the same amount of states
+	pub points: Vec<AtchPoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtchPoint {
+	/// Up to 4 characters
+	pub name: String,
+	pub accessory: bool,
+	pub states: Vec<AtchState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AtchState {
+	pub bone: String,
+	pub scale: f32,
+	pub offset: [f32; 3],
+	pub rotation: [f32; 3],
+}
+
+impl Atch {
+	// the accessory flags are a bitfield of 8 u32s
+	const MAX_POINTS: usize = 256;
+	const STATE_SIZE: u32 = 32;
+	
+	pub fn point(&self, name: &str) -> Option<&AtchPoint> {
+		self.points.iter().find(|v| v.name == name)
+	}
+	
+	pub fn point_mut(&mut self, name: &str) -> Option<&mut AtchPoint> {
+		self.points.iter_mut().find(|v| v.name == name)
+	}
+}
+
+impl BinRead for Atch {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
+		
+		let point_count = r!(u16);
+		let state_count = r!(u16);
+		let accessory = r!([u32; 8]);
+		let names = r!(Vec<[u8; 4]>, point_count);
+		
+		// bones are offsets to null terminated strings after the states
+		let mut raw_states = Vec::with_capacity(point_count as usize);
+		for _ in 0..point_count as usize * state_count as usize {
+			raw_states.push((r!(u32), r!(f32), r!([f32; 3]), r!([f32; 3])));
+		}
+		
+		let strings_offset = 4 + 32 + point_count as u32 * 4 + point_count as u32 * state_count as u32 * Atch::STATE_SIZE;
+		let strings = r!(eof);
+		let string = |offset: u32| strings
+			.get(offset.saturating_sub(strings_offset) as usize..)
+			.and_then(|v| v.null_terminated().ok())
+			.unwrap_or_default();
+		
+		let mut raw_states = raw_states.into_iter();
+		let points = names
+			.into_iter()
+			.enumerate()
+			.map(|(i, name)| AtchPoint {
+				name: name.null_terminated().unwrap_or_default(),
+				accessory: (accessory[i >> 5] >> (i & 0x1F)) & 1 == 1,
+				states: raw_states
+					.by_ref()
+					.take(state_count as usize)
+					.map(|(bone, scale, offset, rotation)| AtchState {
+						bone: string(bone),
+						scale,
+						offset,
+						rotation,
+					}).collect(),
+			}).collect();
+		
+		Ok(Self {
+			points,
+		})
+	}
+}
+
+impl BinWrite for Atch {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		let state_count = self.points.first().map_or(0, |v| v.states.len());
+		if self.points.len() > Atch::MAX_POINTS {
+			return Err(binrw::Error::AssertFail{pos: 0, message: format!("Atch has {} points while the max is {}", self.points.len(), Atch::MAX_POINTS)});
+		}
+		
+		if let Some(point) = self.points.iter().find(|v| v.states.len() != state_count) {
+			return Err(binrw::Error::AssertFail{pos: 0, message: format!("Point {} has {} states while the others have {state_count}", point.name, point.states.len())});
+		}
+		
+		let mut accessory = [0u32; 8];
+		for (i, point) in self.points.iter().enumerate() {
+			accessory[i >> 5] |= (point.accessory as u32) << (i & 0x1F);
+		}
+		
+		(self.points.len() as u16).write_options(writer, endian, ())?;
+		(state_count as u16).write_options(writer, endian, ())?;
+		accessory.write_options(writer, endian, ())?;
+		for point in &self.points {
+			point.name.null_terminated(4).map_err(|err| binrw::Error::Custom{pos: 0, err: Box::new(err)})?.write_options(writer, endian, ())?;
+		}
+		
+		// bones are shared between states, each is only stored once
+		let mut strings = Vec::new();
+		let mut string_offsets = HashMap::new();
+		let strings_offset = 4 + 32 + self.points.len() as u32 * 4 + (self.points.len() * state_count) as u32 * Atch::STATE_SIZE;
+		for state in self.points.iter().flat_map(|v| &v.states) {
+			let offset = *string_offsets.entry(&state.bone).or_insert_with(|| {
+				let offset = strings_offset + strings.len() as u32;
+				strings.extend_from_slice(state.bone.as_bytes());
+				strings.push(0);
+				offset
+			});
+			
+			offset.write_options(writer, endian, ())?;
+			state.scale.write_options(writer, endian, ())?;
+			state.offset.write_options(writer, endian, ())?;
+			state.rotation.write_options(writer, endian, ())?;
+		}
+		
+		strings.write_options(writer, endian, ())?;
+		
+		Ok(())
+	}
+}
+
+impl crate::File for Atch {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Atch::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Atch {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Atch {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Atch::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Json for Atch {
+	type Base = ();
+	
+	fn read<T>(reader: &mut T, _base: Self::Base) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(serde_json::from_reader(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		serde_json::to_writer_pretty(writer, self)?;
+		
+		Ok(())
+	}
+}
\ No newline at end of file
@@ -0,0 +1,233 @@
+use std::io::{Cursor, Read, Seek, Write};
+use binrw::{BinRead, BinWrite};
+
+pub const EXT: &'static [&'static str] = &["avfx"];
+
+pub type Error = binrw::Error;
+
+/// A vfx, a tree of named blocks. Only the root level is split up, use [Block::children] to go deeper
+#[derive(Debug, Clone)]
+pub struct Avfx {
+	pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+	/// Up to 4 characters, eg. Emit, Ptcl, Tex
+	pub name: String,
+	pub data: Vec<u8>,
+}
+
+impl Block {
+	pub fn new(name: &str, data: Vec<u8>) -> Self {
+		Self {
+			name: name.to_string(),
+			data,
+		}
+	}
+	
+	pub fn from_children(name: &str, children: &[Block]) -> Result<Self, Error> {
+		let mut data = Cursor::new(Vec::new());
+		for child in children {
+			child.write_le(&mut data)?;
+		}
+		
+		Ok(Self::new(name, data.into_inner()))
+	}
+	
+	/// Parses the data as child blocks, this fails for blocks that hold a value instead
+	pub fn children(&self) -> Result<Vec<Block>, Error> {
+		let mut reader = Cursor::new(&self.data);
+		let mut children = Vec::new();
+		while (reader.position() as usize) < self.data.len() {
+			children.push(Block::read_le(&mut reader)?);
+		}
+		
+		Ok(children)
+	}
+	
+	/// The first child block with the name
+	pub fn child(&self, name: &str) -> Option<Block> {
+		self.children().ok()?.into_iter().find(|v| v.name == name)
+	}
+	
+	pub fn as_u32(&self) -> Option<u32> {
+		Some(u32::from_le_bytes(self.data.get(0..4)?.try_into().ok()?))
+	}
+	
+	pub fn as_f32(&self) -> Option<f32> {
+		Some(f32::from_le_bytes(self.data.get(0..4)?.try_into().ok()?))
+	}
+	
+	pub fn as_string(&self) -> Option<String> {
+		let end = self.data.iter().position(|v| *v == 0).unwrap_or(self.data.len());
+		String::from_utf8(self.data[..end].to_vec()).ok()
+	}
+}
+
+impl BinRead for Block {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
+		
+		// names are stored reversed and padded with nulls, AVFX is XFVA
+		let name = r!([u8; 4]);
+		let name = name.iter().rev().filter(|v| **v != 0).map(|v| *v as char).collect::<String>();
+		let size = r!(u32);
+		let data = r!(Vec<u8>, size);
+		r!(move (4 - size % 4) % 4);
+		
+		Ok(Self {
+			name,
+			data,
+		})
+	}
+}
+
+impl BinWrite for Block {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		let mut name = [0u8; 4];
+		for (i, c) in self.name.bytes().rev().take(4).enumerate() {
+			name[i] = c;
+		}
+		
+		name.write_options(writer, endian, ())?;
+		(self.data.len() as u32).write_options(writer, endian, ())?;
+		self.data.write_options(writer, endian, ())?;
+		vec![0u8; (4 - self.data.len() % 4) % 4].write_options(writer, endian, ())?;
+		
+		Ok(())
+	}
+}
+
+// ----------
+
+/// Blocks of a particle holding a texture reference, color 1-4, normal, reflection, distortion and palette
+pub const PARTICLE_TEXTURE_SLOTS: &[&str] = &["TC1", "TC2", "TC3", "TC4", "TN", "TR", "TD", "TP"];
+
+/// An Emit block
+#[derive(Debug, Clone, Copy)]
+pub struct Emitter<'a>(pub &'a Block);
+
+impl<'a> Emitter<'a> {
+	/// The raw emitter type (EVT), eg. 0 point, 1 cone, 2 cone model, 3 sphere model, 4 cylinder model, 5 model
+	pub fn emitter_type(&self) -> Option<u32> {
+		self.0.child("EVT")?.as_u32()
+	}
+}
+
+/// A Ptcl block
+#[derive(Debug, Clone, Copy)]
+pub struct Particle<'a>(pub &'a Block);
+
+impl<'a> Particle<'a> {
+	/// The raw particle type (PrVT), eg. 0 parameter, 1 powder, 2 windmill, 3 line, 5 model, 6 polyline, 8 quad, 9 polygon, 10 decal
+	pub fn particle_type(&self) -> Option<u32> {
+		self.0.child("PrVT")?.as_u32()
+	}
+	
+	/// Index into [Avfx::textures] for every texture slot of [PARTICLE_TEXTURE_SLOTS] the particle uses
+	pub fn texture_indices(&self) -> Vec<(&'static str, u32)> {
+		let Ok(children) = self.0.children() else {return Vec::new()};
+		
+		PARTICLE_TEXTURE_SLOTS
+			.iter()
+			.filter_map(|slot| {
+				let index = children.iter().find(|v| v.name == *slot)?.child("TxNo")?.as_u32()?;
+				// -1 is an unused slot
+				(index as i32 >= 0).then_some((*slot, index))
+			})
+			.collect()
+	}
+}
+
+// ----------
+
+impl Avfx {
+	pub fn emitters(&self) -> impl Iterator<Item = Emitter> {
+		self.blocks.iter().filter(|v| v.name == "Emit").map(Emitter)
+	}
+	
+	pub fn particles(&self) -> impl Iterator<Item = Particle> {
+		self.blocks.iter().filter(|v| v.name == "Ptcl").map(Particle)
+	}
+	
+	/// Models are stored inside of the vfx itself, particles reference them by index
+	pub fn models(&self) -> impl Iterator<Item = &Block> {
+		self.blocks.iter().filter(|v| v.name == "Modl")
+	}
+	
+	/// Paths of the textures in the order particles reference them in
+	pub fn textures(&self) -> Vec<String> {
+		self.blocks
+			.iter()
+			.filter(|v| v.name == "Tex")
+			.map(|v| v.as_string().unwrap_or_default())
+			.collect()
+	}
+	
+	/// Replaces texture paths, those the mapping returns None for are kept. Returns how many got replaced
+	pub fn retarget_textures(&mut self, map: impl Fn(&str) -> Option<String>) -> usize {
+		let mut count = 0;
+		for block in self.blocks.iter_mut().filter(|v| v.name == "Tex") {
+			let Some(path) = map(&block.as_string().unwrap_or_default()) else {continue};
+			block.data = path.into_bytes();
+			block.data.push(0);
+			count += 1;
+		}
+		
+		count
+	}
+}
+
+impl BinRead for Avfx {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		let pos = reader.stream_position()?;
+		let root = Block::read_options(reader, endian, ())?;
+		if root.name != "AVFX" {
+			return Err(binrw::Error::BadMagic{pos, found: Box::new(root.name)});
+		}
+		
+		Ok(Self {
+			blocks: root.children()?,
+		})
+	}
+}
+
+impl BinWrite for Avfx {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		Block::from_children("AVFX", &self.blocks)?.write_options(writer, endian, ())
+	}
+}
+
+impl crate::File for Avfx {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Avfx::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Avfx {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Avfx {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Avfx::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
\ No newline at end of file
@@ -0,0 +1,140 @@
+use std::io::{Read, Seek, Write};
+use binrw::{BinRead, BinWrite};
+
+pub const EXT: &'static [&'static str] = &["cmp"];
+
+pub type Error = binrw::Error;
+
+/// Character creation parameters, only the racial scaling at the end of it is exposed
+#[derive(Debug, Clone)]
+pub struct Cmp {
+	/// Indexed by [Cmp::sub_race_index]
+	pub racial_scaling: Vec<RacialScaling>,
+	
+	// everything before the racial scaling, we don't know what most of it is
+	header: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, BinRead, BinWrite)]
+pub struct RacialScaling {
+	pub male_min_size: f32,
+	pub male_max_size: f32,
+	pub male_min_tail: f32,
+	pub male_max_tail: f32,
+	pub female_min_size: f32,
+	pub female_max_size: f32,
+	pub female_min_tail: f32,
+	pub female_max_tail: f32,
+	pub bust_min_x: f32,
+	pub bust_min_y: f32,
+	pub bust_min_z: f32,
+	pub bust_max_x: f32,
+	pub bust_max_y: f32,
+	pub bust_max_z: f32,
+}
+
+impl RacialScaling {
+	const SIZE: u64 = 14 * 4;
+	
+	pub fn attribute_mut(&mut self, attribute: &str) -> Option<&mut f32> {
+		match attribute {
+			"MaleMinSize" => Some(&mut self.male_min_size),
+			"MaleMaxSize" => Some(&mut self.male_max_size),
+			"MaleMinTail" => Some(&mut self.male_min_tail),
+			"MaleMaxTail" => Some(&mut self.male_max_tail),
+			"FemaleMinSize" => Some(&mut self.female_min_size),
+			"FemaleMaxSize" => Some(&mut self.female_max_size),
+			"FemaleMinTail" => Some(&mut self.female_min_tail),
+			"FemaleMaxTail" => Some(&mut self.female_max_tail),
+			"BustMinX" => Some(&mut self.bust_min_x),
+			"BustMinY" => Some(&mut self.bust_min_y),
+			"BustMinZ" => Some(&mut self.bust_min_z),
+			"BustMaxX" => Some(&mut self.bust_max_x),
+			"BustMaxY" => Some(&mut self.bust_max_y),
+			"BustMaxZ" => Some(&mut self.bust_max_z),
+			_ => None,
+		}
+	}
+}
+
+impl Cmp {
+	pub const PATH: &'static str = "chara/xls/charamake/human.cmp";
+	const RACIAL_SCALING_OFFSET: u64 = 0x2A800;
+	
+	/// Index into the racial scaling, race * 10 + clan
+	pub fn sub_race_index(sub_race: &str) -> Option<usize> {
+		match sub_race {
+			"Midlander" => Some(0),
+			"Highlander" => Some(1),
+			"Wildwood" => Some(10),
+			"Duskwight" => Some(11),
+			"Plainsfolk" => Some(20),
+			"Dunesfolk" => Some(21),
+			"SeekerOfTheSun" => Some(30),
+			"KeeperOfTheMoon" => Some(31),
+			"Seawolf" => Some(40),
+			"Hellsguard" => Some(41),
+			"Raen" => Some(50),
+			"Xaela" => Some(51),
+			"Hellion" => Some(60),
+			"Lost" => Some(61),
+			"Rava" => Some(70),
+			"Veena" => Some(71),
+			_ => None,
+		}
+	}
+}
+
+impl BinRead for Cmp {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		let size = reader.seek(std::io::SeekFrom::End(0))?;
+		reader.seek(std::io::SeekFrom::Start(0))?;
+		simple_reader!(reader, endian);
+		
+		let header = r!(Vec<u8>, Cmp::RACIAL_SCALING_OFFSET);
+		let racial_scaling = r!(Vec<RacialScaling>, size.saturating_sub(Cmp::RACIAL_SCALING_OFFSET) / RacialScaling::SIZE);
+		
+		Ok(Self {
+			racial_scaling,
+			header,
+		})
+	}
+}
+
+impl BinWrite for Cmp {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		self.header.write_options(writer, endian, ())?;
+		self.racial_scaling.write_options(writer, endian, ())?;
+		
+		Ok(())
+	}
+}
+
+impl crate::File for Cmp {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Cmp::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Cmp {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Cmp {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Cmp::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
\ No newline at end of file
@@ -0,0 +1,171 @@
+use std::io::{Read, Seek, Write};
+use binrw::{BinRead, BinWrite};
+
+pub const EXT: &'static [&'static str] = &["eqdp"];
+
+pub type Error = binrw::Error;
+
+/// Equipment deformer parameters, which race specific models and materials a set has for each slot.
+/// There is a file per race for equipment and accessories each
+#[derive(Debug, Clone)]
+pub struct Eqdp {
+	identifier: u16,
+	block_size: u16,
+	/// None for blocks that aren't in the file, their sets use 0
+	blocks: Vec<Option<Vec<u16>>>,
+}
+
+impl Eqdp {
+	pub fn path(race_code: u16, accessory: bool) -> String {
+		if accessory {
+			format!("chara/xls/charadb/accessorydeformerparameter/c{race_code:04}.eqdp")
+		} else {
+			format!("chara/xls/charadb/equipmentdeformerparameter/c{race_code:04}.eqdp")
+		}
+	}
+	
+	/// The race code used in paths, eg. 0101 for midlander males
+	pub fn race_code(race: &str, gender: &str) -> Option<u16> {
+		let race = match race {
+			"Midlander" => 0,
+			"Highlander" => 1,
+			"Elezen" => 2,
+			"Miqote" => 3,
+			"Roegadyn" => 4,
+			"Lalafell" => 5,
+			"AuRa" => 6,
+			"Hrothgar" => 7,
+			"Viera" => 8,
+			_ => return None,
+		};
+		
+		let (female, npc) = match gender {
+			"Male" => (0, false),
+			"Female" => (1, false),
+			"MaleNpc" => (0, true),
+			"FemaleNpc" => (1, true),
+			_ => return None,
+		};
+		
+		Some((race * 2 + female + 1) * 100 + if npc {4} else {1})
+	}
+	
+	/// Whether the slot is stored in the accessory file and the bits of an entry belonging to it, 1 bit for the material and 1 for the model
+	pub fn slot_mask(slot: &str) -> Option<(bool, u16)> {
+		match slot {
+			"Head" => Some((false, 0b11)),
+			"Body" => Some((false, 0b11 << 2)),
+			"Hands" => Some((false, 0b11 << 4)),
+			"Legs" => Some((false, 0b11 << 6)),
+			"Feet" => Some((false, 0b11 << 8)),
+			"Ears" => Some((true, 0b11)),
+			"Neck" => Some((true, 0b11 << 2)),
+			"Wrists" => Some((true, 0b11 << 4)),
+			"RFinger" => Some((true, 0b11 << 6)),
+			"LFinger" => Some((true, 0b11 << 8)),
+			_ => None,
+		}
+	}
+	
+	pub fn get(&self, set_id: u16) -> u16 {
+		let block_size = self.block_size.max(1) as usize;
+		let Some(Some(block)) = self.blocks.get(set_id as usize / block_size) else {return 0};
+		block.get(set_id as usize % block_size).copied().unwrap_or(0)
+	}
+	
+	pub fn set(&mut self, set_id: u16, entry: u16) -> Result<(), crate::Error> {
+		let block_size = self.block_size.max(1) as usize;
+		let Some(block) = self.blocks.get_mut(set_id as usize / block_size) else {
+			return Err(crate::Error::InvalidData(format!("Set {set_id} is out of range, the max is {}", self.blocks.len() * block_size - 1)));
+		};
+		
+		block.get_or_insert_with(|| vec![0; block_size])[set_id as usize % block_size] = entry;
+		
+		Ok(())
+	}
+}
+
+impl BinRead for Eqdp {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
+		
+		let identifier = r!(u16);
+		let block_size = r!(u16);
+		let block_count = r!(u16);
+		let offsets = r!(Vec<u16>, block_count);
+		let data_offset = 6 + block_count as u64 * 2;
+		
+		// offsets are in entries from the start of the data
+		let mut blocks = Vec::with_capacity(block_count as usize);
+		for offset in offsets {
+			if offset == u16::MAX {
+				blocks.push(None);
+				continue;
+			}
+			
+			r!(seek data_offset + offset as u64 * 2);
+			blocks.push(Some(r!(Vec<u16>, block_size)));
+		}
+		
+		Ok(Self {
+			identifier,
+			block_size,
+			blocks,
+		})
+	}
+}
+
+impl BinWrite for Eqdp {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		self.identifier.write_options(writer, endian, ())?;
+		self.block_size.write_options(writer, endian, ())?;
+		(self.blocks.len() as u16).write_options(writer, endian, ())?;
+		
+		let mut offset = 0u16;
+		for block in &self.blocks {
+			match block {
+				Some(_) => {
+					offset.write_options(writer, endian, ())?;
+					offset += self.block_size;
+				}
+				
+				None => u16::MAX.write_options(writer, endian, ())?,
+			}
+		}
+		
+		for block in self.blocks.iter().flatten() {
+			block.write_options(writer, endian, ())?;
+		}
+		
+		Ok(())
+	}
+}
+
+impl crate::File for Eqdp {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Eqdp::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Eqdp {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Eqdp {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Eqdp::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
\ No newline at end of file
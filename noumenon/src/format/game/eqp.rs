@@ -0,0 +1,160 @@
+use std::io::{Read, Seek, Write};
+use binrw::{BinRead, BinWrite};
+
+pub const EXT: &'static [&'static str] = &["eqp"];
+
+pub type Error = binrw::Error;
+
+/// Equipment parameters, which parts of the body or other gear a piece of gear hides or shows
+#[derive(Debug, Clone)]
+pub struct Eqp {
+	sets: SetEntries,
+}
+
+impl Eqp {
+	pub const PATH: &'static str = "chara/xls/equipmentparameter/equipmentparameter.eqp";
+	/// What the game uses for sets that aren't in the file
+	pub const DEFAULT_ENTRY: u64 = 0x3FE00070603F00;
+	
+	/// The bits of an entry belonging to a slot
+	pub fn slot_mask(slot: &str) -> Option<u64> {
+		match slot {
+			"Body" => Some(0x0000_0000_0000_FFFF),
+			"Legs" => Some(0x0000_0000_00FF_0000),
+			"Hands" => Some(0x0000_0000_FF00_0000),
+			"Feet" => Some(0x0000_00FF_0000_0000),
+			"Head" => Some(0xFFFF_FF00_0000_0000),
+			_ => None,
+		}
+	}
+	
+	pub fn get(&self, set_id: u16) -> u64 {
+		self.sets.get(set_id, Self::DEFAULT_ENTRY)
+	}
+	
+	pub fn set(&mut self, set_id: u16, entry: u64) -> Result<(), crate::Error> {
+		self.sets.set(set_id, entry, Self::DEFAULT_ENTRY)
+	}
+}
+
+impl BinRead for Eqp {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		Ok(Self {
+			sets: SetEntries::read_options(reader, endian, Self::DEFAULT_ENTRY)?,
+		})
+	}
+}
+
+impl BinWrite for Eqp {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		self.sets.write_options(writer, endian, ())
+	}
+}
+
+impl crate::File for Eqp {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Eqp::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Eqp {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Eqp {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Eqp::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
+
+// ----------
+
+const BLOCK_SIZE: usize = 160;
+const BLOCK_COUNT: usize = 64;
+
+/// A u64 per set id stored in blocks of 160 sets, shared by eqp and gmp.
+/// The entry of set 0 doubles as a mask of which blocks are present, missing blocks use a default entry
+#[derive(Debug, Clone)]
+pub(super) struct SetEntries {
+	entries: Vec<u64>,
+	blocks: u64,
+}
+
+impl SetEntries {
+	pub(super) fn get(&self, set_id: u16, default: u64) -> u64 {
+		// set 0 would be the block mask
+		let set_id = set_id.max(1) as usize;
+		if set_id >= BLOCK_SIZE * BLOCK_COUNT || self.blocks & (1 << (set_id / BLOCK_SIZE)) == 0 {return default}
+		self.entries.get(set_id).copied().unwrap_or(default)
+	}
+	
+	pub(super) fn set(&mut self, set_id: u16, entry: u64, default: u64) -> Result<(), crate::Error> {
+		let set_id = set_id as usize;
+		if set_id == 0 || set_id >= BLOCK_SIZE * BLOCK_COUNT {
+			return Err(crate::Error::InvalidData(format!("Set {set_id} is out of range, the max is {}", BLOCK_SIZE * BLOCK_COUNT - 1)));
+		}
+		
+		let block = set_id / BLOCK_SIZE;
+		if self.blocks & (1 << block) == 0 {
+			self.entries[block * BLOCK_SIZE..(block + 1) * BLOCK_SIZE].fill(default);
+			self.blocks |= 1 << block;
+		}
+		
+		self.entries[set_id] = entry;
+		
+		Ok(())
+	}
+}
+
+impl BinRead for SetEntries {
+	type Args<'a> = u64;
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, default: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
+		
+		let blocks = r!(u64) | 1;
+		r!(move -8);
+		
+		let mut entries = vec![default; BLOCK_SIZE * BLOCK_COUNT];
+		for block in 0..BLOCK_COUNT {
+			if blocks & (1 << block) == 0 {continue}
+			for entry in &mut entries[block * BLOCK_SIZE..(block + 1) * BLOCK_SIZE] {
+				*entry = r!(u64);
+			}
+		}
+		
+		Ok(Self {
+			entries,
+			blocks,
+		})
+	}
+}
+
+impl BinWrite for SetEntries {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		for block in 0..BLOCK_COUNT {
+			if self.blocks & (1 << block) == 0 {continue}
+			for (i, entry) in self.entries[block * BLOCK_SIZE..(block + 1) * BLOCK_SIZE].iter().enumerate() {
+				let entry = if block == 0 && i == 0 {self.blocks} else {*entry};
+				entry.write_options(writer, endian, ())?;
+			}
+		}
+		
+		Ok(())
+	}
+}
\ No newline at end of file
@@ -0,0 +1,112 @@
+use std::io::{Read, Seek, Write};
+use binrw::{BinRead, BinWrite};
+
+pub const EXT: &'static [&'static str] = &["est"];
+
+pub type Error = binrw::Error;
+
+/// Extra skeleton table, which additional skeleton (eg. for hair or a hat) a set uses per race
+#[derive(Debug, Clone)]
+pub struct Est {
+	/// Sorted by race and then set id
+	pub entries: Vec<EstEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EstEntry {
+	pub set_id: u16,
+	/// See [super::Eqdp::race_code]
+	pub race: u16,
+	pub skeleton_id: u16,
+}
+
+impl Est {
+	pub fn path(slot: &str) -> Option<&'static str> {
+		match slot {
+			"Face" => Some("chara/xls/charadb/faceskeletontemplate.est"),
+			"Hair" => Some("chara/xls/charadb/hairskeletontemplate.est"),
+			"Body" => Some("chara/xls/charadb/extra_top.est"),
+			"Head" => Some("chara/xls/charadb/extra_met.est"),
+			_ => None,
+		}
+	}
+	
+	pub fn get(&self, set_id: u16, race: u16) -> Option<u16> {
+		self.entries
+			.binary_search_by_key(&(race, set_id), |v| (v.race, v.set_id))
+			.ok()
+			.map(|i| self.entries[i].skeleton_id)
+	}
+	
+	/// Sets the skeleton of a set, 0 removes the entry
+	pub fn set(&mut self, set_id: u16, race: u16, skeleton_id: u16) {
+		match (self.entries.binary_search_by_key(&(race, set_id), |v| (v.race, v.set_id)), skeleton_id) {
+			(Ok(i), 0) => {self.entries.remove(i);}
+			(Ok(i), _) => self.entries[i].skeleton_id = skeleton_id,
+			(Err(_), 0) => {}
+			(Err(i), _) => self.entries.insert(i, EstEntry {set_id, race, skeleton_id}),
+		}
+	}
+}
+
+impl BinRead for Est {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
+		
+		let count = r!(u32);
+		let ids = r!(Vec<[u16; 2]>, count);
+		let skeletons = r!(Vec<u16>, count);
+		
+		Ok(Self {
+			entries: ids
+				.into_iter()
+				.zip(skeletons)
+				.map(|([set_id, race], skeleton_id)| EstEntry {set_id, race, skeleton_id})
+				.collect(),
+		})
+	}
+}
+
+impl BinWrite for Est {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		(self.entries.len() as u32).write_options(writer, endian, ())?;
+		for entry in &self.entries {
+			[entry.set_id, entry.race].write_options(writer, endian, ())?;
+		}
+		
+		for entry in &self.entries {
+			entry.skeleton_id.write_options(writer, endian, ())?;
+		}
+		
+		Ok(())
+	}
+}
+
+impl crate::File for Est {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Est::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Est {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Est {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Est::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
\ No newline at end of file
@@ -0,0 +1,327 @@
+use std::io::{Cursor, Read, Seek, Write};
+use super::{exd::{Field, Row, SeString, SubRow}, exh::{ColumnKind, Language}, Exd, Exh};
+
+/// A full sheet, the rows of all pages for a single language
+#[derive(Debug, Clone)]
+pub struct Excel {
+	pub name: String,
+	pub language: Language,
+	pub header: Exh,
+	/// Rows of all pages combined
+	pub sheet: Exd,
+}
+
+impl Excel {
+	/// Loads all pages, sheets without localized pages ignore the language
+	pub fn load(name: &str, language: Language, file_reader: impl Fn(&str) -> Option<Vec<u8>>) -> Result<Self, crate::Error> {
+		let header_path = format!("exd/{name}.exh");
+		let header_data = file_reader(&header_path).ok_or_else(|| crate::Error::InvalidData(format!("Failed to load {header_path}")))?;
+		let header = <Exh as crate::format::external::Bytes>::read(&mut Cursor::new(header_data))?;
+		
+		let language = header.resolve_language(language).ok_or_else(|| crate::Error::InvalidData(format!("Sheet {name} does not exist in {language:?}")))?;
+		
+		let mut sheet = Exd::new();
+		for page in &header.pages {
+			let path = Self::page_path(name, page.start_id, language);
+			let data = file_reader(&path).ok_or_else(|| crate::Error::InvalidData(format!("Failed to load {path}")))?;
+			sheet.rows.extend(Exd::read(&mut Cursor::new(data), &header)?.rows);
+		}
+		sheet.rows.sort_by_key(|v| v.id);
+		
+		Ok(Self {
+			name: name.to_string(),
+			language,
+			header,
+			sheet,
+		})
+	}
+	
+	/// Loads the sheet a page or header path (`exd/item_0_en.exd`, `exd/item.exh`) belongs to.
+	/// Header paths use english
+	pub fn load_path(path: &str, file_reader: impl Fn(&str) -> Option<Vec<u8>>) -> Result<Self, crate::Error> {
+		let (name, language) = Self::parse_path(path).ok_or_else(|| crate::Error::InvalidData(format!("{path} is not a sheet path")))?;
+		Self::load(&name, language, file_reader)
+	}
+	
+	pub fn page_path(name: &str, start_id: u32, language: Language) -> String {
+		match language.code() {
+			Some(code) => format!("exd/{name}_{start_id}_{code}.exd"),
+			None => format!("exd/{name}_{start_id}.exd"),
+		}
+	}
+	
+	/// Sheet name and language of a page or header path
+	pub fn parse_path(path: &str) -> Option<(String, Language)> {
+		let path = path.strip_prefix("exd/")?;
+		if let Some(name) = path.strip_suffix(".exh") {
+			return Some((name.to_string(), Language::English));
+		}
+		
+		let path = path.strip_suffix(".exd")?;
+		let (rest, last) = path.rsplit_once('_')?;
+		let (rest, language) = match Language::from_code(last) {
+			Some(language) => (rest.rsplit_once('_')?.0, language),
+			None => (rest, Language::None),
+		};
+		
+		Some((rest.to_string(), language))
+	}
+	
+	/// Splits the rows back up into the pages of the header, (path, page)
+	pub fn pages(&self) -> Result<Vec<(String, Exd)>, crate::Error> {
+		let mut pages = self.header.pages
+			.iter()
+			.map(|v| (Self::page_path(&self.name, v.start_id, self.language), Exd::new()))
+			.collect::<Vec<_>>();
+		
+		for row in &self.sheet.rows {
+			let Some(index) = self.header.page_index(row.id) else {
+				return Err(crate::Error::InvalidData(format!("Row {} is not within any page of sheet {}", row.id, self.name)));
+			};
+			
+			pages[index].1.rows.push(row.clone());
+		}
+		
+		Ok(pages)
+	}
+	
+	// replaces the rows with the ones from (id, sub id, fields)
+	fn set_rows(&mut self, entries: Vec<(u32, u16, Vec<Field>)>) -> Result<(), crate::Error> {
+		let mut sheet = Exd::new();
+		for (id, sub_id, fields) in entries {
+			if fields.len() != self.header.columns.len() {
+				return Err(crate::Error::InvalidData(format!("Row {id} has {} fields while the sheet has {} columns", fields.len(), self.header.columns.len())));
+			}
+			
+			let sub_row = SubRow{id: sub_id, fields};
+			match sheet.rows.binary_search_by_key(&id, |v| v.id) {
+				Ok(_) if !self.header.has_subrows() => return Err(crate::Error::InvalidData(format!("Row {id} exists multiple times"))),
+				Ok(i) => sheet.rows[i].sub_rows.push(sub_row),
+				Err(i) => sheet.rows.insert(i, Row{id, sub_rows: vec![sub_row]}),
+			}
+		}
+		
+		self.sheet.rows = sheet.rows;
+		Ok(())
+	}
+	
+	// (id, sub id, fields) for every subrow
+	fn entries(&self) -> impl Iterator<Item = (u32, u16, &Vec<Field>)> {
+		self.sheet.rows
+			.iter()
+			.flat_map(|row| row.sub_rows.iter().map(|sub_row| (row.id, sub_row.id, &sub_row.fields)))
+	}
+	
+	fn column_names(&self) -> Vec<String> {
+		self.header.columns
+			.iter()
+			.enumerate()
+			.map(|(i, v)| format!("{i}:{}", kind_name(&v.kind)))
+			.collect()
+	}
+}
+
+fn kind_name(kind: &ColumnKind) -> &'static str {
+	match kind {
+		ColumnKind::String => "str",
+		ColumnKind::Bool => "bool",
+		ColumnKind::I8 => "i8",
+		ColumnKind::U8 => "u8",
+		ColumnKind::I16 => "i16",
+		ColumnKind::U16 => "u16",
+		ColumnKind::I32 => "i32",
+		ColumnKind::U32 => "u32",
+		ColumnKind::F32 => "f32",
+		ColumnKind::I64 => "i64",
+		ColumnKind::U64 => "u64",
+		_ => "packedbool",
+	}
+}
+
+pub(crate) fn field_to_text(field: &Field) -> String {
+	match field {
+		Field::String(v) => v.to_text(),
+		Field::Bool(v) => v.to_string(),
+		Field::I8(v) => v.to_string(),
+		Field::U8(v) => v.to_string(),
+		Field::I16(v) => v.to_string(),
+		Field::U16(v) => v.to_string(),
+		Field::I32(v) => v.to_string(),
+		Field::U32(v) => v.to_string(),
+		Field::F32(v) => v.to_string(),
+		Field::I64(v) => v.to_string(),
+		Field::U64(v) => v.to_string(),
+	}
+}
+
+fn field_from_text(kind: &ColumnKind, text: &str) -> Option<Field> {
+	Some(match kind {
+		ColumnKind::String => Field::String(SeString::from_text(text)),
+		ColumnKind::I8 => Field::I8(text.trim().parse().ok()?),
+		ColumnKind::U8 => Field::U8(text.trim().parse().ok()?),
+		ColumnKind::I16 => Field::I16(text.trim().parse().ok()?),
+		ColumnKind::U16 => Field::U16(text.trim().parse().ok()?),
+		ColumnKind::I32 => Field::I32(text.trim().parse().ok()?),
+		ColumnKind::U32 => Field::U32(text.trim().parse().ok()?),
+		ColumnKind::F32 => Field::F32(text.trim().parse().ok()?),
+		ColumnKind::I64 => Field::I64(text.trim().parse().ok()?),
+		ColumnKind::U64 => Field::U64(text.trim().parse().ok()?),
+		_ => Field::Bool(match text.trim() {
+			"true" | "1" => true,
+			"false" | "0" => false,
+			_ => return None,
+		}),
+	})
+}
+
+fn field_to_json(field: &Field) -> serde_json::Value {
+	match field {
+		Field::String(v) => v.to_text().into(),
+		Field::Bool(v) => (*v).into(),
+		Field::I8(v) => (*v).into(),
+		Field::U8(v) => (*v).into(),
+		Field::I16(v) => (*v).into(),
+		Field::U16(v) => (*v).into(),
+		Field::I32(v) => (*v).into(),
+		Field::U32(v) => (*v).into(),
+		// through the shortest string so that 0.1 doesn't become 0.10000000149011612
+		Field::F32(v) => serde_json::Number::from_f64(v.to_string().parse().unwrap_or(0.0)).map_or(serde_json::Value::Null, Into::into),
+		Field::I64(v) => (*v).into(),
+		Field::U64(v) => (*v).into(),
+	}
+}
+
+fn field_from_json(kind: &ColumnKind, value: &serde_json::Value) -> Option<Field> {
+	Some(match kind {
+		ColumnKind::String => Field::String(SeString::from_text(value.as_str()?)),
+		ColumnKind::I8 => Field::I8(value.as_i64()?.try_into().ok()?),
+		ColumnKind::U8 => Field::U8(value.as_u64()?.try_into().ok()?),
+		ColumnKind::I16 => Field::I16(value.as_i64()?.try_into().ok()?),
+		ColumnKind::U16 => Field::U16(value.as_u64()?.try_into().ok()?),
+		ColumnKind::I32 => Field::I32(value.as_i64()?.try_into().ok()?),
+		ColumnKind::U32 => Field::U32(value.as_u64()?.try_into().ok()?),
+		ColumnKind::F32 => Field::F32(if value.is_null() {f32::NAN} else {value.as_f64()? as f32}),
+		ColumnKind::I64 => Field::I64(value.as_i64()?),
+		ColumnKind::U64 => Field::U64(value.as_u64()?),
+		_ => Field::Bool(value.as_bool()?),
+	})
+}
+
+// ----------
+
+impl crate::format::external::Csv for Excel {
+	type Base = Excel;
+	
+	fn read<T>(reader: &mut T, base: Self::Base) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		let mut data = String::new();
+		reader.read_to_string(&mut data)?;
+		let mut records = crate::format::external::csv::parse(&data)?.into_iter();
+		
+		let skip = if base.header.has_subrows() {2} else {1};
+		let columns = records.next().map_or(0, |v| v.len().saturating_sub(skip));
+		if columns != base.header.columns.len() {
+			return Err(crate::Error::InvalidData(format!("Csv has {columns} columns while the sheet has {}", base.header.columns.len())));
+		}
+		
+		let mut entries = Vec::new();
+		for (line, record) in records.enumerate() {
+			let invalid = || crate::Error::InvalidData(format!("Invalid value in record {}", line + 1));
+			if record.len() != columns + skip {return Err(invalid())}
+			
+			let id = record[0].trim().parse::<u32>().map_err(|_| invalid())?;
+			let sub_id = if skip == 2 {record[1].trim().parse::<u16>().map_err(|_| invalid())?} else {0};
+			let fields = base.header.columns
+				.iter()
+				.zip(&record[skip..])
+				.map(|(column, text)| field_from_text(&column.kind, text))
+				.collect::<Option<Vec<_>>>()
+				.ok_or_else(invalid)?;
+			
+			entries.push((id, sub_id, fields));
+		}
+		
+		let mut excel = base;
+		excel.set_rows(entries)?;
+		Ok(excel)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		let subrows = self.header.has_subrows();
+		let mut header = vec!["id".to_string()];
+		if subrows {header.push("sub_id".to_string())}
+		header.extend(self.column_names());
+		crate::format::external::csv::write_record(writer, &header)?;
+		
+		for (id, sub_id, fields) in self.entries() {
+			let mut record = vec![id.to_string()];
+			if subrows {record.push(sub_id.to_string())}
+			record.extend(fields.iter().map(field_to_text));
+			crate::format::external::csv::write_record(writer, &record)?;
+		}
+		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Json for Excel {
+	type Base = Excel;
+	
+	fn read<T>(reader: &mut T, base: Self::Base) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		let json: serde_json::Value = serde_json::from_reader(reader)?;
+		
+		if let Some(columns) = json["columns"].as_array() {
+			if columns.len() != base.header.columns.len() {
+				return Err(crate::Error::InvalidData(format!("Json has {} columns while the sheet has {}", columns.len(), base.header.columns.len())));
+			}
+		}
+		
+		let rows = json["rows"].as_array().ok_or_else(|| crate::Error::InvalidData("Json has no rows".to_string()))?;
+		let mut entries = Vec::with_capacity(rows.len());
+		for (i, row) in rows.iter().enumerate() {
+			let invalid = || crate::Error::InvalidData(format!("Invalid value in row entry {i}"));
+			let id = row["id"].as_u64().and_then(|v| u32::try_from(v).ok()).ok_or_else(invalid)?;
+			let sub_id = match row.get("sub_id") {
+				Some(v) => v.as_u64().and_then(|v| u16::try_from(v).ok()).ok_or_else(invalid)?,
+				None => 0,
+			};
+			let fields = row["fields"].as_array().ok_or_else(invalid)?;
+			if fields.len() != base.header.columns.len() {return Err(invalid())}
+			let fields = base.header.columns
+				.iter()
+				.zip(fields)
+				.map(|(column, value)| field_from_json(&column.kind, value))
+				.collect::<Option<Vec<_>>>()
+				.ok_or_else(invalid)?;
+			
+			entries.push((id, sub_id, fields));
+		}
+		
+		let mut excel = base;
+		excel.set_rows(entries)?;
+		Ok(excel)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		let subrows = self.header.has_subrows();
+		let json = serde_json::json!({
+			"sheet": self.name,
+			"language": self.language.code(),
+			"columns": self.column_names(),
+			"rows": self.entries().map(|(id, sub_id, fields)| {
+				let mut row = serde_json::Map::new();
+				row.insert("id".to_string(), id.into());
+				if subrows {row.insert("sub_id".to_string(), sub_id.into());}
+				row.insert("fields".to_string(), fields.iter().map(field_to_json).collect());
+				row
+			}).collect::<Vec<_>>(),
+		});
+		
+		serde_json::to_writer_pretty(writer, &json)?;
+		
+		Ok(())
+	}
+}
@@ -1,260 +1,417 @@
-use std::{io::{Read, Seek, Write}, ops::{Deref, DerefMut}};
-use binrw::{binrw, BinRead, BinWrite};
+use std::io::{Read, Seek, SeekFrom, Write};
+use binrw::{BinRead, BinWrite};
+use super::exh::{ColumnKind, Exh};
+
+pub const EXT: &'static [&'static str] = &["exd"];
 
 pub type Error = binrw::Error;
 
-#[binrw]
-#[brw(big, magic = b"EXDF")]
+const HEADER_SIZE: usize = 0x20;
+
 #[derive(Debug, Clone)]
 pub struct Exd {
+	/// Sorted by id
+	pub rows: Vec<Row>,
+	
 	_version: u16,
 	_unk1: u16,
-	// #[bw(calc = rows.len() as u32 * 8)]
-	rows_size: u32,
-	_unk2: [u8; 20],
-	#[br(count = rows_size / 8)]
-	rows: Vec<(u32, u32)>, // id, offset
-	#[br(parse_with = binrw::helpers::until_eof)]
-	data: Vec<u8>,
+	_unk2: [u8; 16],
 }
 
-impl Exd {
-	pub fn get_row_mut(&mut self, row: u32, sub_row: u32) -> Option<&mut [u8]> {
-		let row_offset = self.rows.iter().find_map(|(id, offset)| if row == *id {Some(*offset)} else {None})?;
-		let data_offset = 4 + 2 + 2 + 4 + 20 + (8 * self.rows.len());
-		let row_size = u32::from_be_bytes(self.data[row_offset as usize - data_offset..row_offset as usize - data_offset + 4].try_into().unwrap());
-		let row_count = u16::from_be_bytes(self.data[row_offset as usize - data_offset + 4..row_offset as usize - data_offset + 6].try_into().unwrap());
-		let sub_row_size = row_size / row_count as u32;
-		let offset = row_offset as usize - data_offset + 6;
-		let offset = (0..row_count as u32).into_iter().find_map(|sub_id| {
-			let o = offset + (sub_row_size * sub_id) as usize;
-			if u16::from_be_bytes(self.data[o + sub_row_size as usize - 2..o + sub_row_size as usize].try_into().unwrap()) as u32 == sub_row {Some(o)} else {None}
-		})?;
-		
-		Some(&mut self.data[offset..offset + sub_row_size as usize - 2])
-	}
-	
-	pub fn get_fields_mut(&mut self, row: u32, sub_row: u32, header: &super::Exh) -> Option<Vec<Field>> {
-		use super::exh::ColumnKind;
-		
-		let row = self.get_row_mut(row, sub_row)?;
-		let fields_length = header.columns.iter().map(|v| v.kind.len()).sum::<usize>();
-		let mut fields = Vec::new();
-		
-		for c in &header.columns {
-			let l = c.kind.len();
-			let o = c.offset as usize;
-			
-			fields.push(match c.kind {
-				ColumnKind::String => {
-					let o = u32::from_be_bytes(row[o..o + 4].try_into().unwrap()) as usize;
-					Field::String(FieldString::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + fields_length + 1 + o) as *mut u8, row.len() - fields_length - o - 1)}))
-				},
-				
-				ColumnKind::Bool => Field::Bool(FieldBool::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::I8 => Field::I8(FieldI8::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::U8 => Field::U8(FieldU8::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::I16 => Field::I16(FieldI16::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::U16 => Field::U16(FieldU16::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::I32 => Field::I32(FieldI32::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::U32 => Field::U32(FieldU32::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::F32 => Field::F32(FieldF32::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::I64 => Field::I64(FieldI64::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::U64 => Field::U64(FieldU64::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				
-				ColumnKind::PackedBool0 => Field::PackedBool0(FieldPacked::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::PackedBool1 => Field::PackedBool1(FieldPacked::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::PackedBool2 => Field::PackedBool2(FieldPacked::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::PackedBool3 => Field::PackedBool3(FieldPacked::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::PackedBool4 => Field::PackedBool4(FieldPacked::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::PackedBool5 => Field::PackedBool5(FieldPacked::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::PackedBool6 => Field::PackedBool6(FieldPacked::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-				ColumnKind::PackedBool7 => Field::PackedBool7(FieldPacked::new(unsafe{std::slice::from_raw_parts_mut((row.as_mut_ptr() as usize + o) as *mut u8, l)})),
-			});
-		}
-		
-		Some(fields)
-	}
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+	pub id: u32,
+	/// Sheets without subrows always have exactly 1 with id 0
+	pub sub_rows: Vec<SubRow>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubRow {
+	pub id: u16,
+	/// One for every column of the header, in the same order
+	pub fields: Vec<Field>,
 }
 
-impl ironworks::file::File for Exd {
-	fn read(mut data: impl ironworks::FileStream) -> Result<Self, ironworks::Error> {
-		<Exd as crate::format::external::Bytes>::read(&mut data).map_err(|e| ironworks::Error::Resource(e.into()))
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+	String(SeString),
+	/// Both regular and packed bools
+	Bool(bool),
+	I8(i8),
+	U8(u8),
+	I16(i16),
+	U16(u16),
+	I32(i32),
+	U32(u32),
+	F32(f32),
+	I64(i64),
+	U64(u64),
+}
+
+impl Field {
+	/// The value a newly created row has for a column
+	pub fn default_for(kind: &ColumnKind) -> Self {
+		match kind {
+			ColumnKind::String => Field::String(SeString::default()),
+			ColumnKind::Bool => Field::Bool(false),
+			ColumnKind::I8 => Field::I8(0),
+			ColumnKind::U8 => Field::U8(0),
+			ColumnKind::I16 => Field::I16(0),
+			ColumnKind::U16 => Field::U16(0),
+			ColumnKind::I32 => Field::I32(0),
+			ColumnKind::U32 => Field::U32(0),
+			ColumnKind::F32 => Field::F32(0.0),
+			ColumnKind::I64 => Field::I64(0),
+			ColumnKind::U64 => Field::U64(0),
+			_ => Field::Bool(false), // packed bools
+		}
 	}
 }
 
-impl crate::format::external::Bytes for Exd {
-	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
-	where T: Read + Seek {
-		Ok(Exd::read_be(reader)?)
+impl Exd {
+	pub fn new() -> Self {
+		Self {
+			rows: Vec::new(),
+			_version: 2,
+			_unk1: 0,
+			_unk2: [0; 16],
+		}
+	}
+	
+	pub fn read<T>(reader: &mut T, header: &Exh) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Exd::read_be_args(reader, (header,))?)
 	}
 	
-	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	pub fn write<T>(&self, writer: &mut T, header: &Exh) -> Result<(), crate::Error> where
 	T: Write + Seek {
-		self.write_be(writer)?;
+		self.write_be_args(writer, (header,))?;
 		
 		Ok(())
 	}
+	
+	pub fn row(&self, id: u32) -> Option<&Row> {
+		self.rows.binary_search_by_key(&id, |v| v.id).ok().map(|i| &self.rows[i])
+	}
+	
+	pub fn row_mut(&mut self, id: u32) -> Option<&mut Row> {
+		self.rows.binary_search_by_key(&id, |v| v.id).ok().map(|i| &mut self.rows[i])
+	}
+	
+	pub fn get_fields_mut(&mut self, row: u32, sub_row: u16) -> Option<&mut Vec<Field>> {
+		Some(&mut self.row_mut(row)?.sub_rows.iter_mut().find(|v| v.id == sub_row)?.fields)
+	}
+	
+	/// Adds a row with default values, or returns the existing one if it already exists
+	pub fn add_row(&mut self, id: u32, header: &Exh) -> &mut Row {
+		let index = match self.rows.binary_search_by_key(&id, |v| v.id) {
+			Ok(i) => i,
+			Err(i) => {
+				self.rows.insert(i, Row {
+					id,
+					sub_rows: vec![SubRow::new(0, header)],
+				});
+				i
+			}
+		};
+		
+		&mut self.rows[index]
+	}
+	
+	pub fn remove_row(&mut self, id: u32) -> Option<Row> {
+		let index = self.rows.binary_search_by_key(&id, |v| v.id).ok()?;
+		Some(self.rows.remove(index))
+	}
 }
 
-#[derive(Debug)]
-pub enum Field<'a> {
-	String(FieldString),
-	Bool(FieldBool<'a>),
-	I8(FieldI8<'a>),
-	U8(FieldU8<'a>),
-	I16(FieldI16<'a>),
-	U16(FieldU16<'a>),
-	I32(FieldI32<'a>),
-	U32(FieldU32<'a>),
-	F32(FieldF32<'a>),
-	I64(FieldI64<'a>),
-	U64(FieldU64<'a>),
-	PackedBool0(FieldPacked<'a, 0>),
-	PackedBool1(FieldPacked<'a, 1>),
-	PackedBool2(FieldPacked<'a, 2>),
-	PackedBool3(FieldPacked<'a, 3>),
-	PackedBool4(FieldPacked<'a, 4>),
-	PackedBool5(FieldPacked<'a, 5>),
-	PackedBool6(FieldPacked<'a, 6>),
-	PackedBool7(FieldPacked<'a, 7>),
+impl Row {
+	/// Grows or shrinks the amount of subrows, new ones have default values and follow up on the last id
+	pub fn resize(&mut self, count: usize, header: &Exh) {
+		while self.sub_rows.len() < count {
+			let id = self.sub_rows.last().map_or(0, |v| v.id + 1);
+			self.sub_rows.push(SubRow::new(id, header));
+		}
+		
+		self.sub_rows.truncate(count);
+	}
 }
 
-macro_rules! create_field {
-	(base, $n:ident, $t:ident) => {
-		#[derive(Debug)]
-		pub struct $n<'a> {
-			inner: &'a mut [u8],
-			val: $t,
+impl SubRow {
+	pub fn new(id: u16, header: &Exh) -> Self {
+		Self {
+			id,
+			fields: header.columns.iter().map(|v| Field::default_for(&v.kind)).collect(),
 		}
+	}
+}
+
+impl BinRead for Exd {
+	type Args<'a> = (&'a Exh,);
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, (header,): Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
 		
-		impl<'a> Deref for $n<'a> {
-			type Target = $t;
-		
-			fn deref(&self) -> &Self::Target {
-				&self.val
-			}
+		let magic = r!([u8; 4]);
+		if &magic != b"EXDF" {
+			return Err(binrw::Error::BadMagic{pos: 0, found: Box::new(magic)});
 		}
 		
-		impl<'a> DerefMut for $n<'a> {
-			fn deref_mut(&mut self) -> &mut Self::Target {
-				&mut self.val
+		let version = r!(u16);
+		let unk1 = r!(u16);
+		let index_size = r!(u32);
+		let _data_size = r!(u32);
+		let unk2 = r!([u8; 16]);
+		let index = r!(Vec<(u32, u32)>, index_size / 8);
+		
+		let row_size = header.row_size as usize;
+		let subrows = header.has_subrows();
+		
+		let mut rows = Vec::with_capacity(index.len());
+		for (id, offset) in index {
+			r!(seek offset);
+			let size = r!(u32);
+			let count = r!(u16);
+			let data = r!(Vec<u8>, size);
+			
+			let mut sub_rows = Vec::with_capacity(count as usize);
+			for i in 0..if subrows {count as usize} else {1} {
+				let (sub_id, start) = if subrows {
+					let start = i * (row_size + 2);
+					let sub_id = data.get(start..start + 2).ok_or_else(|| out_of_bounds(offset))?;
+					(u16::from_be_bytes([sub_id[0], sub_id[1]]), start + 2)
+				} else {
+					(0, 0)
+				};
+				
+				let fixed = data.get(start..start + row_size).ok_or_else(|| out_of_bounds(offset))?;
+				let strings = &data[start + row_size..];
+				sub_rows.push(SubRow {
+					id: sub_id,
+					fields: header.columns
+						.iter()
+						.map(|column| read_field(fixed, strings, &column.kind, column.offset as usize).ok_or_else(|| out_of_bounds(offset)))
+						.collect::<Result<_, _>>()?,
+				});
 			}
+			
+			rows.push(Row {
+				id,
+				sub_rows,
+			});
 		}
-	};
-	
-	($n:ident, $t:ident, false) => {
-		create_field!(base, $n, $t);
-	};
+		
+		rows.sort_by_key(|v| v.id);
+		
+		Ok(Self {
+			rows,
+			_version: version,
+			_unk1: unk1,
+			_unk2: unk2,
+		})
+	}
+}
+
+impl BinWrite for Exd {
+	type Args<'a> = (&'a Exh,);
 	
-	($n:ident, $t:ident) => {
-		create_field!(base, $n, $t);
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, (header,): Self::Args<'_>,) -> binrw::BinResult<()> {
+		let row_size = header.row_size as usize;
+		let subrows = header.has_subrows();
 		
-		impl<'a> $n<'a> {
-			pub(crate) fn new(val: &'a mut [u8]) -> Self {
-				Self {
-					val: $t::from_be_bytes(val.try_into().unwrap()),
-					inner: val,
-				}
+		let mut index = Vec::with_capacity(self.rows.len());
+		let mut data = Vec::new();
+		for row in &self.rows {
+			if !subrows && row.sub_rows.len() != 1 {
+				return Err(binrw::Error::AssertFail{pos: 0, message: format!("Row {} has {} subrows while the sheet doesn't support them", row.id, row.sub_rows.len())});
 			}
-		}
-		
-		impl<'a> Drop for $n<'a> {
-			fn drop(&mut self) {
-				// self.inner.write_all(&self.val.to_be_bytes());
-				for (i, v) in self.val.to_be_bytes().into_iter().enumerate() {
-					self.inner[i] = v;
+			
+			let sub_row_size = if subrows {row_size + 2} else {row_size};
+			let mut fixed = vec![0u8; sub_row_size * row.sub_rows.len()];
+			let mut strings = Vec::new();
+			for (i, sub_row) in row.sub_rows.iter().enumerate() {
+				if sub_row.fields.len() != header.columns.len() {
+					return Err(binrw::Error::AssertFail{pos: 0, message: format!("Row {} has {} fields while the sheet has {} columns", row.id, sub_row.fields.len(), header.columns.len())});
 				}
+				
+				let start = i * sub_row_size;
+				let fixed = &mut fixed[start..start + sub_row_size];
+				let fixed = if subrows {
+					fixed[0..2].copy_from_slice(&sub_row.id.to_be_bytes());
+					&mut fixed[2..]
+				} else {
+					fixed
+				};
+				
+				// string offsets are relative to the end of the subrow they belong to
+				let string_base = (row.sub_rows.len() - i - 1) * sub_row_size;
+				for (column, field) in header.columns.iter().zip(&sub_row.fields) {
+					write_field(fixed, &mut strings, string_base, &column.kind, column.offset as usize, field)
+						.ok_or_else(|| binrw::Error::AssertFail{pos: 0, message: format!("Row {} has a {field:?} in a {:?} column", row.id, column.kind)})?;
+				}
+			}
+			
+			fixed.extend_from_slice(&strings);
+			while fixed.len() % 4 != 0 {
+				fixed.push(0);
 			}
+			
+			index.push((row.id, (HEADER_SIZE + self.rows.len() * 8 + data.len()) as u32));
+			data.extend_from_slice(&(fixed.len() as u32).to_be_bytes());
+			data.extend_from_slice(&(row.sub_rows.len() as u16).to_be_bytes());
+			data.extend_from_slice(&fixed);
 		}
+		
+		let start = writer.stream_position()?;
+		b"EXDF".write_options(writer, endian, ())?;
+		self._version.write_options(writer, endian, ())?;
+		self._unk1.write_options(writer, endian, ())?;
+		(index.len() as u32 * 8).write_options(writer, endian, ())?;
+		(data.len() as u32).write_options(writer, endian, ())?;
+		self._unk2.write_options(writer, endian, ())?;
+		index.write_options(writer, endian, ())?;
+		writer.seek(SeekFrom::Start(start + (HEADER_SIZE + index.len() * 8) as u64))?;
+		data.write_options(writer, endian, ())?;
+		
+		Ok(())
 	}
 }
 
-create_field!(FieldI8, i8);
-create_field!(FieldU8, u8);
-create_field!(FieldI16, i16);
-create_field!(FieldU16, u16);
-create_field!(FieldI32, i32);
-create_field!(FieldU32, u32);
-create_field!(FieldF32, f32);
-create_field!(FieldI64, i64);
-create_field!(FieldU64, u64);
-
-create_field!(FieldBool, bool, false);
-impl<'a> FieldBool<'a> {
-	pub(crate) fn new(val: &'a mut [u8]) -> Self {
-		Self {
-			val: if val[0] == 1 {true} else {false},
-			inner: val,
-		}
-	}
+fn out_of_bounds(pos: u32) -> binrw::Error {
+	binrw::Error::AssertFail{pos: pos as u64, message: "Row data is smaller than the header says it is".to_string()}
 }
 
-impl<'a> Drop for FieldBool<'a> {
-	fn drop(&mut self) {
-		self.inner[0] = if self.val {1} else {0}
-	}
+fn read_field(fixed: &[u8], strings: &[u8], kind: &ColumnKind, offset: usize) -> Option<Field> {
+	let bytes = fixed.get(offset..offset + kind.len().max(1))?;
+	
+	Some(match kind {
+		ColumnKind::String => {
+			let offset = u32::from_be_bytes(bytes.try_into().ok()?) as usize;
+			let string = strings.get(offset..)?;
+			Field::String(SeString(string[..string.iter().position(|v| *v == 0).unwrap_or(string.len())].to_vec()))
+		}
+		
+		ColumnKind::Bool => Field::Bool(bytes[0] != 0),
+		ColumnKind::I8 => Field::I8(bytes[0] as i8),
+		ColumnKind::U8 => Field::U8(bytes[0]),
+		ColumnKind::I16 => Field::I16(i16::from_be_bytes(bytes.try_into().ok()?)),
+		ColumnKind::U16 => Field::U16(u16::from_be_bytes(bytes.try_into().ok()?)),
+		ColumnKind::I32 => Field::I32(i32::from_be_bytes(bytes.try_into().ok()?)),
+		ColumnKind::U32 => Field::U32(u32::from_be_bytes(bytes.try_into().ok()?)),
+		ColumnKind::F32 => Field::F32(f32::from_be_bytes(bytes.try_into().ok()?)),
+		ColumnKind::I64 => Field::I64(i64::from_be_bytes(bytes.try_into().ok()?)),
+		ColumnKind::U64 => Field::U64(u64::from_be_bytes(bytes.try_into().ok()?)),
+		_ => Field::Bool(bytes[0] & (1 << kind.packed_bit()?) != 0),
+	})
 }
 
-/// Does not support modifying
-#[derive(Debug)]
-pub struct FieldString {
-	val: String,
+// None if the field doesn't match the column
+fn write_field(fixed: &mut [u8], strings: &mut Vec<u8>, string_base: usize, kind: &ColumnKind, offset: usize, field: &Field) -> Option<()> {
+	let bytes = match (kind, field) {
+		(ColumnKind::String, Field::String(v)) => {
+			let string_offset = (string_base + strings.len()) as u32;
+			strings.extend_from_slice(&v.0);
+			strings.push(0);
+			string_offset.to_be_bytes().to_vec()
+		}
+		
+		(ColumnKind::Bool, Field::Bool(v)) => vec![*v as u8],
+		(ColumnKind::I8, Field::I8(v)) => v.to_be_bytes().to_vec(),
+		(ColumnKind::U8, Field::U8(v)) => v.to_be_bytes().to_vec(),
+		(ColumnKind::I16, Field::I16(v)) => v.to_be_bytes().to_vec(),
+		(ColumnKind::U16, Field::U16(v)) => v.to_be_bytes().to_vec(),
+		(ColumnKind::I32, Field::I32(v)) => v.to_be_bytes().to_vec(),
+		(ColumnKind::U32, Field::U32(v)) => v.to_be_bytes().to_vec(),
+		(ColumnKind::F32, Field::F32(v)) => v.to_be_bytes().to_vec(),
+		(ColumnKind::I64, Field::I64(v)) => v.to_be_bytes().to_vec(),
+		(ColumnKind::U64, Field::U64(v)) => v.to_be_bytes().to_vec(),
+		(kind, Field::Bool(v)) => {
+			let bit = kind.packed_bit()?;
+			vec![fixed.get(offset)? & !(1 << bit) | ((*v as u8) << bit)]
+		}
+		
+		_ => return None,
+	};
+	
+	fixed.get_mut(offset..offset + bytes.len())?.copy_from_slice(&bytes);
+	Some(())
 }
 
-impl Deref for FieldString {
-	type Target = String;
-
-	fn deref(&self) -> &Self::Target {
-		&self.val
-	}
-}
+// ----------
 
-impl DerefMut for FieldString {
-	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.val
-	}
-}
+/// A game string, these can contain payloads (icons, colors, etc) which aren't valid utf8
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeString(pub Vec<u8>);
 
-impl FieldString {
-	pub(crate) fn new(val: &mut [u8]) -> Self {
-		Self {
-			val: crate::NullReader::null_terminated(val).unwrap(),
+impl SeString {
+	/// Readable text with the payloads written as `<hex:...>`, the inverse of `from_text`
+	pub fn to_text(&self) -> String {
+		let mut text = String::new();
+		let mut pos = 0;
+		while pos < self.0.len() {
+			let payload_start = self.0[pos..].iter().position(|v| *v == 2).map_or(self.0.len(), |v| pos + v);
+			text.push_str(&String::from_utf8_lossy(&self.0[pos..payload_start]));
+			if payload_start >= self.0.len() {break}
+			
+			let payload_end = payload_len(&self.0[payload_start..]).map_or(self.0.len(), |v| (payload_start + v).min(self.0.len()));
+			text.push_str("<hex:");
+			for v in &self.0[payload_start..payload_end] {
+				text.push_str(&format!("{v:02X}"));
+			}
+			text.push('>');
+			pos = payload_end;
 		}
+		
+		text
 	}
-}
-
-#[derive(Debug)]
-pub struct FieldPacked<'a, const B: u8> {
-	inner: &'a mut [u8],
-	val: bool,
-}
-
-impl<'a, const B: u8> Deref for FieldPacked<'a, B> {
-	type Target = bool;
-
-	fn deref(&self) -> &Self::Target {
-		&self.val
+	
+	pub fn from_text(text: &str) -> Self {
+		let mut data = Vec::new();
+		let mut text = text;
+		while let Some(start) = text.find("<hex:") {
+			let Some(end) = text[start..].find('>').map(|v| start + v) else {break};
+			let Some(payload) = (start + 5..end).step_by(2).map(|i| text.get(i..i + 2).and_then(|v| u8::from_str_radix(v, 16).ok())).collect::<Option<Vec<_>>>() else {
+				data.extend_from_slice(text[..end + 1].as_bytes());
+				text = &text[end + 1..];
+				continue;
+			};
+			
+			data.extend_from_slice(text[..start].as_bytes());
+			data.extend_from_slice(&payload);
+			text = &text[end + 1..];
+		}
+		data.extend_from_slice(text.as_bytes());
+		
+		Self(data)
 	}
 }
 
-impl<'a, const B: u8> DerefMut for FieldPacked<'a, B> {
-	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.val
+impl From<&str> for SeString {
+	fn from(value: &str) -> Self {
+		Self(value.as_bytes().to_vec())
 	}
 }
 
-impl<'a, const B: u8> FieldPacked<'a, B> {
-	pub(crate) fn new(val: &'a mut [u8]) -> Self {
-		Self {
-			val: val[0] & (1 << B) == 1 << B,
-			inner: val,
+// total length of the payload at the start of data, including the start and end markers
+fn payload_len(data: &[u8]) -> Option<usize> {
+	let marker = *data.get(2)?;
+	let flags = match marker {
+		// the length is stored in the marker itself, offset by 1
+		0x01..=0xCF => return Some(3 + (marker as usize - 1) + 1),
+		// the length is stored in up to 4 bytes, which ones are present is stored in the marker
+		0xF0..=0xFE => marker - 0xEF,
+		// other expression types can't be a length
+		_ => return None,
+	};
+	
+	let mut len = 0usize;
+	let mut read = 0;
+	for i in (0..4).rev() {
+		len <<= 8;
+		if flags & (1 << i) != 0 {
+			len |= *data.get(3 + read)? as usize;
+			read += 1;
 		}
 	}
+	
+	Some(3 + read + len + 1)
 }
-
-impl<'a, const B: u8> Drop for FieldPacked<'a, B> {
-	fn drop(&mut self) {
-		self.inner[0] = self.inner[0] & !(1 << B) | (if self.val {1} else {0} << B)
-	}
-}
\ No newline at end of file
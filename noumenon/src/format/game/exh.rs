@@ -3,6 +3,8 @@
 use std::io::{Read, Seek, Write};
 use binrw::{binrw, BinRead, BinWrite};
 
+pub const EXT: &'static [&'static str] = &["exh"];
+
 pub type Error = binrw::Error;
 
 #[binrw]
@@ -10,13 +12,13 @@ pub type Error = binrw::Error;
 #[derive(Debug, Clone)]
 pub struct Exh {
 	_version: u16,
-	row_size: u16,
+	pub(crate) row_size: u16,
 	column_count: u16,
 	page_count: u16,
 	language_count: u16,
 	_unk1: u16,
 	_unk2: u8,
-	sheet_type: u8,
+	pub(crate) sheet_type: u8,
 	_unk3: u16,
 	row_count: u32,
 	_unk4: [u8; 8],
@@ -29,9 +31,33 @@ pub struct Exh {
 	pub languages: Vec<LanguageSeg>,
 }
 
-impl ironworks::file::File for Exh {
-	fn read(mut data: impl ironworks::FileStream) -> Result<Self, ironworks::Error> {
-		<Exh as crate::format::external::Bytes>::read(&mut data).map_err(|e| ironworks::Error::Resource(e.into()))
+impl Exh {
+	/// Whether rows of the sheets can contain multiple subrows
+	pub fn has_subrows(&self) -> bool {
+		self.sheet_type == 2
+	}
+	
+	/// The language the pages are stored as, sheets without localized pages use None for every language
+	pub fn resolve_language(&self, language: Language) -> Option<Language> {
+		if self.languages.iter().any(|v| v.language == language as u8) {
+			Some(language)
+		} else if self.languages.iter().all(|v| v.language == Language::None as u8) {
+			Some(Language::None)
+		} else {
+			None
+		}
+	}
+	
+	/// Index of the page containing the row
+	pub fn page_index(&self, row: u32) -> Option<usize> {
+		self.pages.iter().position(|v| row >= v.start_id && row < v.start_id + v.row_count)
+	}
+}
+
+impl crate::File for Exh {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(<Exh as crate::format::external::Bytes>::read(&mut reader)?)
 	}
 }
 
@@ -109,6 +135,21 @@ impl ColumnKind {
 			ColumnKind::PackedBool7 => 0,
 		}
 	}
+	
+	/// The bit within the byte for packed bools
+	pub fn packed_bit(&self) -> Option<u8> {
+		match self {
+			ColumnKind::PackedBool0 => Some(0),
+			ColumnKind::PackedBool1 => Some(1),
+			ColumnKind::PackedBool2 => Some(2),
+			ColumnKind::PackedBool3 => Some(3),
+			ColumnKind::PackedBool4 => Some(4),
+			ColumnKind::PackedBool5 => Some(5),
+			ColumnKind::PackedBool6 => Some(6),
+			ColumnKind::PackedBool7 => Some(7),
+			_ => None,
+		}
+	}
 }
 
 #[binrw]
@@ -142,6 +183,22 @@ pub enum Language {
 }
 
 impl Language {
+	pub const ALL: [Language; 9] = [
+		Language::None,
+		Language::Japanese,
+		Language::English,
+		Language::German,
+		Language::French,
+		Language::ChineseSimplified,
+		Language::ChineseTraditional,
+		Language::Korean,
+		Language::ChineseTraditional2,
+	];
+	
+	pub fn from_code(code: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|v| v.code() == Some(code))
+	}
+	
 	pub fn code(&self) -> Option<&'static str> {
 		match self {
 			Language::None                => None,
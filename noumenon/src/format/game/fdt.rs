@@ -0,0 +1,397 @@
+use std::{collections::HashMap, io::{Read, Seek, Write}};
+use binrw::{BinRead, BinWrite};
+use super::tex::{Format, Tex};
+
+pub const EXT: &'static [&'static str] = &["fdt"];
+
+pub type Error = binrw::Error;
+
+/// Font definition, where the glyphs of a font are in the font textures and how they are spaced
+#[derive(Debug, Clone)]
+pub struct Fdt {
+	/// In points
+	pub size: f32,
+	pub line_height: u32,
+	pub ascent: u32,
+	pub texture_width: u16,
+	pub texture_height: u16,
+	/// Sorted by codepoint, the game binary searches them
+	pub glyphs: Vec<Glyph>,
+	/// Sorted by left and then right codepoint
+	pub kerning: Vec<Kerning>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyph {
+	pub codepoint: char,
+	pub sjis: u16,
+	/// Texture file * 4 + channel, channels are in rgba order. See [Fdt::texture_path]
+	pub texture_index: u16,
+	pub x: u16,
+	pub y: u16,
+	pub width: u8,
+	pub height: u8,
+	/// Added to the width to get the advance
+	pub offset_x: i8,
+	/// From the top of the line
+	pub offset_y: i8,
+}
+
+impl Glyph {
+	pub fn advance(&self) -> i32 {
+		self.width as i32 + self.offset_x as i32
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Kerning {
+	pub left: char,
+	pub right: char,
+	pub left_sjis: u16,
+	pub right_sjis: u16,
+	pub offset: i32,
+}
+
+impl Fdt {
+	/// The lobby fonts use their own textures
+	pub fn texture_path(texture_index: u16, lobby: bool) -> String {
+		if lobby {
+			format!("common/font/font_lobby{}.tex", texture_index / 4 + 1)
+		} else {
+			format!("common/font/font{}.tex", texture_index / 4 + 1)
+		}
+	}
+	
+	pub fn glyph(&self, codepoint: char) -> Option<&Glyph> {
+		self.glyphs
+			.binary_search_by_key(&codepoint, |v| v.codepoint)
+			.ok()
+			.map(|i| &self.glyphs[i])
+	}
+	
+	pub fn kerning(&self, left: char, right: char) -> i32 {
+		self.kerning
+			.binary_search_by_key(&(left, right), |v| (v.left, v.right))
+			.map_or(0, |i| self.kerning[i].offset)
+	}
+	
+	/// Rebuilds the glyphs and kerning from a ttf/otf font at the size of the fdt, drawing the glyphs into the atlas.
+	/// Codepoints the font doesn't have are dropped
+	pub fn rasterize(&mut self, font: &[u8], codepoints: impl IntoIterator<Item = char>, atlas: &mut FontAtlas) -> Result<(), crate::Error> {
+		use ab_glyph::{Font, ScaleFont};
+		
+		let font = ab_glyph::FontRef::try_from_slice(font).map_err(|err| crate::Error::InvalidData(format!("Invalid font ({err})")))?;
+		// ab_glyph scales by the height of the font instead of the em, sizes are in points at 96 dpi
+		let units_per_em = font.units_per_em().ok_or_else(|| crate::Error::InvalidData("Font has no units per em".to_string()))?;
+		let scale = ab_glyph::PxScale::from(self.size * 4.0 / 3.0 * font.height_unscaled() / units_per_em);
+		let scaled = font.as_scaled(scale);
+		
+		self.ascent = scaled.ascent().round().max(0.0) as u32;
+		self.line_height = (scaled.height() + scaled.line_gap()).round().max(0.0) as u32;
+		self.texture_width = atlas.width as u16;
+		self.texture_height = atlas.height as u16;
+		
+		// the game has no way to convert to shift-jis, keep the codes of the glyphs we already had
+		let sjis = self.glyphs.iter().map(|v| (v.codepoint, v.sjis)).collect::<HashMap<_, _>>();
+		let mut codepoints = codepoints
+			.into_iter()
+			.filter(|v| font.glyph_id(*v).0 != 0)
+			.collect::<Vec<_>>();
+		codepoints.sort();
+		codepoints.dedup();
+		
+		self.glyphs.clear();
+		for &codepoint in &codepoints {
+			let id = font.glyph_id(codepoint);
+			let advance = scaled.h_advance(id).round() as i32;
+			let mut glyph = Glyph {
+				codepoint,
+				sjis: sjis.get(&codepoint).copied().unwrap_or(0),
+				texture_index: 0,
+				x: 0,
+				y: 0,
+				width: advance.clamp(0, 255) as u8,
+				height: 0,
+				offset_x: 0,
+				offset_y: 0,
+			};
+			
+			// glyphs are drawn from the pen position, anything left of it gets cut off
+			if let Some(outline) = font.outline_glyph(id.with_scale_and_position(scale, ab_glyph::point(0.0, self.ascent as f32))) {
+				let bounds = outline.px_bounds();
+				let top = bounds.min.y.clamp(-128.0, 127.0) as i32;
+				let width = bounds.max.x.clamp(0.0, 255.0) as u32;
+				let height = (bounds.max.y as i32 - top).clamp(0, 255) as u32;
+				let (plane, x, y) = atlas.allocate(width, height).ok_or_else(|| crate::Error::InvalidData("Glyph does not fit in the font atlas".to_string()))?;
+				let left = bounds.min.x as i32;
+				outline.draw(|gx, gy, coverage| {
+					let px = left + gx as i32;
+					if px < 0 || px >= width as i32 {return}
+					atlas.draw(plane, x + px as u32, y + gy, coverage);
+				});
+				
+				glyph.texture_index = plane as u16;
+				glyph.x = x as u16;
+				glyph.y = y as u16;
+				glyph.width = width as u8;
+				glyph.height = height as u8;
+				glyph.offset_y = top as i8;
+			}
+			
+			glyph.offset_x = (advance - glyph.width as i32).clamp(-128, 127) as i8;
+			self.glyphs.push(glyph);
+		}
+		
+		// every pair of the cjk ranges would take forever and those fonts rarely kern them anyways
+		let kerned = self.glyphs.iter().filter(|v| v.codepoint < '\u{3000}').collect::<Vec<_>>();
+		self.kerning.clear();
+		for left in &kerned {
+			for right in &kerned {
+				let offset = scaled.kern(font.glyph_id(left.codepoint), font.glyph_id(right.codepoint)).round() as i32;
+				if offset == 0 {continue}
+				self.kerning.push(Kerning {
+					left: left.codepoint,
+					right: right.codepoint,
+					left_sjis: left.sjis,
+					right_sjis: right.sjis,
+					offset,
+				});
+			}
+		}
+		
+		Ok(())
+	}
+}
+
+fn read_utf8(value: u32) -> char {
+	let bytes = value.to_be_bytes();
+	let start = bytes.iter().position(|v| *v != 0).unwrap_or(3);
+	std::str::from_utf8(&bytes[start..]).ok().and_then(|v| v.chars().next()).unwrap_or('\0')
+}
+
+fn write_utf8(codepoint: char) -> u32 {
+	codepoint.encode_utf8(&mut [0; 4]).bytes().fold(0, |acc, v| acc << 8 | v as u32)
+}
+
+impl BinRead for Fdt {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
+		
+		let magic = r!([u8; 8]);
+		if &magic != b"fcsv0100" {
+			return Err(binrw::Error::BadMagic{pos: 0, found: Box::new(magic)});
+		}
+		
+		let font_table_offset = r!(u32);
+		let kerning_table_offset = r!(u32);
+		
+		r!(seek font_table_offset + 4);
+		let glyph_count = r!(u32);
+		r!(move 8);
+		let texture_width = r!(u16);
+		let texture_height = r!(u16);
+		let size = r!(f32);
+		let line_height = r!(u32);
+		let ascent = r!(u32);
+		
+		let mut glyphs = Vec::with_capacity(glyph_count as usize);
+		for _ in 0..glyph_count {
+			glyphs.push(Glyph {
+				codepoint: read_utf8(r!(u32)),
+				sjis: r!(u16),
+				texture_index: r!(u16),
+				x: r!(u16),
+				y: r!(u16),
+				width: r!(u8),
+				height: r!(u8),
+				offset_x: r!(i8),
+				offset_y: r!(i8),
+			});
+		}
+		
+		r!(seek kerning_table_offset + 4);
+		let kerning_count = r!(u32);
+		r!(move 8);
+		
+		let mut kerning = Vec::with_capacity(kerning_count as usize);
+		for _ in 0..kerning_count {
+			kerning.push(Kerning {
+				left: read_utf8(r!(u32)),
+				right: read_utf8(r!(u32)),
+				left_sjis: r!(u16),
+				right_sjis: r!(u16),
+				offset: r!(i32),
+			});
+		}
+		
+		Ok(Self {
+			size,
+			line_height,
+			ascent,
+			texture_width,
+			texture_height,
+			glyphs,
+			kerning,
+		})
+	}
+}
+
+impl BinWrite for Fdt {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		let kerning_table_offset = 0x40 + self.glyphs.len() as u32 * 0x10;
+		
+		b"fcsv0100".write_options(writer, endian, ())?;
+		0x20u32.write_options(writer, endian, ())?;
+		kerning_table_offset.write_options(writer, endian, ())?;
+		[0u8; 16].write_options(writer, endian, ())?;
+		
+		b"fthd".write_options(writer, endian, ())?;
+		(self.glyphs.len() as u32).write_options(writer, endian, ())?;
+		(self.kerning.len() as u32).write_options(writer, endian, ())?;
+		0u32.write_options(writer, endian, ())?;
+		self.texture_width.write_options(writer, endian, ())?;
+		self.texture_height.write_options(writer, endian, ())?;
+		self.size.write_options(writer, endian, ())?;
+		self.line_height.write_options(writer, endian, ())?;
+		self.ascent.write_options(writer, endian, ())?;
+		
+		for glyph in &self.glyphs {
+			write_utf8(glyph.codepoint).write_options(writer, endian, ())?;
+			glyph.sjis.write_options(writer, endian, ())?;
+			glyph.texture_index.write_options(writer, endian, ())?;
+			glyph.x.write_options(writer, endian, ())?;
+			glyph.y.write_options(writer, endian, ())?;
+			glyph.width.write_options(writer, endian, ())?;
+			glyph.height.write_options(writer, endian, ())?;
+			glyph.offset_x.write_options(writer, endian, ())?;
+			glyph.offset_y.write_options(writer, endian, ())?;
+		}
+		
+		b"knhd".write_options(writer, endian, ())?;
+		(self.kerning.len() as u32).write_options(writer, endian, ())?;
+		[0u8; 8].write_options(writer, endian, ())?;
+		
+		for kerning in &self.kerning {
+			write_utf8(kerning.left).write_options(writer, endian, ())?;
+			write_utf8(kerning.right).write_options(writer, endian, ())?;
+			kerning.left_sjis.write_options(writer, endian, ())?;
+			kerning.right_sjis.write_options(writer, endian, ())?;
+			kerning.offset.write_options(writer, endian, ())?;
+		}
+		
+		Ok(())
+	}
+}
+
+impl crate::File for Fdt {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Fdt::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Fdt {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Fdt {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Fdt::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
+
+// ----------
+
+/// Font textures being filled by [Fdt::rasterize]. Fonts share textures, rasterize all of them into the same atlas
+/// and replace every fdt using those textures
+#[derive(Debug, Clone)]
+pub struct FontAtlas {
+	pub width: u32,
+	pub height: u32,
+	
+	// rgba8 pixels per texture, every channel holds its own glyphs
+	pages: Vec<Vec<u8>>,
+	// the channel being filled and the row within it
+	plane: usize,
+	x: u32,
+	y: u32,
+	row_height: u32,
+}
+
+impl FontAtlas {
+	pub fn new(width: u32, height: u32) -> Self {
+		Self {
+			width,
+			height,
+			pages: Vec::new(),
+			plane: 0,
+			x: 0,
+			y: 0,
+			row_height: 0,
+		}
+	}
+	
+	/// The textures in order, see [Fdt::texture_path]
+	pub fn textures(&self) -> Vec<Tex> {
+		self.pages
+			.iter()
+			.map(|pixels| Tex {
+				flags: Tex::TYPE_2D,
+				format: Format::A4R4G4B4,
+				width: self.width,
+				height: self.height,
+				depth: 1,
+				layers: 1,
+				mip_levels: 1,
+				lods: true,
+				pixels: pixels.clone(),
+				native: None,
+			}).collect()
+	}
+	
+	// glyphs are padded by a pixel so they don't bleed into eachother
+	fn allocate(&mut self, width: u32, height: u32) -> Option<(usize, u32, u32)> {
+		if width + 1 > self.width || height + 1 > self.height {return None}
+		
+		if self.x + width + 1 > self.width {
+			self.x = 0;
+			self.y += self.row_height;
+			self.row_height = 0;
+		}
+		
+		if self.y + height + 1 > self.height {
+			self.plane += 1;
+			self.x = 0;
+			self.y = 0;
+			self.row_height = 0;
+		}
+		
+		while self.pages.len() <= self.plane / 4 {
+			self.pages.push(vec![0; self.width as usize * self.height as usize * 4]);
+		}
+		
+		let pos = (self.plane, self.x, self.y);
+		self.x += width + 1;
+		self.row_height = self.row_height.max(height + 1);
+		
+		Some(pos)
+	}
+	
+	fn draw(&mut self, plane: usize, x: u32, y: u32, coverage: f32) {
+		if x >= self.width || y >= self.height {return}
+		let pixel = &mut self.pages[plane / 4][(y as usize * self.width as usize + x as usize) * 4 + plane % 4];
+		*pixel = (*pixel).max((coverage.clamp(0.0, 1.0) * 255.0).round() as u8);
+	}
+}
\ No newline at end of file
@@ -0,0 +1,110 @@
+use std::io::{Read, Seek, Write};
+use binrw::{BinRead, BinWrite};
+use super::eqp::SetEntries;
+
+pub const EXT: &'static [&'static str] = &["gmp"];
+
+pub type Error = binrw::Error;
+
+/// Gimmick parameters, used by headgear that can be toggled (visors)
+#[derive(Debug, Clone)]
+pub struct Gmp {
+	sets: SetEntries,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GmpEntry {
+	pub enabled: bool,
+	pub animated: bool,
+	/// 10 bits
+	pub rotation_a: u16,
+	/// 10 bits
+	pub rotation_b: u16,
+	/// 10 bits
+	pub rotation_c: u16,
+	/// 4 bits
+	pub unknown_a: u8,
+	/// 4 bits
+	pub unknown_b: u8,
+}
+
+impl From<u64> for GmpEntry {
+	fn from(value: u64) -> Self {
+		Self {
+			enabled: value & 1 != 0,
+			animated: value & 2 != 0,
+			rotation_a: (value >> 2 & 0x3FF) as u16,
+			rotation_b: (value >> 12 & 0x3FF) as u16,
+			rotation_c: (value >> 22 & 0x3FF) as u16,
+			unknown_a: (value >> 32 & 0xF) as u8,
+			unknown_b: (value >> 36 & 0xF) as u8,
+		}
+	}
+}
+
+impl From<GmpEntry> for u64 {
+	fn from(value: GmpEntry) -> Self {
+		value.enabled as u64 |
+		(value.animated as u64) << 1 |
+		(value.rotation_a as u64 & 0x3FF) << 2 |
+		(value.rotation_b as u64 & 0x3FF) << 12 |
+		(value.rotation_c as u64 & 0x3FF) << 22 |
+		(value.unknown_a as u64 & 0xF) << 32 |
+		(value.unknown_b as u64 & 0xF) << 36
+	}
+}
+
+impl Gmp {
+	pub const PATH: &'static str = "chara/xls/equipmentparameter/gimmickparameter.gmp";
+	
+	pub fn get(&self, set_id: u16) -> GmpEntry {
+		self.sets.get(set_id, 0).into()
+	}
+	
+	pub fn set(&mut self, set_id: u16, entry: GmpEntry) -> Result<(), crate::Error> {
+		self.sets.set(set_id, entry.into(), 0)
+	}
+}
+
+impl BinRead for Gmp {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		Ok(Self {
+			sets: SetEntries::read_options(reader, endian, 0)?,
+		})
+	}
+}
+
+impl BinWrite for Gmp {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		self.sets.write_options(writer, endian, ())
+	}
+}
+
+impl crate::File for Gmp {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Gmp::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Gmp {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Gmp {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Gmp::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
\ No newline at end of file
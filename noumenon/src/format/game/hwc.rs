@@ -90,6 +90,68 @@ impl crate::format::external::Tga for Hwc {
 		let img = image::codecs::tga::TgaEncoder::new(writer);
 		img.write_image(&self.pixels, 64, 64, image::ColorType::Rgba8.into())?;
 		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Jpeg for Hwc {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		let img = image::ImageReader::with_format(BufReader::new(reader), image::ImageFormat::Jpeg)
+			.decode()?;
+		
+		Ok(Self {
+			pixels: img.into_rgba8().into_vec()[..64 * 64 * 4].try_into().unwrap(),
+		})
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		// jpeg has no alpha
+		let pixels = self.pixels.chunks_exact(4).flat_map(|v| [v[0], v[1], v[2]]).collect::<Vec<_>>();
+		let img = image::codecs::jpeg::JpegEncoder::new_with_quality(writer, 95);
+		img.write_image(&pixels, 64, 64, image::ColorType::Rgb8.into())?;
+		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Webp for Hwc {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		let img = image::ImageReader::with_format(BufReader::new(reader), image::ImageFormat::WebP)
+			.decode()?;
+		
+		Ok(Self {
+			pixels: img.into_rgba8().into_vec()[..64 * 64 * 4].try_into().unwrap(),
+		})
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		let img = image::codecs::webp::WebPEncoder::new_lossless(writer);
+		img.write_image(&self.pixels, 64, 64, image::ColorType::Rgba8.into())?;
+		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Bmp for Hwc {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		let img = image::ImageReader::with_format(BufReader::new(reader), image::ImageFormat::Bmp)
+			.decode()?;
+		
+		Ok(Self {
+			pixels: img.into_rgba8().into_vec()[..64 * 64 * 4].try_into().unwrap(),
+		})
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		let img = image::codecs::bmp::BmpEncoder::new(writer);
+		img.write_image(&self.pixels, 64, 64, image::ColorType::Rgba8.into())?;
+		
 		Ok(())
 	}
 }
\ No newline at end of file
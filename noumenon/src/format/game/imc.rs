@@ -0,0 +1,145 @@
+use std::io::{Read, Seek, Write};
+use binrw::{BinRead, BinWrite};
+
+pub const EXT: &'static [&'static str] = &["imc"];
+
+pub type Error = binrw::Error;
+
+#[derive(Debug, Clone)]
+pub struct Imc {
+	/// Which parts (slots) the variants contain an entry for, equipment and accessories use all 5, everything else only the first
+	pub part_mask: u16,
+	/// The first variant is the default one (variant 0), every variant has an entry per part
+	pub variants: Vec<Vec<ImcEntry>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, BinRead, BinWrite)]
+pub struct ImcEntry {
+	pub material_id: u8,
+	pub decal_id: u8,
+	/// Lower 10 bits are the attribute mask, upper 6 the sound id
+	pub attribute_and_sound: u16,
+	pub vfx_id: u8,
+	pub material_animation_id: u8,
+}
+
+impl ImcEntry {
+	pub fn attribute_mask(&self) -> u16 {
+		self.attribute_and_sound & 0x3FF
+	}
+	
+	pub fn sound_id(&self) -> u8 {
+		(self.attribute_and_sound >> 10) as u8
+	}
+}
+
+impl Imc {
+	pub fn part_count(&self) -> usize {
+		self.part_mask.count_ones() as usize
+	}
+	
+	/// Index of the part for an equipment or accessory slot, in the order they are stored in
+	pub fn part_index(slot: &str) -> Option<usize> {
+		match slot {
+			"Head" | "Ears" => Some(0),
+			"Body" | "Neck" => Some(1),
+			"Hands" | "Wrists" => Some(2),
+			"Legs" | "RFinger" => Some(3),
+			"Feet" | "LFinger" => Some(4),
+			_ => None,
+		}
+	}
+	
+	pub fn entry(&self, variant: usize, part: usize) -> Option<&ImcEntry> {
+		self.variants.get(variant)?.get(part)
+	}
+	
+	/// Sets the entry of a variant, variants that don't exist yet are added as copies of the default one
+	pub fn set_entry(&mut self, variant: usize, part: usize, entry: ImcEntry) -> Result<(), crate::Error> {
+		if part >= self.part_count() {
+			return Err(crate::Error::InvalidData(format!("Part {part} is out of range, the imc has {} parts", self.part_count())));
+		}
+		
+		if variant > u16::MAX as usize {
+			return Err(crate::Error::InvalidData(format!("Variant {variant} is out of range")));
+		}
+		
+		if variant >= self.variants.len() {
+			let default = self.variants.first().cloned().unwrap_or_else(|| vec![ImcEntry::default(); self.part_count()]);
+			self.variants.resize(variant + 1, default);
+		}
+		
+		self.variants[variant][part] = entry;
+		
+		Ok(())
+	}
+}
+
+impl BinRead for Imc {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
+		
+		let count = r!(u16);
+		let part_mask = r!(u16);
+		let part_count = part_mask.count_ones();
+		
+		// the count doesn't include the default variant
+		let mut variants = Vec::with_capacity(count as usize + 1);
+		for _ in 0..=count {
+			variants.push(r!(Vec<ImcEntry>, part_count));
+		}
+		
+		Ok(Self {
+			part_mask,
+			variants,
+		})
+	}
+}
+
+impl BinWrite for Imc {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		let part_count = self.part_count();
+		(self.variants.len().saturating_sub(1) as u16).write_options(writer, endian, ())?;
+		self.part_mask.write_options(writer, endian, ())?;
+		
+		// there's always atleast the default variant
+		let empty = vec![ImcEntry::default(); part_count];
+		let variants = if self.variants.len() > 0 {&self.variants[..]} else {std::slice::from_ref(&empty)};
+		for variant in variants {
+			for part in 0..part_count {
+				variant.get(part).copied().unwrap_or_default().write_options(writer, endian, ())?;
+			}
+		}
+		
+		Ok(())
+	}
+}
+
+impl crate::File for Imc {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Imc::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Imc {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Imc {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Imc::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
\ No newline at end of file
@@ -4,6 +4,8 @@ use binrw::{binrw, BinRead, BinWrite};
 use glam::Vec4Swizzles;
 use crate::{format::external::gltf::{MaterialBake, MaterialBakeTexture}, NullReader};
 
+mod edit;
+
 pub const EXT: &'static [&'static str] = &["mdl"];
 
 pub type Error = binrw::Error;
@@ -17,6 +19,10 @@ pub struct Lod {
 pub struct Mesh {
 	pub material: String,
 	pub submeshes: Vec<Submesh>,
+	
+	// kept around so that writing the mesh back results in the same layout, empty for new meshes
+	vertex_declaration: Vec<VertexElementRaw>,
+	bone_table: Vec<u16>,
 }
 
 #[derive(Debug, Clone)]
@@ -25,6 +31,8 @@ pub struct Submesh {
 	pub indices: Vec<u16>,
 	pub attributes: Vec<String>,
 	pub shapes: Vec<Shape>,
+	
+	bone_map: Vec<u16>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -60,6 +68,9 @@ pub struct ShapeValue {
 pub struct Mdl {
 	pub lods: Vec<Lod>,
 	pub bones: Vec<String>,
+	
+	// everything we dont expose but still want to write back, None for new models
+	raw: Option<RetainedRaw>,
 }
 
 impl Mdl {
@@ -217,6 +228,35 @@ impl Mdl {
 				)
 			}).collect::<HashMap<_, _>>()
 	}
+	
+	// the per bone boxes only contain the vertices weighted to that bone
+	fn calculate_bounding_boxes(&self) -> ([BoundingBoxRaw; 4], Vec<BoundingBoxRaw>) {
+		let mut bb = (glam::Vec3::MAX, glam::Vec3::MIN);
+		let mut bones_bb = vec![(glam::Vec3::MAX, glam::Vec3::MIN); self.bones.len()];
+		for vertex in self.lods.iter().flat_map(|v| &v.meshes).flat_map(|v| &v.submeshes).flat_map(|v| &v.vertices) {
+			bb = (bb.0.min(vertex.position), bb.1.max(vertex.position));
+			for blend in &vertex.blends {
+				if blend.weight <= 0.0 {continue}
+				let Some(bone_bb) = bones_bb.get_mut(blend.bone as usize) else {continue};
+				*bone_bb = (bone_bb.0.min(vertex.position), bone_bb.1.max(vertex.position));
+			}
+		}
+		
+		let to_raw = |(min, max): (glam::Vec3, glam::Vec3)| if min.x > max.x {
+			BoundingBoxRaw::default()
+		} else {
+			BoundingBoxRaw {
+				min: min.extend(1.0).to_array(),
+				max: max.extend(1.0).to_array(),
+			}
+		};
+		
+		let bb = to_raw(bb);
+		(
+			[bb.clone(), bb, BoundingBoxRaw::default(), BoundingBoxRaw::default()],
+			bones_bb.into_iter().map(to_raw).collect(),
+		)
+	}
 }
 
 impl BinRead for Mdl {
@@ -333,7 +373,7 @@ impl BinRead for Mdl {
 											vertex.position = val.xyz(),
 										
 										VertexUsageRaw::BlendWeights =>
-											for i in 0..4 {vertex.blends[i].weight = val[i] / 255.0},
+											for i in 0..4 {vertex.blends[i].weight = val[i]},
 										
 										VertexUsageRaw::BlendIndices =>
 											for i in 0..4 {vertex.blends[i].bone = bone_table[mesh_raw.bone_table_index as usize][val[i] as usize] as u8},
@@ -426,12 +466,21 @@ impl BinRead for Mdl {
 						indices: indices_new,
 						attributes: attributes_new,
 						shapes: shapes_new,
+						bone_map: submesh_bone_map
+							.get(submesh_raw.bone_start_index as usize..(submesh_raw.bone_start_index + submesh_raw.bone_count) as usize)
+							.map_or_else(|| Vec::new(), |v| v.to_vec()),
 					});
 				}
 				
 				meshes_new.push(Mesh {
 					material: strings_buf[material_string_offset[mesh_raw.material_index as usize] as usize..].null_terminated().unwrap(),
 					submeshes: submeshes_new,
+					vertex_declaration: vertex_decl
+						.iter()
+						.take_while(|v| v.stream != 255)
+						.cloned()
+						.collect(),
+					bone_table: bone_table.get(mesh_raw.bone_table_index as usize).cloned().unwrap_or_default(),
 				});
 			}
 			
@@ -440,12 +489,124 @@ impl BinRead for Mdl {
 			});
 		}
 		
+		let string = |offset: u32| strings_buf[offset as usize..].null_terminated().unwrap();
+		
+		// meshes that aren't drawn normally (water, shadow, ...) aren't parsed, they are kept as is so they survive a roundtrip
+		let mut special_meshes = Vec::new();
+		let mut terrain_shadow_meshes_new = Vec::new();
+		let mut edge_geometry = Vec::new();
+		for (lod_index, lod_raw) in lods.iter().enumerate() {
+			let regular = lod_raw.mesh_index as usize..(lod_raw.mesh_index + lod_raw.mesh_count) as usize;
+			let mut mesh_indices = special_mesh_ranges(lod_raw, extra_lods.get(lod_index))
+				.into_iter()
+				.flat_map(|(start, count)| start as usize..start as usize + count as usize)
+				.filter(|v| !regular.contains(v))
+				.collect::<Vec<_>>();
+			mesh_indices.sort();
+			mesh_indices.dedup();
+			
+			let mut meshes_new = Vec::new();
+			for mesh_index in mesh_indices {
+				let mesh_raw = meshes.get(mesh_index).ok_or_else(|| binrw::Error::AssertFail{pos: 0, message: format!("Lod {lod_index} uses mesh {mesh_index} while there are {}", meshes.len())})?;
+				let mut streams = [Vec::new(), Vec::new(), Vec::new()];
+				for (stream, data) in streams.iter_mut().enumerate() {
+					let size = mesh_raw.vertex_count as usize * mesh_raw.vertex_buffer_stride[stream] as usize;
+					if size == 0 {continue}
+					r!(seek header.vertex_offsets[lod_index] as u64 + mesh_raw.vertex_buffer_offset[stream] as u64);
+					*data = r!(Vec<u8>, size);
+				}
+				
+				r!(seek header.index_offsets[lod_index] as u64 + mesh_raw.start_index as u64 * 2);
+				let indices = r!(Vec<u16>, mesh_raw.index_count);
+				
+				meshes_new.push(SpecialMeshRaw {
+					index: mesh_index as u16,
+					mesh: mesh_raw.clone(),
+					material: material_string_offset.get(mesh_raw.material_index as usize).map(|v| string(*v)),
+					declaration: vertex_declerations
+						.get(mesh_index)
+						.map_or_else(|| Vec::new(), |v| v.iter().take_while(|v| v.stream != 255).cloned().collect()),
+					bone_table: bone_table.get(mesh_raw.bone_table_index as usize).cloned(),
+					streams,
+					indices,
+					submeshes: submeshes
+						.get(mesh_raw.submesh_index as usize..(mesh_raw.submesh_index + mesh_raw.submesh_count) as usize)
+						.unwrap_or_default()
+						.iter()
+						.map(|v| SpecialSubmeshRaw {
+							index_offset: v.index_offset.saturating_sub(mesh_raw.start_index),
+							index_count: v.index_count,
+							attributes: (0..attribute_string_offset.len())
+								.filter(|i| v.attribute_index_mask & (1 << i) != 0)
+								.map(|i| string(attribute_string_offset[i]))
+								.collect(),
+							bone_map: submesh_bone_map
+								.get(v.bone_start_index as usize..(v.bone_start_index + v.bone_count) as usize)
+								.map_or_else(|| Vec::new(), |v| v.to_vec()),
+						}).collect(),
+				});
+			}
+			special_meshes.push(meshes_new);
+			
+			let mut shadow_meshes = Vec::new();
+			for mesh_index in lod_raw.terrain_shadow_mesh_index as usize..(lod_raw.terrain_shadow_mesh_index + lod_raw.terrain_shadow_mesh_count) as usize {
+				let mesh_raw = terrain_shadow_meshes.get(mesh_index).ok_or_else(|| binrw::Error::AssertFail{pos: 0, message: format!("Lod {lod_index} uses terrain shadow mesh {mesh_index} while there are {}", terrain_shadow_meshes.len())})?;
+				r!(seek header.vertex_offsets[lod_index] as u64 + mesh_raw.vertex_buffer_offset as u64);
+				let vertices = r!(Vec<u8>, mesh_raw.vertex_count as usize * mesh_raw.vertex_buffer_stride as usize);
+				r!(seek header.index_offsets[lod_index] as u64 + mesh_raw.start_index as u64 * 2);
+				let indices = r!(Vec<u16>, mesh_raw.index_count);
+				
+				shadow_meshes.push(TerrainShadowMesh {
+					mesh: mesh_raw.clone(),
+					vertices,
+					indices,
+					submeshes: terrain_shadow_submeshes
+						.get(mesh_raw.submesh_index as usize..(mesh_raw.submesh_index + mesh_raw.submesh_count) as usize)
+						.unwrap_or_default()
+						.iter()
+						.map(|v| {
+							let mut v = v.clone();
+							v.index_offset = v.index_offset.saturating_sub(mesh_raw.start_index);
+							v
+						}).collect(),
+				});
+			}
+			terrain_shadow_meshes_new.push(shadow_meshes);
+			
+			if lod_raw.edge_geometry_size > 0 {
+				r!(seek lod_raw.edge_geometry_data_offset);
+				edge_geometry.push(r!(Vec<u8>, lod_raw.edge_geometry_size));
+			} else {
+				edge_geometry.push(Vec::new());
+			}
+		}
+		
 		Ok(Self {
 			lods: lods_new,
 			bones: bone_string_offset
 				.into_iter()
-				.map(|v| strings_buf[v as usize..].null_terminated().unwrap())
+				.map(|v| string(v))
 				.collect(),
+			raw: Some(RetainedRaw {
+				version: header.version,
+				index_buffer_streaming: header.index_buffer_streaming,
+				edge_geometry_flag: header.edge_geometry,
+				lod_count: header.lod_count,
+				attributes: attribute_string_offset.into_iter().map(|v| string(v)).collect(),
+				materials: material_string_offset.into_iter().map(|v| string(v)).collect(),
+				shapes: shapes.iter().map(|v| string(v.string_offset)).collect(),
+				element_ids: element_ids.into_iter().map(|v| (string(v.parent_bone_name), v)).collect(),
+				model_header,
+				lods,
+				extra_lods,
+				neck_morphs: neck_morth,
+				face_shadow_data: unkown_face_shadow_data,
+				bounding_boxes: [bb, model_bb, water_bb, vertical_fog_bb],
+				bone_bounding_boxes: bones_bb,
+				special_meshes,
+				terrain_shadow_meshes: terrain_shadow_meshes_new,
+				edge_geometry,
+			}),
 		})
 	}
 }
@@ -454,13 +615,564 @@ impl BinWrite for Mdl {
 	type Args<'a> = ();
 	
 	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
-		todo!();
+		let start = writer.stream_position()?;
+		let err = |message: String| binrw::Error::AssertFail{pos: start, message};
+		let raw = self.raw.as_ref();
+		let version = raw.map_or(0x01000005, |v| v.version);
+		let lods = &self.lods[..self.lods.len().min(3)];
+		let submeshes_iter = || lods.iter().flat_map(|v| &v.meshes).flat_map(|v| &v.submeshes);
+		
+		let special_meshes_iter = || raw.iter().flat_map(|v| &v.special_meshes).flatten();
+		
+		// strings, anything that was already in the file keeps its original order
+		let attributes = ordered_unique(raw.map_or(&[], |v| &v.attributes), submeshes_iter().flat_map(|v| &v.attributes)
+			.chain(special_meshes_iter().flat_map(|v| &v.submeshes).flat_map(|v| &v.attributes)));
+		let materials = ordered_unique(raw.map_or(&[], |v| &v.materials), lods.iter().flat_map(|v| &v.meshes).map(|v| &v.material)
+			.chain(special_meshes_iter().filter_map(|v| v.material.as_ref())));
+		let shapes = ordered_unique(raw.map_or(&[], |v| &v.shapes), submeshes_iter().flat_map(|v| &v.shapes).map(|v| &v.name));
+		if attributes.len() > 32 {
+			return Err(err(format!("Model has {} attributes while the max is 32", attributes.len())));
+		}
+		
+		let mut strings_buf = Vec::new();
+		let mut string_offsets = HashMap::new();
+		for s in attributes.iter()
+			.chain(&self.bones)
+			.chain(&materials)
+			.chain(&shapes)
+			.chain(raw.iter().flat_map(|v| v.element_ids.iter().map(|v| &v.0))) {
+			string_offsets.entry(s.as_str()).or_insert_with(|| {
+				let offset = strings_buf.len() as u32;
+				strings_buf.extend_from_slice(s.as_bytes());
+				strings_buf.push(0);
+				offset
+			});
+		}
+		let strings_count = string_offsets.len();
+		strings_buf.resize(strings_buf.len().next_multiple_of(4), 0);
+		
+		// meshes and their buffers
+		let mut declarations = Vec::new();
+		let mut meshes_raw = Vec::new();
+		let mut submeshes_raw = Vec::new();
+		let mut bone_tables = Vec::new();
+		let mut submesh_bone_map = Vec::new();
+		let mut shape_meshes_tmp = vec![[Vec::new(), Vec::new(), Vec::new()]; shapes.len()];
+		let mut lods_raw = Vec::new();
+		let mut special_remaps = Vec::new();
+		let mut terrain_shadow_meshes_raw = Vec::new();
+		let mut terrain_shadow_submeshes_raw = Vec::new();
+		let mut buffers = Vec::new();
+		for lod_index in 0..3 {
+			let mesh_index = meshes_raw.len();
+			let mut vertex_data = Vec::new();
+			let mut index_data = Vec::<u16>::new();
+			
+			for mesh in lods.get(lod_index).map_or(&[][..], |v| &v.meshes) {
+				let declaration = if mesh.vertex_declaration.len() > 0 {
+					mesh.vertex_declaration.clone()
+				} else {
+					let blend_count = if self.bones.len() > 0 {
+						mesh.submeshes
+							.iter()
+							.flat_map(|v| &v.vertices)
+							.filter_map(|v| v.blends.iter().rposition(|v| v.weight > 0.0))
+							.max()
+							.map_or(0, |v| v + 1)
+					} else {0};
+					
+					default_vertex_declaration(blend_count)
+				};
+				
+				let mut strides = [0u8; 3];
+				for element in &declaration {
+					let stride = &mut strides[element.stream as usize];
+					*stride = (*stride).max(element.offset + element.typ.size());
+				}
+				
+				let uses_bones = declaration.iter().any(|v| matches!(v.usage, VertexUsageRaw::BlendIndices));
+				let mut bone_table = mesh.bone_table.clone();
+				if uses_bones {
+					for blend in mesh.submeshes.iter().flat_map(|v| &v.vertices).flat_map(|v| &v.blends) {
+						if blend.weight > 0.0 && !bone_table.contains(&(blend.bone as u16)) {
+							bone_table.push(blend.bone as u16);
+						}
+					}
+					
+					if bone_table.len() == 0 {
+						bone_table.push(0);
+					}
+					
+					if version & 0xFF == 5 && bone_table.len() > 64 {
+						return Err(err(format!("Mesh uses {} bones while the max is 64", bone_table.len())));
+					}
+				}
+				
+				// merge the submesh vertices back into a single buffer
+				let mut streams = [Vec::new(), Vec::new(), Vec::new()];
+				let mut vertex_ids = HashMap::new();
+				let mut indices = Vec::new();
+				let mut shape_values = HashMap::<usize, Vec<ShapeValueRaw>>::new();
+				let submesh_index = submeshes_raw.len();
+				for submesh in &mesh.submeshes {
+					let index_offset = indices.len();
+					let mut vertex_map = Vec::with_capacity(submesh.vertices.len());
+					for vertex in &submesh.vertices {
+						let data = encode_vertex(vertex, &declaration, strides, &bone_table, endian)?;
+						let next_id = vertex_ids.len();
+						let id = *vertex_ids.entry(data.concat()).or_insert_with(|| {
+							for (stream, data) in streams.iter_mut().zip(data) {
+								stream.extend_from_slice(&data);
+							}
+							
+							next_id
+						});
+						
+						vertex_map.push(id);
+					}
+					
+					if vertex_ids.len() > u16::MAX as usize {
+						return Err(err(format!("Mesh has {} vertices while the max is {}", vertex_ids.len(), u16::MAX)));
+					}
+					
+					indices.extend(submesh.indices.iter().map(|v| vertex_map[*v as usize] as u16));
+					
+					for shape in &submesh.shapes {
+						let shape_index = shapes.iter().position(|v| *v == shape.name).unwrap();
+						shape_values.entry(shape_index).or_default().extend(shape.values.iter().map(|v| ShapeValueRaw {
+							base_indices_index: (index_offset + v.index as usize) as u16,
+							replacing_vertex_index: vertex_map[v.new_vertex as usize] as u16,
+						}));
+					}
+					
+					submeshes_raw.push(SubmeshRaw {
+						index_offset: (index_data.len() + index_offset) as u32,
+						index_count: submesh.indices.len() as u32,
+						attribute_index_mask: submesh.attributes
+							.iter()
+							.fold(0, |mask, attr| mask | 1 << attributes.iter().position(|v| v == attr).unwrap()),
+						bone_start_index: submesh_bone_map.len() as u16,
+						bone_count: submesh.bone_map.len() as u16,
+					});
+					
+					submesh_bone_map.extend_from_slice(&submesh.bone_map);
+				}
+				
+				let mut vertex_buffer_offset = [0u32; 3];
+				for (stream, data) in streams.iter().enumerate() {
+					if data.len() == 0 {continue}
+					vertex_buffer_offset[stream] = vertex_data.len() as u32;
+					vertex_data.extend_from_slice(data);
+				}
+				
+				for (shape_index, values) in shape_values {
+					shape_meshes_tmp[shape_index][lod_index].push((index_data.len() as u32, values));
+				}
+				
+				meshes_raw.push(MeshRaw {
+					vertex_count: vertex_ids.len() as u16,
+					_padding: 0,
+					index_count: indices.len() as u32,
+					material_index: materials.iter().position(|v| *v == mesh.material).unwrap() as u16,
+					submesh_index: submesh_index as u16,
+					submesh_count: mesh.submeshes.len() as u16,
+					bone_table_index: if uses_bones {bone_tables.len() as u16} else {255},
+					start_index: index_data.len() as u32,
+					vertex_buffer_offset,
+					vertex_buffer_stride: strides,
+					vertex_stream_count: strides.iter().filter(|v| **v > 0).count() as u8,
+				});
+				
+				if uses_bones {
+					bone_tables.push(bone_table);
+				}
+				
+				declarations.push(declaration);
+				
+				// each mesh starts 16 byte aligned
+				index_data.extend_from_slice(&indices);
+				index_data.resize(index_data.len().next_multiple_of(8), 0);
+			}
+			
+			// meshes that aren't drawn normally are written back as they were read, after the regular ones
+			let regular_end = meshes_raw.len() as u16;
+			let mut remap = HashMap::new();
+			for mesh in raw.and_then(|v| v.special_meshes.get(lod_index)).map_or(&[][..], |v| v) {
+				remap.insert(mesh.index, meshes_raw.len() as u16);
+				let mut mesh_raw = mesh.mesh.clone();
+				for (stream, data) in mesh.streams.iter().enumerate() {
+					if data.len() == 0 {continue}
+					mesh_raw.vertex_buffer_offset[stream] = vertex_data.len() as u32;
+					vertex_data.extend_from_slice(data);
+				}
+				
+				mesh_raw.start_index = index_data.len() as u32;
+				if let Some(material) = &mesh.material {
+					mesh_raw.material_index = materials.iter().position(|v| v == material).unwrap() as u16;
+				}
+				
+				if let Some(bone_table) = &mesh.bone_table {
+					mesh_raw.bone_table_index = bone_tables.len() as u16;
+					bone_tables.push(bone_table.clone());
+				}
+				
+				mesh_raw.submesh_index = submeshes_raw.len() as u16;
+				for submesh in &mesh.submeshes {
+					submeshes_raw.push(SubmeshRaw {
+						index_offset: mesh_raw.start_index + submesh.index_offset,
+						index_count: submesh.index_count,
+						attribute_index_mask: submesh.attributes
+							.iter()
+							.fold(0, |mask, attr| mask | 1 << attributes.iter().position(|v| v == attr).unwrap()),
+						bone_start_index: submesh_bone_map.len() as u16,
+						bone_count: submesh.bone_map.len() as u16,
+					});
+					
+					submesh_bone_map.extend_from_slice(&submesh.bone_map);
+				}
+				
+				meshes_raw.push(mesh_raw);
+				declarations.push(mesh.declaration.clone());
+				index_data.extend_from_slice(&mesh.indices);
+				index_data.resize(index_data.len().next_multiple_of(8), 0);
+			}
+			
+			let terrain_shadow_index = terrain_shadow_meshes_raw.len() as u16;
+			for mesh in raw.and_then(|v| v.terrain_shadow_meshes.get(lod_index)).map_or(&[][..], |v| v) {
+				let mut mesh_raw = mesh.mesh.clone();
+				mesh_raw.vertex_buffer_offset = vertex_data.len() as u32;
+				vertex_data.extend_from_slice(&mesh.vertices);
+				mesh_raw.start_index = index_data.len() as u32;
+				mesh_raw.submesh_index = terrain_shadow_submeshes_raw.len() as u16;
+				for submesh in &mesh.submeshes {
+					let mut submesh = submesh.clone();
+					submesh.index_offset += mesh_raw.start_index;
+					terrain_shadow_submeshes_raw.push(submesh);
+				}
+				
+				terrain_shadow_meshes_raw.push(mesh_raw);
+				index_data.extend_from_slice(&mesh.indices);
+				index_data.resize(index_data.len().next_multiple_of(8), 0);
+			}
+			
+			let mesh_end = meshes_raw.len() as u16;
+			let map_range = |(start, count): (u16, u16)| (remap.get(&start).copied().unwrap_or(if count == 0 {regular_end} else {mesh_end}), count);
+			let mut lod_raw = raw.and_then(|v| v.lods.get(lod_index)).cloned().unwrap_or_default();
+			lod_raw.mesh_index = mesh_index as u16;
+			lod_raw.mesh_count = regular_end - mesh_index as u16;
+			(lod_raw.water_mesh_index, lod_raw.water_mesh_count) = map_range((lod_raw.water_mesh_index, lod_raw.water_mesh_count));
+			(lod_raw.shadow_mesh_index, lod_raw.shadow_mesh_count) = map_range((lod_raw.shadow_mesh_index, lod_raw.shadow_mesh_count));
+			(lod_raw.vertical_fog_mesh_index, lod_raw.vertical_fog_mesh_count) = map_range((lod_raw.vertical_fog_mesh_index, lod_raw.vertical_fog_mesh_count));
+			lod_raw.terrain_shadow_mesh_index = terrain_shadow_index;
+			lod_raw.terrain_shadow_mesh_count = terrain_shadow_meshes_raw.len() as u16 - terrain_shadow_index;
+			lod_raw.vertex_buffer_size = vertex_data.len() as u32;
+			lod_raw.index_buffer_size = index_data.len() as u32 * 2;
+			lods_raw.push(lod_raw);
+			special_remaps.push((remap, regular_end, mesh_end));
+			buffers.push((vertex_data, index_data));
+		}
+		
+		// shapes
+		let mut shapes_raw = Vec::new();
+		let mut shape_meshes_raw = Vec::new();
+		let mut shape_values_raw = Vec::new();
+		for (shape_index, lod_meshes) in shape_meshes_tmp.into_iter().enumerate() {
+			let mut shape_raw = ShapeRaw {
+				string_offset: string_offsets[shapes[shape_index].as_str()],
+				mesh_start_index: [0; 3],
+				mesh_count: [0; 3],
+			};
+			
+			for (lod_index, mut meshes) in lod_meshes.into_iter().enumerate() {
+				meshes.sort_by_key(|v| v.0);
+				shape_raw.mesh_start_index[lod_index] = shape_meshes_raw.len() as u16;
+				shape_raw.mesh_count[lod_index] = meshes.len() as u16;
+				for (mesh_index_offset, values) in meshes {
+					shape_meshes_raw.push(ShapeMeshRaw {
+						mesh_index_offset,
+						value_count: values.len() as u32,
+						value_offset: shape_values_raw.len() as u32,
+					});
+					
+					shape_values_raw.extend(values);
+				}
+			}
+			
+			shapes_raw.push(shape_raw);
+		}
+		
+		// header, the offsets and sizes get filled in once the buffers are written
+		let lod_count = raw.map_or_else(|| lods.iter().take_while(|v| v.meshes.len() > 0).count().max(1) as u8, |v| v.lod_count);
+		let mut header = HeaderRaw {
+			version,
+			stack_size: (declarations.len() * 17 * 8) as u32,
+			runtime_size: 0,
+			vertex_declaration_count: declarations.len() as u16,
+			material_count: materials.len() as u16,
+			vertex_offsets: [0; 3],
+			index_offsets: [0; 3],
+			vertex_buffer_offsets: [0; 3],
+			index_buffer_offsets: [0; 3],
+			lod_count,
+			index_buffer_streaming: raw.map_or(0, |v| v.index_buffer_streaming),
+			edge_geometry: raw.map_or(0, |v| v.edge_geometry_flag),
+			_padding: 0,
+		};
+		header.write_options(writer, endian, ())?;
+		
+		for declaration in &declarations {
+			let element = |stream: u8| VertexElementRaw {
+				stream,
+				offset: 0,
+				typ: VertexTypeRaw::F32x1,
+				usage: VertexUsageRaw::Position,
+				usage_index: 0,
+				_padding: [0; 3],
+			};
+			
+			for i in 0..17 {
+				match declaration.get(i) {
+					Some(v) if i < 16 => v.write_options(writer, endian, ())?,
+					_ if i == declaration.len().min(16) => element(255).write_options(writer, endian, ())?,
+					_ => element(0).write_options(writer, endian, ())?,
+				}
+			}
+		}
+		
+		let runtime_start = writer.stream_position()?;
+		(strings_count as u16).write_options(writer, endian, ())?;
+		0u16.write_options(writer, endian, ())?;
+		(strings_buf.len() as u32).write_options(writer, endian, ())?;
+		strings_buf.write_options(writer, endian, ())?;
+		
+		// retained ones are written as is, recalculate_bounding_boxes updates them after editing meshes
+		let bounding_boxes = raw.map_or_else(|| self.calculate_bounding_boxes(), |v| (v.bounding_boxes.clone(), v.bone_bounding_boxes.clone()));
+		let (bounding_boxes, bone_bounding_boxes) = if bounding_boxes.1.len() == self.bones.len() {
+			bounding_boxes
+		} else {
+			(bounding_boxes.0, self.calculate_bounding_boxes().1)
+		};
+		
+		let mut model_header = raw.map_or_else(|| ModelHeaderRaw::default(), |v| v.model_header.clone());
+		if raw.is_none() {
+			model_header.radius = edit::radius(&bounding_boxes[0]);
+		}
+		
+		let remap_material = |index: u8| -> u8 {
+			let Some(raw) = raw else {return index};
+			let Some(material) = raw.materials.get(index as usize) else {return index};
+			materials.iter().position(|v| v == material).map_or(255, |v| v as u8)
+		};
+		
+		let extra_lods = raw.map_or(&[][..], |v| &v.extra_lods);
+		model_header.flags2.set(ModelFlags2Raw::EXTRA_LOD_ENABLED, extra_lods.len() == 3);
+		model_header.mesh_count = meshes_raw.len() as u16;
+		model_header.attribute_count = attributes.len() as u16;
+		model_header.submesh_count = submeshes_raw.len() as u16;
+		model_header.material_count = materials.len() as u16;
+		model_header.bone_count = self.bones.len() as u16;
+		model_header.bone_table_count = bone_tables.len() as u16;
+		model_header.shape_count = shapes_raw.len() as u16;
+		model_header.shape_mesh_count = shape_meshes_raw.len() as u16;
+		model_header.shape_value_count = shape_values_raw.len() as u16;
+		model_header.lod_count = lod_count;
+		model_header.element_id_count = raw.map_or(0, |v| v.element_ids.len() as u16);
+		model_header.terrain_shadow_mesh_count = terrain_shadow_meshes_raw.len() as u8;
+		model_header.terrain_shadow_submesh_count = terrain_shadow_submeshes_raw.len() as u16;
+		model_header.bg_change_material_index = remap_material(model_header.bg_change_material_index);
+		model_header.bg_crest_change_material_index = remap_material(model_header.bg_crest_change_material_index);
+		model_header.neck_morph_count = raw.map_or(0, |v| v.neck_morphs.len() as u8);
+		model_header.unknown_face_shadow_data_count = raw.map_or(0, |v| v.face_shadow_data.len() as u16);
+		if version & 0xFF == 6 {
+			model_header.bone_table_array_count_total = bone_tables.iter().map(|v| v.len().next_multiple_of(2) as u16).sum();
+		}
+		model_header.write_options(writer, endian, ())?;
+		
+		for (name, element_id) in raw.iter().flat_map(|v| &v.element_ids) {
+			let mut element_id = element_id.clone();
+			element_id.parent_bone_name = string_offsets[name.as_str()];
+			element_id.write_options(writer, endian, ())?;
+		}
+		
+		let lods_pos = writer.stream_position()?;
+		lods_raw.write_options(writer, endian, ())?;
+		
+		for ((remap, regular_end, mesh_end), extra_lod) in special_remaps.iter().zip(extra_lods) {
+			let map_range = |(start, count): (u16, u16)| (remap.get(&start).copied().unwrap_or(if count == 0 {*regular_end} else {*mesh_end}), count);
+			let mut extra_lod = extra_lod.clone();
+			(extra_lod.lightshaft_mesh_index, extra_lod.lightshaft_mesh_count) = map_range((extra_lod.lightshaft_mesh_index, extra_lod.lightshaft_mesh_count));
+			(extra_lod.glass_mesh_index, extra_lod.glass_mesh_count) = map_range((extra_lod.glass_mesh_index, extra_lod.glass_mesh_count));
+			(extra_lod.material_change_mesh_index, extra_lod.material_change_mesh_count) = map_range((extra_lod.material_change_mesh_index, extra_lod.material_change_mesh_count));
+			(extra_lod.crest_change_mesh_index, extra_lod.crest_change_mesh_count) = map_range((extra_lod.crest_change_mesh_index, extra_lod.crest_change_mesh_count));
+			extra_lod.write_options(writer, endian, ())?;
+		}
+		
+		meshes_raw.write_options(writer, endian, ())?;
+		for attribute in &attributes {
+			string_offsets[attribute.as_str()].write_options(writer, endian, ())?;
+		}
+		terrain_shadow_meshes_raw.write_options(writer, endian, ())?;
+		submeshes_raw.write_options(writer, endian, ())?;
+		terrain_shadow_submeshes_raw.write_options(writer, endian, ())?;
+		for material in &materials {
+			string_offsets[material.as_str()].write_options(writer, endian, ())?;
+		}
+		for bone in &self.bones {
+			string_offsets[bone.as_str()].write_options(writer, endian, ())?;
+		}
+		bone_table_writer(&bone_tables, writer, endian, (version,))?;
+		shapes_raw.write_options(writer, endian, ())?;
+		shape_meshes_raw.write_options(writer, endian, ())?;
+		shape_values_raw.write_options(writer, endian, ())?;
+		(submesh_bone_map.len() as u32 * 2).write_options(writer, endian, ())?;
+		submesh_bone_map.write_options(writer, endian, ())?;
+		raw.map_or(&[][..], |v| &v.neck_morphs).write_options(writer, endian, ())?;
+		raw.map_or(&[][..], |v| &v.face_shadow_data).write_options(writer, endian, ())?;
+		
+		// bounding boxes are 8 byte aligned
+		let padding = (8 - (writer.stream_position()? - start + 1) % 8) % 8;
+		(padding as u8).write_options(writer, endian, ())?;
+		vec![0u8; padding as usize].write_options(writer, endian, ())?;
+		
+		bounding_boxes.write_options(writer, endian, ())?;
+		bone_bounding_boxes.write_options(writer, endian, ())?;
+		header.runtime_size = (writer.stream_position()? - runtime_start) as u32;
+		
+		for (lod_index, (vertex_data, index_data)) in buffers.into_iter().enumerate() {
+			let lod_raw = &mut lods_raw[lod_index];
+			
+			let pos = (writer.stream_position()? - start) as u32;
+			header.vertex_offsets[lod_index] = pos;
+			header.vertex_buffer_offsets[lod_index] = vertex_data.len() as u32;
+			lod_raw.vertex_data_offset = pos;
+			vertex_data.write_options(writer, endian, ())?;
+			
+			let pos = (writer.stream_position()? - start) as u32;
+			header.index_offsets[lod_index] = pos;
+			header.index_buffer_offsets[lod_index] = index_data.len() as u32 * 2;
+			lod_raw.index_data_offset = pos;
+			index_data.write_options(writer, endian, ())?;
+			
+			let edge_geometry = raw.and_then(|v| v.edge_geometry.get(lod_index)).map_or(&[][..], |v| v);
+			lod_raw.edge_geometry_size = edge_geometry.len() as u32;
+			lod_raw.edge_geometry_data_offset = if edge_geometry.len() > 0 {(writer.stream_position()? - start) as u32} else {0};
+			edge_geometry.write_options(writer, endian, ())?;
+		}
+		
+		let end = writer.stream_position()?;
+		writer.seek(SeekFrom::Start(start))?;
+		header.write_options(writer, endian, ())?;
+		writer.seek(SeekFrom::Start(lods_pos))?;
+		lods_raw.write_options(writer, endian, ())?;
+		writer.seek(SeekFrom::Start(end))?;
+		
+		Ok(())
 	}
 }
 
-impl ironworks::file::File for Mdl {
-	fn read(mut data: impl ironworks::FileStream) -> Result<Self, ironworks::Error> {
-		Mdl::read_le(&mut data).map_err(|e| ironworks::Error::Resource(e.into()))
+// keeps the order of the values that were in the original file and appends new ones
+fn ordered_unique<'a>(original: &[String], used: impl Iterator<Item = &'a String>) -> Vec<String> {
+	let used = used.collect::<Vec<_>>();
+	let mut values = original
+		.iter()
+		.filter(|v| used.contains(v))
+		.cloned()
+		.collect::<Vec<_>>();
+	
+	for v in used {
+		if !values.contains(v) {
+			values.push(v.clone());
+		}
+	}
+	
+	values
+}
+
+fn default_vertex_declaration(blend_count: usize) -> Vec<VertexElementRaw> {
+	let element = |stream: u8, offset: u8, typ: VertexTypeRaw, usage: VertexUsageRaw| VertexElementRaw {
+		stream,
+		offset,
+		typ,
+		usage,
+		usage_index: 0,
+		_padding: [0; 3],
+	};
+	
+	let mut declaration = vec![element(0, 0, VertexTypeRaw::F32x3, VertexUsageRaw::Position)];
+	if blend_count > 4 {
+		declaration.push(element(0, 12, VertexTypeRaw::U16x4, VertexUsageRaw::BlendWeights));
+		declaration.push(element(0, 20, VertexTypeRaw::U16x4, VertexUsageRaw::BlendIndices));
+	} else if blend_count > 0 {
+		declaration.push(element(0, 12, VertexTypeRaw::F8x4, VertexUsageRaw::BlendWeights));
+		declaration.push(element(0, 16, VertexTypeRaw::U8x4, VertexUsageRaw::BlendIndices));
+	}
+	
+	declaration.push(element(1, 0, VertexTypeRaw::F32x3, VertexUsageRaw::Normal));
+	declaration.push(element(1, 12, VertexTypeRaw::F8x4, VertexUsageRaw::Tangent1));
+	declaration.push(element(1, 16, VertexTypeRaw::F8x4, VertexUsageRaw::Color));
+	declaration.push(element(1, 20, VertexTypeRaw::F32x4, VertexUsageRaw::Uv));
+	declaration
+}
+
+// the inverse of what the reader does, bones are turned back into indices of the mesh bone table
+fn encode_vertex(vertex: &Vertex, declaration: &[VertexElementRaw], strides: [u8; 3], bone_table: &[u16], endian: binrw::Endian) -> binrw::BinResult<[Vec<u8>; 3]> {
+	let mut streams = strides.map(|v| Cursor::new(vec![0u8; v as usize]));
+	let weight = |blend: &Blend| (blend.weight * 255.0).round() as u8;
+	let bone = |blend: &Blend| bone_table.iter().position(|v| *v == blend.bone as u16).unwrap_or(0) as u8;
+	
+	for element in declaration {
+		let writer = &mut streams[element.stream as usize];
+		writer.seek(SeekFrom::Start(element.offset as u64))?;
+		
+		match (element.usage, element.typ) {
+			(VertexUsageRaw::BlendWeights, VertexTypeRaw::U16x4) =>
+				vertex.blends.iter().map(weight).collect::<Vec<_>>().write_options(writer, endian, ())?,
+			
+			(VertexUsageRaw::BlendWeights, VertexTypeRaw::U8x4) =>
+				vertex.blends[..4].iter().map(weight).collect::<Vec<_>>().write_options(writer, endian, ())?,
+			
+			(VertexUsageRaw::BlendIndices, VertexTypeRaw::U16x4) =>
+				vertex.blends.iter().map(bone).collect::<Vec<_>>().write_options(writer, endian, ())?,
+			
+			(VertexUsageRaw::BlendIndices, VertexTypeRaw::U8x4) =>
+				vertex.blends[..4].iter().map(bone).collect::<Vec<_>>().write_options(writer, endian, ())?,
+			
+			_ => {
+				let val = match element.usage {
+					VertexUsageRaw::Position => vertex.position.extend(1.0),
+					VertexUsageRaw::BlendWeights => glam::Vec4::from_array(std::array::from_fn(|i| vertex.blends[i].weight)),
+					VertexUsageRaw::BlendIndices => glam::Vec4::from_array(std::array::from_fn(|i| bone(&vertex.blends[i]) as f32)),
+					VertexUsageRaw::Normal => vertex.normal.extend(0.0),
+					VertexUsageRaw::Uv => vertex.uv,
+					VertexUsageRaw::Tangent2 => glam::Vec4::ZERO,
+					VertexUsageRaw::Tangent1 => vertex.tangent,
+					VertexUsageRaw::Color => vertex.color,
+				};
+				
+				let f16 = |v: f32| half::f16::from_f32(v).to_bits();
+				match element.typ {
+					VertexTypeRaw::F32x1 => val.x.write_options(writer, endian, ())?,
+					VertexTypeRaw::F32x2 => val.xy().to_array().write_options(writer, endian, ())?,
+					VertexTypeRaw::F32x3 => val.xyz().to_array().write_options(writer, endian, ())?,
+					VertexTypeRaw::F32x4 => val.to_array().write_options(writer, endian, ())?,
+					VertexTypeRaw::U8x4  => val.to_array().map(|v| v.round() as u8).write_options(writer, endian, ())?,
+					VertexTypeRaw::F8x4  => val.to_array().map(|v| (v * 255.0).round() as u8).write_options(writer, endian, ())?,
+					VertexTypeRaw::F16x2 => val.xy().to_array().map(f16).write_options(writer, endian, ())?,
+					VertexTypeRaw::F16x4 => val.to_array().map(f16).write_options(writer, endian, ())?,
+					VertexTypeRaw::U16x2 => val.xy().to_array().map(|v| v.round() as u16).write_options(writer, endian, ())?,
+					VertexTypeRaw::U16x4 => val.to_array().map(|v| v.round() as u16).write_options(writer, endian, ())?,
+				}
+			}
+		}
+	}
+	
+	Ok(streams.map(|v| v.into_inner()))
+}
+
+impl crate::File for Mdl {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Mdl::read_le(&mut reader)?)
 	}
 }
 
@@ -485,9 +1197,229 @@ impl crate::format::external::Bytes for Mdl {
 // ----------
 
 impl crate::format::external::Gltf for Mdl {
-	fn read<T>(reader: &mut T) -> Result<Self, crate::Error> where
+	fn read<T>(reader: &mut T, base_path: Option<&std::path::Path>) -> Result<Self, crate::Error> where
 	T: Read + Seek {
-		todo!()
+		use std::collections::BTreeMap;
+		
+		let gltf = gltf::Gltf::from_reader(reader)?;
+		let buffers = gltf::import_buffers(&gltf.document, base_path, gltf.blob)?;
+		
+		let extras = |extras: &gltf::json::Extras| extras
+			.as_ref()
+			.and_then(|v| gltf::json::deserialize::from_str::<gltf::json::Value>(v.get()).ok());
+		
+		let strings = |value: Option<gltf::json::Value>, key: &str| value
+			.and_then(|v| v.get(key).and_then(|v| v.as_array()).map(|v| v.iter().filter_map(|v| v.as_str().map(|v| v.to_string())).collect::<Vec<_>>()))
+			.unwrap_or_default();
+		
+		// collect all nodes in the order they appear, together with their world transform
+		let mut nodes = Vec::new();
+		let mut stack = gltf.document
+			.default_scene()
+			.or_else(|| gltf.document.scenes().next())
+			.map_or_else(|| Vec::new(), |v| v.nodes().map(|v| (v, glam::Mat4::IDENTITY)).collect::<Vec<_>>());
+		stack.reverse();
+		while let Some((node, parent_transform)) = stack.pop() {
+			let transform = parent_transform * glam::Mat4::from_cols_array_2d(&node.transform().matrix());
+			stack.extend(node.children().map(|v| (v, transform)).collect::<Vec<_>>().into_iter().rev());
+			nodes.push((node, transform));
+		}
+		
+		let mut bones = Vec::<String>::new();
+		let mut placed = BTreeMap::<usize, BTreeMap<usize, (String, Vec<((usize, usize), Submesh)>)>>::new();
+		let mut unplaced = Vec::<(String, Submesh)>::new();
+		for (node, transform) in nodes {
+			let Some(mesh) = node.mesh() else {continue};
+			
+			// skinned meshes ignore the node transform
+			let transform = if node.skin().is_some() {glam::Mat4::IDENTITY} else {transform};
+			let placement = gltf_placement(node.name().or(mesh.name()).unwrap_or(""));
+			let attributes = strings(extras(node.extras()), "attributes");
+			let shape_names = strings(extras(mesh.extras()), "targetNames");
+			let joints = node.skin().map_or_else(|| Vec::new(), |v| v.joints().map(|v| v.name().unwrap_or("").to_string()).collect::<Vec<_>>());
+			
+			for (primitive_index, primitive) in mesh.primitives().enumerate() {
+				if primitive.mode() != gltf::mesh::Mode::Triangles {continue}
+				
+				let reader = primitive.reader(|v| Some(&buffers[v.index()][..]));
+				let Some(positions) = reader.read_positions() else {continue};
+				
+				let mut vertices = positions
+					.map(|v| Vertex {
+						position: transform.transform_point3(glam::Vec3::from_array(v)),
+						tangent: glam::Vec4::new(0.5, 0.5, 0.5, 1.0),
+						color: glam::Vec4::ONE,
+						..Default::default()
+					}).collect::<Vec<_>>();
+				let vertex_count = vertices.len();
+				
+				let has_normals = reader.read_normals().is_some();
+				if let Some(normals) = reader.read_normals() {
+					for (vertex, normal) in vertices.iter_mut().zip(normals) {
+						vertex.normal = transform.transform_vector3(glam::Vec3::from_array(normal)).normalize_or_zero();
+					}
+				}
+				
+				// the game stores the bitangent in 0-1 range with the handedness in w
+				let has_tangents = reader.read_tangents().is_some();
+				if let Some(tangents) = reader.read_tangents() {
+					for (vertex, tangent) in vertices.iter_mut().zip(tangents) {
+						let tangent = glam::Vec4::from_array(tangent);
+						let bitangent = (vertex.normal.cross(transform.transform_vector3(tangent.xyz())) * tangent.w).normalize_or_zero();
+						vertex.tangent = ((bitangent + 1.0) / 2.0).extend(if tangent.w > 0.0 {1.0} else {0.0});
+					}
+				}
+				
+				if let Some(uvs) = reader.read_tex_coords(0) {
+					for (vertex, uv) in vertices.iter_mut().zip(uvs.into_f32()) {
+						vertex.uv.x = uv[0];
+						vertex.uv.y = uv[1];
+					}
+				}
+				
+				if let Some(uvs) = reader.read_tex_coords(1) {
+					for (vertex, uv) in vertices.iter_mut().zip(uvs.into_f32()) {
+						vertex.uv.z = uv[0];
+						vertex.uv.w = uv[1];
+					}
+				}
+				
+				// the exporter puts a dummy in the first color set, so prefer the second one
+				if let Some(colors) = reader.read_colors(1).or_else(|| reader.read_colors(0)) {
+					for (vertex, color) in vertices.iter_mut().zip(colors.into_rgba_f32()) {
+						vertex.color = glam::Vec4::from_array(color);
+					}
+				}
+				
+				for set in 0..2 {
+					let (Some(joint_sets), Some(weight_sets)) = (reader.read_joints(set), reader.read_weights(set)) else {continue};
+					for (vertex, (joint_set, weight_set)) in vertices.iter_mut().zip(joint_sets.into_u16().zip(weight_sets.into_f32())) {
+						for i in 0..4 {
+							if weight_set[i] <= 0.0 {continue}
+							
+							let Some(name) = joints.get(joint_set[i] as usize) else {continue};
+							let bone = match bones.iter().position(|v| v == name) {
+								Some(v) => v,
+								None => {
+									bones.push(name.clone());
+									bones.len() - 1
+								}
+							};
+							
+							vertex.blends[set as usize * 4 + i] = Blend {
+								bone: bone as u8,
+								weight: weight_set[i],
+							};
+						}
+					}
+				}
+				
+				let indices = match reader.read_indices() {
+					Some(v) => v.into_u32().collect::<Vec<_>>(),
+					None => (0..vertex_count as u32).collect::<Vec<_>>(),
+				};
+				
+				// morph targets become new vertices which get swapped in for every index using the original one
+				let mut shapes = Vec::new();
+				for (target_index, (positions, normals, _)) in reader.read_morph_targets().enumerate() {
+					let positions = positions.map_or_else(|| vec![[0.0; 3]; vertex_count], |v| v.collect::<Vec<_>>());
+					let normals = normals.map_or_else(|| vec![[0.0; 3]; vertex_count], |v| v.collect::<Vec<_>>());
+					
+					let mut new_vertices = HashMap::new();
+					let mut values = Vec::new();
+					for (index, vertex_index) in indices.iter().enumerate() {
+						let vertex_index = *vertex_index as usize;
+						let position = glam::Vec3::from_array(positions[vertex_index]);
+						let normal = glam::Vec3::from_array(normals[vertex_index]);
+						if position == glam::Vec3::ZERO && normal == glam::Vec3::ZERO {continue}
+						
+						let new_vertex = *new_vertices.entry(vertex_index).or_insert_with(|| {
+							let mut vertex = vertices[vertex_index].clone();
+							vertex.position += transform.transform_vector3(position);
+							vertex.normal = (vertex.normal + transform.transform_vector3(normal)).normalize_or_zero();
+							vertices.push(vertex);
+							vertices.len() - 1
+						});
+						
+						values.push(ShapeValue {
+							index: index as u16,
+							new_vertex: new_vertex as u16,
+						});
+					}
+					
+					if values.len() > 0 {
+						shapes.push(Shape {
+							name: shape_names.get(target_index).cloned().unwrap_or_else(|| format!("shp_{target_index}")),
+							values,
+						});
+					}
+				}
+				
+				if vertices.len() > u16::MAX as usize {
+					return Err(crate::Error::InvalidData(format!("Primitive has {} vertices (including shapes) while the max is {}", vertices.len(), u16::MAX)));
+				}
+				
+				let material = gltf_material_path(primitive.material().name());
+				let mut submesh = Submesh {
+					vertices,
+					indices: indices.into_iter().map(|v| v as u16).collect(),
+					attributes: attributes.clone(),
+					shapes,
+					bone_map: Vec::new(),
+				};
+				
+				if !has_normals {
+					submesh.recalculate_normals();
+				}
+				
+				if !has_tangents {
+					submesh.recalculate_tangents();
+				}
+				
+				match placement {
+					Some((lod, mesh, submesh_index)) if lod < 3 =>
+						placed.entry(lod).or_default().entry(mesh).or_insert_with(|| (material, Vec::new())).1.push(((submesh_index, primitive_index), submesh)),
+					_ => unplaced.push((material, submesh)),
+				}
+			}
+		}
+		
+		if bones.len() > 256 {
+			return Err(crate::Error::InvalidData(format!("Model uses {} bones while the max is 256", bones.len())));
+		}
+		
+		// anything that didnt come from our exporter goes into lod 0, one mesh per material
+		let lod = placed.entry(0).or_default();
+		let mut next_mesh = lod.keys().last().map_or(0, |v| v + 1);
+		let mut material_meshes = HashMap::new();
+		for (i, (material, submesh)) in unplaced.into_iter().enumerate() {
+			let mesh = *material_meshes.entry(material.clone()).or_insert_with(|| {
+				next_mesh += 1;
+				next_mesh - 1
+			});
+			
+			lod.entry(mesh).or_insert_with(|| (material, Vec::new())).1.push(((i, 0), submesh));
+		}
+		
+		Ok(Mdl {
+			lods: placed
+				.into_values()
+				.map(|meshes| Lod {
+					meshes: meshes
+						.into_values()
+						.map(|(material, mut submeshes)| {
+							submeshes.sort_by_key(|v| v.0);
+							Mesh {
+								material,
+								submeshes: submeshes.into_iter().map(|v| v.1).collect(),
+								vertex_declaration: Vec::new(),
+								bone_table: Vec::new(),
+							}
+						}).collect(),
+				}).collect(),
+			bones,
+			raw: None,
+		})
 	}
 	
 	fn write<T>(&self, writer: &mut T, materials: HashMap<String, MaterialBake>, bones: Vec<crate::format::external::gltf::Bone>) -> Result<(), crate::Error> where
@@ -788,18 +1720,26 @@ impl crate::format::external::Gltf for Mdl {
 						extras: Default::default(),
 					};
 					
+					// blender uses targetNames for the shape key names, the importer reads them back together with the attributes
+					let extras = |key: &str, values: Vec<&String>| if values.len() > 0 {
+						json::extras::RawValue::from_string(json::serialize::to_string(&HashMap::from([(key, values)])).unwrap()).ok()
+					} else {
+						None
+					};
+					
 					let mesh = root.push(json::Mesh {
 						name: Some(format!("Mesh Lod{lod_index} Mesh{mesh_index} Submesh{submesh_index}")),
 						primitives: vec![primitive],
 						weights: None,
 						extensions: Default::default(),
-						extras: Default::default(),
+						extras: extras("targetNames", submesh.shapes.iter().map(|v| &v.name).collect()),
 					});
 					
 					let node = root.push(json::Node {
 						name: Some(format!("Lod{lod_index} Mesh{mesh_index} Submesh{submesh_index}")),
 						mesh: Some(mesh),
 						skin,
+						extras: extras("attributes", submesh.attributes.iter().collect()),
 						..Default::default()
 					});
 					
@@ -861,8 +1801,114 @@ fn to_padded_byte_vector<T: bytemuck::NoUninit>(data: &[T]) -> Vec<u8> {
 	new_vec
 }
 
+// gets the lod, mesh and submesh index out of names like "Lod0 Mesh1 Submesh2" (or "Lod0 Mesh1 Submesh2.001" after blender got to it)
+fn gltf_placement(name: &str) -> Option<(usize, usize, usize)> {
+	let mut lod = None;
+	let mut mesh = None;
+	let mut submesh = None;
+	for part in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+		let (key, value) = part.split_at(part.find(|c: char| c.is_ascii_digit()).unwrap_or(part.len()));
+		let Ok(value) = value.parse::<usize>() else {continue};
+		match key {
+			"Lod" => lod = Some(value),
+			"Mesh" => mesh = Some(value),
+			"Submesh" => submesh = Some(value),
+			_ => {}
+		}
+	}
+	
+	Some((lod?, mesh?, submesh?))
+}
+
+// the exporter names materials after the last segment of the path, so turn those back into a relative mtrl path
+fn gltf_material_path(name: Option<&str>) -> String {
+	let name = name.unwrap_or("default");
+	let name = name.find(".mtrl").map_or(name, |i| &name[..i + 5]);
+	let mut path = if name.contains("/") {name.to_string()} else {format!("/{name}")};
+	if !path.ends_with(".mtrl") {
+		path.push_str(".mtrl");
+	}
+	
+	path
+}
+
 // ----------
 
+#[derive(Debug, Clone)]
+struct RetainedRaw {
+	version: u32,
+	index_buffer_streaming: u8,
+	lod_count: u8,
+	attributes: Vec<String>,
+	materials: Vec<String>,
+	shapes: Vec<String>,
+	element_ids: Vec<(String, ElementIdRaw)>,
+	model_header: ModelHeaderRaw,
+	lods: Vec<LodRaw>,
+	extra_lods: Vec<ExtraLodRaw>,
+	neck_morphs: Vec<NeckMorphRaw>,
+	face_shadow_data: Vec<UnkFaceShadowDataRaw>,
+	bounding_boxes: [BoundingBoxRaw; 4],
+	bone_bounding_boxes: Vec<BoundingBoxRaw>,
+	edge_geometry_flag: u8,
+	// per lod
+	special_meshes: Vec<Vec<SpecialMeshRaw>>,
+	terrain_shadow_meshes: Vec<Vec<TerrainShadowMesh>>,
+	edge_geometry: Vec<Vec<u8>>,
+}
+
+// a water, shadow, fog or extra lod mesh with its buffers. indices into the other tables are resolved so it can be moved around
+#[derive(Debug, Clone)]
+struct SpecialMeshRaw {
+	// in the original file, used to remap the ranges of the lod
+	index: u16,
+	mesh: MeshRaw,
+	material: Option<String>,
+	declaration: Vec<VertexElementRaw>,
+	bone_table: Option<Vec<u16>>,
+	streams: [Vec<u8>; 3],
+	indices: Vec<u16>,
+	submeshes: Vec<SpecialSubmeshRaw>,
+}
+
+#[derive(Debug, Clone)]
+struct SpecialSubmeshRaw {
+	// relative to the start of the mesh
+	index_offset: u32,
+	index_count: u32,
+	attributes: Vec<String>,
+	bone_map: Vec<u16>,
+}
+
+#[derive(Debug, Clone)]
+struct TerrainShadowMesh {
+	mesh: TerrainShadowMeshRaw,
+	vertices: Vec<u8>,
+	indices: Vec<u16>,
+	// index offsets are relative to the start of the mesh
+	submeshes: Vec<TerrainShadowSubmeshRaw>,
+}
+
+// water, shadow and vertical fog, followed by lightshaft, glass, material change and crest change if there are extra lods
+fn special_mesh_ranges(lod: &LodRaw, extra_lod: Option<&ExtraLodRaw>) -> Vec<(u16, u16)> {
+	let mut ranges = vec![
+		(lod.water_mesh_index, lod.water_mesh_count),
+		(lod.shadow_mesh_index, lod.shadow_mesh_count),
+		(lod.vertical_fog_mesh_index, lod.vertical_fog_mesh_count),
+	];
+	
+	if let Some(v) = extra_lod {
+		ranges.extend([
+			(v.lightshaft_mesh_index, v.lightshaft_mesh_count),
+			(v.glass_mesh_index, v.glass_mesh_count),
+			(v.material_change_mesh_index, v.material_change_mesh_count),
+			(v.crest_change_mesh_index, v.crest_change_mesh_count),
+		]);
+	}
+	
+	ranges
+}
+
 #[binrw]
 #[derive(Debug, Clone)]
 struct HeaderRaw {
@@ -909,6 +1955,23 @@ enum VertexTypeRaw {
 	U16x4 = 17,
 }
 
+impl VertexTypeRaw {
+	fn size(&self) -> u8 {
+		match self {
+			VertexTypeRaw::F32x1 => 4,
+			VertexTypeRaw::F32x2 => 8,
+			VertexTypeRaw::F32x3 => 12,
+			VertexTypeRaw::F32x4 => 16,
+			VertexTypeRaw::U8x4  => 4,
+			VertexTypeRaw::F8x4  => 4,
+			VertexTypeRaw::F16x2 => 4,
+			VertexTypeRaw::F16x4 => 8,
+			VertexTypeRaw::U16x2 => 4,
+			VertexTypeRaw::U16x4 => 8,
+		}
+	}
+}
+
 #[binrw]
 #[brw(repr = u8)]
 #[repr(u8)]
@@ -925,7 +1988,7 @@ enum VertexUsageRaw {
 }
 
 #[binrw]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 struct ModelHeaderRaw {
 	radius: f32,
 	mesh_count: u16,
@@ -964,7 +2027,7 @@ struct ModelHeaderRaw {
 
 bitflags::bitflags! {
 	#[repr(transparent)]
-	#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+	#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
 	struct ModelFlags1Raw: u8 {
 		const DUST_OCCLUSION_ENABLED = 0x80;
 		const SNOW_OCCLUSION_ENABLED = 0x40;
@@ -979,7 +2042,7 @@ bitflags::bitflags! {
 
 bitflags::bitflags! {
 	#[repr(transparent)]
-	#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+	#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
 	struct ModelFlags2Raw: u8 {
 		const UNKNOWN2 = 0x80;
 		const BG_UV_SCROLL_ENABLED = 0x40;
@@ -1002,7 +2065,7 @@ struct ElementIdRaw {
 }
 
 #[binrw]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 struct LodRaw {
 	mesh_index: u16,
 	mesh_count: u16,
@@ -1160,11 +2223,12 @@ fn bone_table_writer(bones_all: &Vec<Vec<u16>>, version: u32) -> binrw::BinResul
 		}
 		
 		6 => {
+			// offsets are relative to the entry itself, in 4 byte units
 			let mut offset = bones_all.len();
 			for bones in bones_all {
 				(offset as u16).write_options(writer, endian, ())?;
 				(bones.len() as u16).write_options(writer, endian, ())?;
-				offset += (bones.len() + 1) / 2;
+				offset = offset + (bones.len() + 1) / 2 - 1;
 			}
 			
 			for bones in bones_all {
@@ -1228,7 +2292,7 @@ struct UnkFaceShadowDataRaw {
 }
 
 #[binrw]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 struct BoundingBoxRaw {
 	min: [f32; 4],
 	max: [f32; 4],
@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+use glam::Vec4Swizzles;
+use super::{BoundingBoxRaw, Mdl, Mesh, Shape, ShapeValue, Submesh, Vertex};
+
+impl Mdl {
+	/// Renames bones using the mapping, bones it returns None for are kept as is.
+	/// Bones that end up with the same name are merged, their weights get combined
+	pub fn remap_bones(&mut self, map: impl Fn(&str) -> Option<String>) {
+		let renamed = self.bones
+			.iter()
+			.map(|v| map(v).unwrap_or_else(|| v.clone()))
+			.collect::<Vec<_>>();
+		
+		let mut bones = Vec::<String>::new();
+		let mut remap = Vec::with_capacity(renamed.len());
+		for name in &renamed {
+			remap.push(match bones.iter().position(|v| v == name) {
+				Some(v) => v,
+				None => {
+					bones.push(name.clone());
+					bones.len() - 1
+				}
+			});
+		}
+		
+		let remap_list = |list: &mut Vec<u16>| {
+			let mut new = Vec::with_capacity(list.len());
+			for bone in list.iter() {
+				let bone = remap.get(*bone as usize).map_or(*bone, |v| *v as u16);
+				if !new.contains(&bone) {
+					new.push(bone);
+				}
+			}
+			*list = new;
+		};
+		
+		for mesh in self.lods.iter_mut().flat_map(|v| &mut v.meshes) {
+			remap_list(&mut mesh.bone_table);
+			for submesh in &mut mesh.submeshes {
+				remap_list(&mut submesh.bone_map);
+				for vertex in &mut submesh.vertices {
+					for i in 0..vertex.blends.len() {
+						if vertex.blends[i].weight <= 0.0 {continue}
+						let bone = remap.get(vertex.blends[i].bone as usize).map_or(vertex.blends[i].bone, |v| *v as u8);
+						vertex.blends[i].bone = bone;
+						
+						// merge into an earlier blend that already uses the same bone
+						if let Some(j) = (0..i).find(|j| vertex.blends[*j].weight > 0.0 && vertex.blends[*j].bone == bone) {
+							vertex.blends[j].weight += vertex.blends[i].weight;
+							vertex.blends[i] = Default::default();
+						}
+					}
+				}
+			}
+		}
+		
+		if let Some(raw) = &mut self.raw {
+			for (parent, _) in &mut raw.element_ids {
+				if let Some(name) = map(parent) {
+					*parent = name;
+				}
+			}
+		}
+		
+		self.bones = bones;
+		let bone_bounding_boxes = self.calculate_bounding_boxes().1;
+		if let Some(raw) = &mut self.raw {
+			raw.bone_bounding_boxes = bone_bounding_boxes;
+		}
+	}
+	
+	/// Recalculates the bounding boxes from the current vertices.
+	/// Models read from a file otherwise keep writing their original ones
+	pub fn recalculate_bounding_boxes(&mut self) {
+		let ([bb, model_bb, _, _], bones_bb) = self.calculate_bounding_boxes();
+		let Some(raw) = &mut self.raw else {return};
+		raw.model_header.radius = radius(&bb);
+		raw.bounding_boxes[0] = bb;
+		raw.bounding_boxes[1] = model_bb;
+		raw.bone_bounding_boxes = bones_bb;
+	}
+}
+
+pub(super) fn radius(bb: &BoundingBoxRaw) -> f32 {
+	glam::Vec3::from_slice(&bb.min).length().max(glam::Vec3::from_slice(&bb.max).length())
+}
+
+impl Mesh {
+	/// Merges the given submeshes into the first one of them, the others are removed
+	pub fn merge_submeshes(&mut self, submeshes: &[usize]) -> Result<(), crate::Error> {
+		let mut submeshes = submeshes.to_vec();
+		submeshes.sort_unstable();
+		submeshes.dedup();
+		if let Some(index) = submeshes.iter().find(|v| **v >= self.submeshes.len()) {
+			return Err(crate::Error::NotFound(format!("Submesh {index}")));
+		}
+		
+		let Some((&target, others)) = submeshes.split_first() else {return Ok(())};
+		let vertex_count = submeshes.iter().map(|v| self.submeshes[*v].vertices.len()).sum::<usize>();
+		if vertex_count > u16::MAX as usize {
+			return Err(crate::Error::InvalidData(format!("Merged submesh has {vertex_count} vertices while the max is {}", u16::MAX)));
+		}
+		
+		let index_count = submeshes.iter().map(|v| self.submeshes[*v].indices.len()).sum::<usize>();
+		if index_count > u16::MAX as usize + 1 {
+			return Err(crate::Error::InvalidData(format!("Merged submesh has {index_count} indices while the max is {}", u16::MAX as usize + 1)));
+		}
+		
+		let mut merged = Vec::with_capacity(others.len());
+		for index in others.iter().rev() {
+			merged.push(self.submeshes.remove(*index));
+		}
+		
+		let submesh = &mut self.submeshes[target];
+		for other in merged.into_iter().rev() {
+			let vertex_offset = submesh.vertices.len() as u16;
+			let index_offset = submesh.indices.len() as u16;
+			
+			submesh.vertices.extend(other.vertices);
+			submesh.indices.extend(other.indices.into_iter().map(|v| v + vertex_offset));
+			
+			for shape in other.shapes {
+				let values = shape.values.into_iter().map(|v| ShapeValue {
+					index: v.index + index_offset,
+					new_vertex: v.new_vertex + vertex_offset,
+				});
+				
+				match submesh.shapes.iter_mut().find(|v| v.name == shape.name) {
+					Some(v) => v.values.extend(values),
+					None => submesh.shapes.push(Shape {
+						name: shape.name,
+						values: values.collect(),
+					}),
+				}
+			}
+			
+			for attribute in other.attributes {
+				if !submesh.attributes.contains(&attribute) {
+					submesh.attributes.push(attribute);
+				}
+			}
+			
+			for bone in other.bone_map {
+				if !submesh.bone_map.contains(&bone) {
+					submesh.bone_map.push(bone);
+				}
+			}
+		}
+		
+		Ok(())
+	}
+	
+	/// Moves every triangle of the submesh the filter returns true for into a new submesh, which is inserted right after it.
+	/// Returns the index of the new submesh, None if no triangle was moved
+	pub fn split_submesh(&mut self, submesh: usize, filter: impl Fn([&Vertex; 3]) -> bool) -> Option<usize> {
+		let original = self.submeshes.get_mut(submesh)?;
+		
+		// where each index of the original ends up, (moved, new index)
+		let mut placement = Vec::with_capacity(original.indices.len());
+		let mut kept = Vec::new();
+		let mut moved = Vec::new();
+		for triangle in original.indices.chunks(3) {
+			let is_moved = match triangle {
+				&[a, b, c] => filter([a, b, c].map(|v| &original.vertices[v as usize])),
+				_ => false,
+			};
+			
+			let target = if is_moved {&mut moved} else {&mut kept};
+			for index in triangle {
+				placement.push((is_moved, target.len() as u16));
+				target.push(*index);
+			}
+		}
+		
+		if moved.len() == 0 {return None}
+		
+		let mut shapes_kept = Vec::new();
+		let mut shapes_moved = Vec::new();
+		for shape in &original.shapes {
+			let mut values_kept = Vec::new();
+			let mut values_moved = Vec::new();
+			for value in &shape.values {
+				let Some((is_moved, index)) = placement.get(value.index as usize) else {continue};
+				(if *is_moved {&mut values_moved} else {&mut values_kept}).push(ShapeValue {
+					index: *index,
+					new_vertex: value.new_vertex,
+				});
+			}
+			
+			for (shapes, values) in [(&mut shapes_kept, values_kept), (&mut shapes_moved, values_moved)] {
+				if values.len() > 0 {
+					shapes.push(Shape {
+						name: shape.name.clone(),
+						values,
+					});
+				}
+			}
+		}
+		
+		let mut new = Submesh {
+			vertices: original.vertices.clone(),
+			indices: moved,
+			attributes: original.attributes.clone(),
+			shapes: shapes_moved,
+			bone_map: original.bone_map.clone(),
+		};
+		new.remove_unused_vertices();
+		
+		original.indices = kept;
+		original.shapes = shapes_kept;
+		original.remove_unused_vertices();
+		
+		self.submeshes.insert(submesh + 1, new);
+		Some(submesh + 1)
+	}
+}
+
+impl Submesh {
+	/// The indices with every shape the filter returns true for applied
+	pub fn shape_indices(&self, enabled: impl Fn(&str) -> bool) -> Vec<u16> {
+		let mut indices = self.indices.clone();
+		for shape in &self.shapes {
+			if !enabled(&shape.name) {continue}
+			for value in &shape.values {
+				if let Some(index) = indices.get_mut(value.index as usize) {
+					*index = value.new_vertex;
+				}
+			}
+		}
+		
+		indices
+	}
+	
+	/// Permanently applies every shape the filter returns true for and removes them
+	pub fn bake_shapes(&mut self, bake: impl Fn(&str) -> bool) {
+		self.indices = self.shape_indices(&bake);
+		self.shapes.retain(|v| !bake(&v.name));
+		self.remove_unused_vertices();
+	}
+	
+	/// Removes vertices that aren't referenced by the indices or any shape
+	pub fn remove_unused_vertices(&mut self) {
+		let mut used = vec![false; self.vertices.len()];
+		for index in self.indices.iter().chain(self.shapes.iter().flat_map(|v| v.values.iter().map(|v| &v.new_vertex))) {
+			if let Some(v) = used.get_mut(*index as usize) {
+				*v = true;
+			}
+		}
+		
+		if used.iter().all(|v| *v) {return}
+		
+		let mut remap = vec![0u16; self.vertices.len()];
+		let mut vertices = Vec::new();
+		for (i, vertex) in std::mem::take(&mut self.vertices).into_iter().enumerate() {
+			if !used[i] {continue}
+			remap[i] = vertices.len() as u16;
+			vertices.push(vertex);
+		}
+		
+		self.vertices = vertices;
+		for index in &mut self.indices {
+			*index = remap.get(*index as usize).copied().unwrap_or(*index);
+		}
+		
+		for value in self.shapes.iter_mut().flat_map(|v| &mut v.values) {
+			value.new_vertex = remap.get(value.new_vertex as usize).copied().unwrap_or(value.new_vertex);
+		}
+	}
+	
+	/// Recalculates smooth normals, vertices sharing a position are treated as one.
+	/// Vertices swapped in by a shape use the triangles with that shape applied
+	pub fn recalculate_normals(&mut self) {
+		let mut normals = vertex_normals(&self.vertices, &self.indices);
+		for shape in &self.shapes {
+			let shape_normals = vertex_normals(&self.vertices, &self.shape_indices(|v| v == shape.name));
+			for value in &shape.values {
+				let i = value.new_vertex as usize;
+				if i < normals.len() {
+					normals[i] = shape_normals[i];
+				}
+			}
+		}
+		
+		for (vertex, normal) in self.vertices.iter_mut().zip(normals) {
+			if normal != glam::Vec3::ZERO {
+				vertex.normal = normal;
+			}
+		}
+	}
+	
+	/// Recalculates the tangents from the normals and first uv set
+	pub fn recalculate_tangents(&mut self) {
+		let mut tangents = vec![(glam::Vec3::ZERO, glam::Vec3::ZERO); self.vertices.len()];
+		let mut sets = vec![self.indices.clone()];
+		sets.extend(self.shapes.iter().map(|shape| self.shape_indices(|v| v == shape.name)));
+		
+		// https://terathon.com/blog/tangent-space.html
+		for triangle in sets.iter().flat_map(|v| v.chunks_exact(3)) {
+			let [v1, v2, v3] = [0, 1, 2].map(|i| &self.vertices[triangle[i] as usize]);
+			let e1 = v2.position - v1.position;
+			let e2 = v3.position - v1.position;
+			let uv1 = v2.uv.xy() - v1.uv.xy();
+			let uv2 = v3.uv.xy() - v1.uv.xy();
+			
+			let r = uv1.x * uv2.y - uv2.x * uv1.y;
+			if r == 0.0 {continue}
+			let sdir = (e1 * uv2.y - e2 * uv1.y) / r;
+			let tdir = (e2 * uv1.x - e1 * uv2.x) / r;
+			
+			for index in triangle {
+				let tangent = &mut tangents[*index as usize];
+				tangent.0 += sdir;
+				tangent.1 += tdir;
+			}
+		}
+		
+		// the game stores the bitangent in 0-1 range with the handedness in w
+		for (vertex, (sdir, tdir)) in self.vertices.iter_mut().zip(tangents) {
+			let n = vertex.normal;
+			let tangent = (sdir - n * n.dot(sdir)).normalize_or_zero();
+			if tangent == glam::Vec3::ZERO {continue}
+			let w = if n.cross(sdir).dot(tdir) < 0.0 {-1.0} else {1.0};
+			let bitangent = (n.cross(tangent) * w).normalize_or_zero();
+			vertex.tangent = ((bitangent + 1.0) / 2.0).extend(if w > 0.0 {1.0} else {0.0});
+		}
+	}
+}
+
+fn vertex_normals(vertices: &[Vertex], indices: &[u16]) -> Vec<glam::Vec3> {
+	let key = |v: glam::Vec3| v.to_array().map(f32::to_bits);
+	
+	// not normalized so that larger triangles have more influence
+	let mut normals = HashMap::<[u32; 3], glam::Vec3>::new();
+	for triangle in indices.chunks_exact(3) {
+		let [a, b, c] = [0, 1, 2].map(|i| vertices[triangle[i] as usize].position);
+		let normal = (b - a).cross(c - a);
+		for index in triangle {
+			*normals.entry(key(vertices[*index as usize].position)).or_default() += normal;
+		}
+	}
+	
+	vertices
+		.iter()
+		.map(|v| normals.get(&key(v.position)).map_or(glam::Vec3::ZERO, |v| v.normalize_or_zero()))
+		.collect()
+}
\ No newline at end of file
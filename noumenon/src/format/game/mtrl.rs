@@ -521,6 +521,10 @@ pub struct Mtrl {
 	pub samplers: Vec<Sampler>,
 	pub shader_keys: Vec<(u32, u32)>,
 	pub shader_flags: u32,
+	/// The texture list as stored in the file (path, flags). Samplers reference textures by path,
+	/// this only exists so the textures keep their original order when writing
+	pub _textures: Vec<(String, u16)>,
+	pub _extra_data: Vec<u8>,
 }
 
 impl BinRead for Mtrl {
@@ -544,10 +548,34 @@ impl BinRead for Mtrl {
 		let colorset_infos = r!(Vec<(u16, u16)>, colorset_count); // name offset, index
 		let strings = r!(Vec<u8>, strings_size);
 		
-		let _extra_data = r!(Vec<u8>, extra_data_size);
+		let extra_data = r!(Vec<u8>, extra_data_size);
 		
-		let colorsets = if dataset_size >= 2048 {r!(Vec<[ColorRow; 32]>, colorset_count)} else {Vec::new()};
-		let colorset_dyes = if dataset_size >= 2176 {r!(Vec<[ColorDyeRow; 32]>, colorset_count)} else {Vec::new()};
+		// pre dawntrail materials have a 16 row table with less data per row
+		let (has_table, has_dyes, legacy) = match extra_data.get(..4) {
+			Some(v) => table_layout(u32::from_le_bytes(v.try_into().unwrap()))
+				.map_err(|message| binrw::Error::AssertFail{pos: 0, message})?,
+			// materials without flags only ever have the legacy table
+			None => (dataset_size >= 512, dataset_size >= 512 + 32, true),
+		};
+		
+		let (table_size, dyes_size) = if legacy {(512, 32)} else {(2048, 128)};
+		let table_count = if has_table {colorset_count as usize} else {0};
+		let expected_size = table_count * (table_size + if has_dyes {dyes_size} else {0});
+		if dataset_size as usize != expected_size {
+			return Err(binrw::Error::AssertFail{pos: 0, message: format!("Colorset data is {dataset_size} bytes while the flags say it should be {expected_size}")});
+		}
+		
+		let mut colorsets = Vec::new();
+		for _ in 0..table_count {
+			colorsets.push(r!(read_table, legacy));
+		}
+		
+		let mut colorset_dyes = Vec::new();
+		if has_dyes {
+			for _ in 0..table_count {
+				colorset_dyes.push(r!(read_table, legacy));
+			}
+		}
 		
 		let constant_values_size = r!(u16);
 		let shader_key_count = r!(u16);
@@ -572,6 +600,7 @@ impl BinRead for Mtrl {
 					name: strings[colorset_infos.iter().find(|v| v.1 as usize == i).unwrap().0 as usize..].null_terminated().unwrap(),
 					regular: v,
 					dyes: colorset_dyes.get(i).map(|v| v.to_owned()),
+					legacy,
 				}).collect(),
 			constants: constants
 				.into_iter()
@@ -588,9 +617,16 @@ impl BinRead for Mtrl {
 					v_address_mode: (v.flags >> 2 & 0x3).into(),
 					lod_bias: ((v.flags as i32) << 12 >> 22) as f32 / 64.0,
 					min_lod: v.flags >> 20 & 0xF,
+					_flags: v.flags & !SAMPLER_FLAGS_MASK,
+					_texture_flags: if v.texture_id == 255 {0} else {texture_infos[v.texture_id as usize].1},
 				}).collect(),
 			shader_keys,
 			shader_flags,
+			_textures: texture_infos
+				.iter()
+				.map(|v| (strings[v.0 as usize..].null_terminated().unwrap(), v.1))
+				.collect(),
+			_extra_data: extra_data,
 		})
 	}
 }
@@ -599,13 +635,184 @@ impl BinWrite for Mtrl {
 	type Args<'a> = ();
 	
 	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
-		todo!();
+		let start = writer.stream_position()?;
+		let legacy = self.colorsets.first().map_or(false, |v| v.legacy);
+		if self.colorsets.iter().any(|v| v.legacy != legacy) {
+			return Err(binrw::Error::AssertFail{pos: start, message: "Colorsets can't mix legacy and regular layouts".to_string()});
+		}
+		
+		// only the textures the samplers use, those that were already in the file keep their original order
+		let mut textures = self._textures
+			.iter()
+			.filter_map(|v| self.samplers.iter().find(|s| s.texture == v.0).map(|s| (s.texture.as_str(), s._texture_flags)))
+			.collect::<Vec<_>>();
+		let mut sampler_texture_ids = Vec::new();
+		for sampler in &self.samplers {
+			if sampler.texture.is_empty() {
+				sampler_texture_ids.push(255u8);
+				continue;
+			}
+			
+			let id = match textures.iter().position(|v| v.0 == sampler.texture) {
+				Some(v) => v,
+				None => {
+					textures.push((&sampler.texture, sampler._texture_flags));
+					textures.len() - 1
+				}
+			};
+			sampler_texture_ids.push(id as u8);
+		}
+		
+		// 255 is used for samplers without a texture
+		if textures.len() > 254 {
+			return Err(binrw::Error::AssertFail{pos: start, message: format!("Mtrl has {} textures while the max is 254", textures.len())});
+		}
+		
+		let mut strings = Vec::new();
+		let mut add_string = |s: &str| {
+			let offset = strings.len() as u16;
+			strings.extend_from_slice(s.as_bytes());
+			strings.push(0);
+			offset
+		};
+		
+		let texture_infos = textures.iter().map(|v| (add_string(v.0), v.1)).collect::<Vec<_>>();
+		let uvset_infos = self.uvsets.iter().enumerate().map(|(i, v)| (add_string(v), i as u16)).collect::<Vec<_>>();
+		let colorset_infos = self.colorsets.iter().enumerate().map(|(i, v)| (add_string(&v.name), i as u16)).collect::<Vec<_>>();
+		let shader_name_offset = add_string(&self.shader);
+		strings.resize(strings.len().next_multiple_of(4), 0);
+		
+		let has_dyes = self.colorsets.len() > 0 && self.colorsets.iter().all(|v| v.dyes.is_some());
+		let (table_size, dyes_size) = if legacy {(512, 32)} else {(2048, 128)};
+		let dataset_size = self.colorsets.len() * (table_size + if has_dyes {dyes_size} else {0});
+		
+		// the flags have to match the tables we write, the reader relies on them
+		let mut extra_data = self._extra_data.clone();
+		if extra_data.len() >= 4 || self.colorsets.len() > 0 {
+			extra_data.resize(extra_data.len().max(4), 0);
+			let flags = u32::from_le_bytes(extra_data[..4].try_into().unwrap());
+			let layout = (self.colorsets.len() > 0, has_dyes, legacy || self.colorsets.len() == 0);
+			if table_layout(flags).map_or(true, |v| (v.0, v.1, v.2 || !v.0) != layout) {
+				let flags = flags & !TABLE_FLAGS_MASK |
+					if layout.0 {HAS_TABLE_FLAG} else {0} |
+					if layout.1 {HAS_DYE_TABLE_FLAG} else {0} |
+					if layout.2 {0} else {DAWNTRAIL_TABLE_DIMENSIONS << 8};
+				extra_data[..4].copy_from_slice(&flags.to_le_bytes());
+			}
+		}
+		
+		let mut constant_values = Vec::new();
+		let constants = self.constants
+			.iter()
+			.map(|v| {
+				let offset = constant_values.len() as u16;
+				constant_values.extend_from_slice(&v.value);
+				constant_values.resize(constant_values.len().next_multiple_of(4), 0);
+				ConstantDefinitionRaw {
+					id: v.id,
+					offset,
+					size: v.value.len() as u16,
+				}
+			}).collect::<Vec<_>>();
+		
+		0x01030000u32.write_options(writer, endian, ())?;
+		0u16.write_options(writer, endian, ())?; // file size, written at the end
+		(dataset_size as u16).write_options(writer, endian, ())?;
+		(strings.len() as u16).write_options(writer, endian, ())?;
+		shader_name_offset.write_options(writer, endian, ())?;
+		(texture_infos.len() as u8).write_options(writer, endian, ())?;
+		(uvset_infos.len() as u8).write_options(writer, endian, ())?;
+		(colorset_infos.len() as u8).write_options(writer, endian, ())?;
+		(extra_data.len() as u8).write_options(writer, endian, ())?;
+		texture_infos.write_options(writer, endian, ())?;
+		uvset_infos.write_options(writer, endian, ())?;
+		colorset_infos.write_options(writer, endian, ())?;
+		strings.write_options(writer, endian, ())?;
+		extra_data.write_options(writer, endian, ())?;
+		
+		for colorset in &self.colorsets {
+			write_table(&colorset.regular, writer, endian, legacy)?;
+		}
+		
+		if has_dyes {
+			for colorset in &self.colorsets {
+				write_table(colorset.dyes.as_ref().unwrap(), writer, endian, legacy)?;
+			}
+		}
+		
+		(constant_values.len() as u16).write_options(writer, endian, ())?;
+		(self.shader_keys.len() as u16).write_options(writer, endian, ())?;
+		(constants.len() as u16).write_options(writer, endian, ())?;
+		(self.samplers.len() as u16).write_options(writer, endian, ())?;
+		self.shader_flags.write_options(writer, endian, ())?;
+		self.shader_keys.write_options(writer, endian, ())?;
+		constants.write_options(writer, endian, ())?;
+		for (sampler, texture_id) in self.samplers.iter().zip(sampler_texture_ids) {
+			SamplerRaw {
+				id: sampler.id,
+				flags: (sampler.u_address_mode as u32) |
+					(sampler.v_address_mode as u32) << 2 |
+					((sampler.lod_bias * 64.0).round() as i32 as u32 & 0x3FF) << 10 |
+					(sampler.min_lod & 0xF) << 20 |
+					(sampler._flags & !SAMPLER_FLAGS_MASK),
+				texture_id,
+				_padding: [0; 3],
+			}.write_options(writer, endian, ())?;
+		}
+		constant_values.write_options(writer, endian, ())?;
+		
+		let end = writer.stream_position()?;
+		writer.seek(std::io::SeekFrom::Start(start + 4))?;
+		((end - start) as u16).write_options(writer, endian, ())?;
+		writer.seek(std::io::SeekFrom::Start(end))?;
+		
+		Ok(())
+	}
+}
+
+const HAS_TABLE_FLAG: u32 = 0x4;
+const HAS_DYE_TABLE_FLAG: u32 = 0x8;
+// second byte of the flags, log2 of the width (in vec4s) and height of the table. 0 means the legacy 4x16 table
+const DAWNTRAIL_TABLE_DIMENSIONS: u32 = 0x53;
+const TABLE_FLAGS_MASK: u32 = HAS_TABLE_FLAG | HAS_DYE_TABLE_FLAG | 0xFF << 8;
+
+// has table, has dye table, legacy
+fn table_layout(flags: u32) -> Result<(bool, bool, bool), String> {
+	let legacy = match flags >> 8 & 0xFF {
+		0 | 0x42 => true,
+		DAWNTRAIL_TABLE_DIMENSIONS => false,
+		v => return Err(format!("Unsupported colorset table size of {}x{}", 1 << (v & 0xF), 1 << (v >> 4))),
+	};
+	
+	Ok((flags & HAS_TABLE_FLAG != 0, flags & HAS_DYE_TABLE_FLAG != 0, legacy))
+}
+
+// the colorsets always have 32 rows, legacy tables only fill the first 16
+fn read_table<R, T>(reader: &mut R, endian: binrw::Endian, legacy: bool) -> binrw::BinResult<[T; 32]> where
+R: Read + Seek,
+T: for<'a> BinRead<Args<'a> = (bool,)> + Default {
+	let mut rows: [T; 32] = Default::default();
+	for row in rows.iter_mut().take(if legacy {16} else {32}) {
+		*row = T::read_options(reader, endian, (legacy,))?;
 	}
+	
+	Ok(rows)
+}
+
+fn write_table<W, T>(rows: &[T; 32], writer: &mut W, endian: binrw::Endian, legacy: bool) -> binrw::BinResult<()> where
+W: Write + Seek,
+T: for<'a> BinWrite<Args<'a> = (bool,)> {
+	for row in rows.iter().take(if legacy {16} else {32}) {
+		row.write_options(writer, endian, (legacy,))?;
+	}
+	
+	Ok(())
 }
 
-impl ironworks::file::File for Mtrl {
-	fn read(mut data: impl ironworks::FileStream) -> Result<Self, ironworks::Error> {
-		Mtrl::read_le(&mut data).map_err(|e| ironworks::Error::Resource(e.into()))
+impl crate::File for Mtrl {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Mtrl::read_le(&mut reader)?)
 	}
 }
 
@@ -649,8 +856,13 @@ pub struct Sampler {
 	pub v_address_mode: AddressMode,
 	pub lod_bias: f32,
 	pub min_lod: u32,
+	// the bits of the sampler and texture flags we dont know the meaning of
+	pub _flags: u32,
+	pub _texture_flags: u16,
 }
 
+const SAMPLER_FLAGS_MASK: u32 = 0x00FFFC0F;
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AddressMode {
@@ -677,6 +889,7 @@ pub struct ColorSet {
 	pub name: String,
 	pub regular: [ColorRow; 32],
 	pub dyes: Option<[ColorDyeRow; 32]>,
+	pub legacy: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -736,12 +949,26 @@ impl Default for ColorRow {
 	}
 }
 
+// legacy rows only have the colors, specular strength (_diffuse_alpha), gloss (_specular_alpha) and tiling
 impl BinRead for ColorRow {
-	type Args<'a> = ();
+	type Args<'a> = (bool,);
 	
-	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, (legacy,): Self::Args<'_>,) -> binrw::BinResult<Self> {
 		simple_reader!(reader, endian);
 		
+		if legacy {
+			return Ok(Self {
+				diffuse: glam::vec3(r!(f16), r!(f16), r!(f16)),
+				_diffuse_alpha: r!(f16),
+				specular: glam::vec3(r!(f16), r!(f16), r!(f16)),
+				_specular_alpha: r!(f16),
+				emmisive: glam::vec3(r!(f16), r!(f16), r!(f16)),
+				tile_index: (r!(f16) * 64.0) as u16,
+				tile_transform: glam::Mat2::from_cols_array(&[r!(f16), r!(f16), r!(f16), r!(f16)]),
+				..Default::default()
+			})
+		}
+		
 		Ok(Self {
 			diffuse: glam::vec3(r!(f16), r!(f16), r!(f16)),
 			_diffuse_alpha: r!(f16),
@@ -771,14 +998,49 @@ impl BinRead for ColorRow {
 }
 
 impl BinWrite for ColorRow {
-	type Args<'a> = ();
+	type Args<'a> = (bool,);
 	
-	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
-		todo!();
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, (legacy,): Self::Args<'_>,) -> binrw::BinResult<()> {
+		let mut values = Vec::with_capacity(32);
+		values.extend_from_slice(&self.diffuse.to_array());
+		values.push(self._diffuse_alpha);
+		values.extend_from_slice(&self.specular.to_array());
+		values.push(self._specular_alpha);
+		values.extend_from_slice(&self.emmisive.to_array());
+		
+		if legacy {
+			values.push(self.tile_index as f32 / 64.0);
+			values.extend_from_slice(&self.tile_transform.to_cols_array());
+			return values.into_iter().map(|v| half::f16::from_f32(v).to_bits()).collect::<Vec<_>>().write_options(writer, endian, ());
+		}
+		
+		values.extend_from_slice(&[
+			self._emmisive_alpha,
+			self.sheen_rate,
+			self.sheen_tint_rate,
+			self.sheen_aperature,
+			self._unknown15,
+			self.roughness,
+			self._unknown17,
+			self.metalic,
+			self.anisotropy,
+			self._unknown20,
+			self.sphere_map_mask,
+			self._unknown22,
+			self._unknown23,
+		]);
+		
+		let mut data = values.into_iter().map(|v| half::f16::from_f32(v).to_bits()).collect::<Vec<_>>();
+		data.push(self.shader_id);
+		data.push(half::f16::from_f32(self.tile_index as f32 / 64.0).to_bits());
+		data.push(half::f16::from_f32(self.tile_alpha).to_bits());
+		data.push(self.sphere_map_index);
+		data.extend(self.tile_transform.to_cols_array().map(|v| half::f16::from_f32(v).to_bits()));
+		data.write_options(writer, endian, ())
 	}
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ColorDyeRow {
 	pub template: u16,
 	pub channel: u8,
@@ -794,20 +1056,28 @@ pub struct ColorDyeRow {
 	pub anisotropy: bool,
 	pub sphere_map_index: bool,
 	pub sphere_map_mask: bool,
+	// gloss, only exists on legacy rows
+	pub scalar7: bool,
 }
 
-// impl Default for ColorDyeRow {
-// 	fn default() -> Self {
-// 		Self {
-// 			
-// 		}
-// 	}
-// }
-
 impl BinRead for ColorDyeRow {
-	type Args<'a> = ();
+	type Args<'a> = (bool,);
 	
-	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, (legacy,): Self::Args<'_>,) -> binrw::BinResult<Self> {
+		if legacy {
+			let data = u16::read_options(reader, endian, ())?;
+			
+			return Ok(Self {
+				template: data >> 5,
+				diffuse: (data & 0x01) != 0,
+				specular: (data & 0x02) != 0,
+				emmisive: (data & 0x04) != 0,
+				scalar7: (data & 0x08) != 0,
+				scalar3: (data & 0x10) != 0,
+				..Default::default()
+			})
+		}
+		
 		let data = u32::read_options(reader, endian, ())?;
 		
 		Ok(Self {
@@ -825,15 +1095,42 @@ impl BinRead for ColorDyeRow {
 			anisotropy: (data & 0x0200) != 0,
 			sphere_map_index: (data & 0x0400) != 0,
 			sphere_map_mask: (data & 0x0800) != 0,
+			scalar7: false,
 		})
 	}
 }
 
 impl BinWrite for ColorDyeRow {
-	type Args<'a> = ();
+	type Args<'a> = (bool,);
 	
-	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
-		todo!();
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, (legacy,): Self::Args<'_>,) -> binrw::BinResult<()> {
+		if legacy {
+			let data = (self.template & 0x7FF) << 5 |
+				(self.diffuse as u16) |
+				(self.specular as u16) << 1 |
+				(self.emmisive as u16) << 2 |
+				(self.scalar7 as u16) << 3 |
+				(self.scalar3 as u16) << 4;
+			
+			return data.write_options(writer, endian, ());
+		}
+		
+		let data = (self.template as u32 & 0x7FF) << 16 |
+			(self.channel as u32 & 0x3) << 27 |
+			(self.diffuse as u32) |
+			(self.specular as u32) << 1 |
+			(self.emmisive as u32) << 2 |
+			(self.scalar3 as u32) << 3 |
+			(self.metalic as u32) << 4 |
+			(self.roughness as u32) << 5 |
+			(self.sheen_rate as u32) << 6 |
+			(self.sheen_tint_rate as u32) << 7 |
+			(self.sheen_aperature as u32) << 8 |
+			(self.anisotropy as u32) << 9 |
+			(self.sphere_map_index as u32) << 10 |
+			(self.sphere_map_mask as u32) << 11;
+		
+		data.write_options(writer, endian, ())
 	}
 }
 
@@ -0,0 +1,430 @@
+use std::{collections::HashMap, io::{Read, Seek, Write}};
+use binrw::{binrw, BinRead, BinWrite};
+use crate::{format::external::gltf::{Bone, MaterialBake}, NullReader, NullWriter};
+
+pub const EXT: &'static [&'static str] = &["pap"];
+
+pub type Error = binrw::Error;
+
+#[derive(Debug, Clone)]
+pub struct Pap {
+	pub model_id: u16,
+	pub model_type: u8,
+	pub variant: u8,
+	pub animations: Vec<Animation>,
+	
+	// the havok container holding the actual animation data, animations point to one of its bindings
+	havok: Vec<u8>,
+	version: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Animation {
+	pub name: String,
+	pub animation_type: u16,
+	pub havok_index: i16,
+	pub face: bool,
+	/// Raw timeline (tmb) data, the events (vfx, sounds, etc) that play alongside the animation
+	pub timeline: Vec<u8>,
+}
+
+/// An animation sampled at the framerate it was authored at
+#[derive(Debug, Clone)]
+pub struct SampledAnimation {
+	/// In seconds
+	pub duration: f32,
+	pub frame_count: usize,
+	pub tracks: Vec<Track>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Track {
+	/// Index of the bone in the skeleton the animation was made for
+	pub bone: usize,
+	pub translations: Vec<glam::Vec3>,
+	pub rotations: Vec<glam::Quat>,
+	pub scales: Vec<glam::Vec3>,
+}
+
+impl Pap {
+	pub fn skeleton_path(&self) -> Option<String> {
+		let id = self.model_id;
+		let variant = self.variant;
+		
+		// TODO: face animations should use the face skeleton, however the pap doesn't tell us which face
+		match self.model_type {
+			0 => Some(format!("chara/human/c{id:04}/skeleton/base/b0001/skl_c{id:04}b0001.sklb")),
+			1 => Some(format!("chara/monster/m{id:04}/skeleton/base/b{variant:04}/skl_m{id:04}b{variant:04}.sklb")),
+			2 => Some(format!("chara/demihuman/d{id:04}/skeleton/base/b{variant:04}/skl_d{id:04}b{variant:04}.sklb")),
+			3 => Some(format!("chara/weapon/w{id:04}/skeleton/base/b{variant:04}/skl_w{id:04}b{variant:04}.sklb")),
+			_ => None,
+		}
+	}
+	
+	/// Samples every animation, the result is in the same order as `animations`
+	pub fn sample(&self) -> Vec<Option<SampledAnimation>> {
+		if self.havok.len() == 0 {
+			return self.animations.iter().map(|_| None).collect();
+		}
+		
+		let root = crate::havok::HavokBinaryTagFileReader::read(&self.havok);
+		let container = crate::havok::HavokAnimationContainer::new(root.find_object_by_type("hkaAnimationContainer"));
+		
+		self.animations
+			.iter()
+			.map(|animation| {
+				let binding = container.bindings.get(usize::try_from(animation.havok_index).ok()?)?;
+				let duration = binding.animation.duration();
+				let frame_count = binding.animation.frame_count().max(1);
+				
+				let mut tracks = binding.transform_track_to_bone_indices
+					.iter()
+					.map(|bone| Track {
+						bone: *bone as usize,
+						translations: Vec::with_capacity(frame_count),
+						rotations: Vec::with_capacity(frame_count),
+						scales: Vec::with_capacity(frame_count),
+					}).collect::<Vec<_>>();
+				
+				for frame in 0..frame_count {
+					let time = if frame_count > 1 {duration * frame as f32 / (frame_count - 1) as f32} else {0.0};
+					for (track, transform) in tracks.iter_mut().zip(binding.animation.sample(time * 1000.0)) {
+						track.translations.push(glam::Vec3::from_slice(&transform.translation[0..3]));
+						track.rotations.push(glam::Quat::from_array(transform.rotation));
+						track.scales.push(glam::Vec3::from_slice(&transform.scale[0..3]));
+					}
+				}
+				
+				Some(SampledAnimation {
+					duration,
+					frame_count,
+					tracks,
+				})
+			}).collect()
+	}
+}
+
+impl BinRead for Pap {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
+		
+		let _magic = r!(u32);
+		let version = r!(u32);
+		let animation_count = r!(u16);
+		let model_id = r!(u16);
+		let model_type = r!(u8);
+		let variant = r!(u8);
+		let info_offset = r!(u32);
+		let havok_offset = r!(u32);
+		let timeline_offset = r!(u32);
+		
+		r!(seek info_offset);
+		let animations_raw = r!(Vec<AnimationRaw>, animation_count);
+		
+		let Some(havok_size) = timeline_offset.checked_sub(havok_offset) else {
+			return Err(binrw::Error::AssertFail{pos: havok_offset as u64, message: format!("Havok data at {havok_offset} starts after the timelines at {timeline_offset}")});
+		};
+		
+		r!(seek havok_offset);
+		let havok = r!(Vec<u8>, havok_size);
+		
+		r!(seek timeline_offset);
+		fn read_timeline<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _: ()) -> binrw::BinResult<Vec<u8>> {
+			let pos = reader.stream_position()?;
+			let _magic = u32::read_options(reader, endian, ())?;
+			let size = u32::read_options(reader, endian, ())?;
+			reader.seek(std::io::SeekFrom::Start(pos))?;
+			let timeline = <Vec<u8>>::read_options(reader, endian, binrw::VecArgs{count: size as usize, inner: ()})?;
+			reader.seek(std::io::SeekFrom::Start((pos + size as u64 + 3) & !3))?;
+			Ok(timeline)
+		}
+		
+		let mut animations = Vec::with_capacity(animations_raw.len());
+		for animation in animations_raw {
+			let timeline = r!(read_timeline, ());
+			
+			animations.push(Animation {
+				name: animation.name,
+				animation_type: animation.animation_type,
+				havok_index: animation.havok_index,
+				face: animation.face != 0,
+				timeline,
+			});
+		}
+		
+		Ok(Self {
+			model_id,
+			model_type,
+			variant,
+			animations,
+			havok,
+			version,
+		})
+	}
+}
+
+impl BinWrite for Pap {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		let info_offset = 0x1A;
+		let havok_offset = info_offset + self.animations.len() as u32 * 40;
+		let timeline_offset = havok_offset + self.havok.len() as u32;
+		
+		b"pap ".write_options(writer, endian, ())?;
+		self.version.write_options(writer, endian, ())?;
+		(self.animations.len() as u16).write_options(writer, endian, ())?;
+		self.model_id.write_options(writer, endian, ())?;
+		self.model_type.write_options(writer, endian, ())?;
+		self.variant.write_options(writer, endian, ())?;
+		info_offset.write_options(writer, endian, ())?;
+		havok_offset.write_options(writer, endian, ())?;
+		timeline_offset.write_options(writer, endian, ())?;
+		
+		for animation in &self.animations {
+			AnimationRaw {
+				name: animation.name.clone(),
+				animation_type: animation.animation_type,
+				havok_index: animation.havok_index,
+				face: animation.face as u32,
+			}.write_options(writer, endian, ())?;
+		}
+		
+		self.havok.write_options(writer, endian, ())?;
+		
+		for animation in &self.animations {
+			animation.timeline.write_options(writer, endian, ())?;
+			vec![0u8; (4 - writer.stream_position()? as usize % 4) % 4].write_options(writer, endian, ())?;
+		}
+		
+		Ok(())
+	}
+}
+
+impl crate::File for Pap {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Pap::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Pap {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Pap {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Pap::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Gltf for Pap {
+	fn read<T>(_reader: &mut T, _base_path: Option<&std::path::Path>) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		// TODO: this requires compressing the animations back into havok splines
+		Err(crate::Error::InvalidFormatFrom(crate::format::external::gltf::EXT[0].to_string()))
+	}
+	
+	fn write<T>(&self, writer: &mut T, _materials: HashMap<String, MaterialBake>, bones: Vec<Bone>) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		use gltf::json::{self, validation::Checked::Valid};
+		
+		if bones.len() == 0 {
+			return Err(crate::Error::InvalidData("Animations can only be exported alongside the skeleton they are for".to_string()));
+		}
+		
+		let mut root = json::Root::default();
+		root.asset.generator = Some("Aetherment Pap glTF Exporter v0.1.0".to_string());
+		
+		let buffer = root.push(json::Buffer {
+			name: None,
+			byte_length: json::validation::USize64(0), // we set the size afterwards
+			uri: None,
+			extensions: Default::default(),
+			extras: Default::default(),
+		});
+		
+		let mut buf = Vec::new();
+		fn accessor(root: &mut json::Root, buffer: json::Index<json::Buffer>, buf: &mut Vec<u8>, data: &[u8], count: usize, typ: json::accessor::Type, minmax: Option<(f32, f32)>) -> json::Index<json::Accessor> {
+			let offset = buf.len();
+			buf.extend_from_slice(data);
+			while buf.len() % 4 != 0 {
+				buf.push(0);
+			}
+			
+			let view = root.push(json::buffer::View {
+				name: None,
+				buffer,
+				byte_offset: Some(json::validation::USize64(offset as u64)),
+				byte_length: json::validation::USize64(data.len() as u64),
+				byte_stride: None,
+				target: None,
+				extensions: Default::default(),
+				extras: Default::default(),
+			});
+			
+			root.push(json::Accessor {
+				name: None,
+				buffer_view: Some(view),
+				byte_offset: Some(json::validation::USize64(0)),
+				count: json::validation::USize64(count as u64),
+				type_: Valid(typ),
+				component_type: Valid(json::accessor::GenericComponentType(gltf::accessor::DataType::F32)),
+				min: minmax.map(|v| json::Value::from(vec![v.0])),
+				max: minmax.map(|v| json::Value::from(vec![v.1])),
+				sparse: None,
+				normalized: false,
+				extensions: Default::default(),
+				extras: Default::default(),
+			})
+		}
+		
+		// the skeleton, same layout as the one mdl exports, so that the animations can be applied to those
+		let mut bone_nodes = Vec::new();
+		let mut bone_map = HashMap::<String, json::Index<json::Node>>::new();
+		let mut bone_matrixes = Vec::<glam::Mat4>::new();
+		let mut root_nodes = Vec::new();
+		for bone in &bones {
+			let node = root.push(json::Node {
+				name: Some(bone.name.clone()),
+				translation: Some(bone.translation.into()),
+				rotation: Some(json::scene::UnitQuaternion(bone.rotation.into())),
+				scale: Some(bone.scale.into()),
+				..Default::default()
+			});
+			
+			let mut matrix = glam::Mat4::from_rotation_translation(bone.rotation, bone.translation);
+			match bone.parent.as_ref().and_then(|v| bone_map.get(v).map(|node| (v, *node))) {
+				Some((parent, parent_node)) => {
+					root.nodes[parent_node.value()].children.get_or_insert_with(|| Vec::new()).push(node);
+					matrix = bone_matrixes[bones.iter().position(|v| &v.name == parent).unwrap()] * matrix;
+				}
+				
+				None => root_nodes.push(node),
+			}
+			
+			bone_nodes.push(node);
+			bone_map.insert(bone.name.clone(), node);
+			bone_matrixes.push(matrix);
+		}
+		
+		let inverse_matrixes = bone_matrixes.iter().map(|v| v.inverse()).collect::<Vec<_>>();
+		let matrix_access = accessor(&mut root, buffer, &mut buf, bytemuck::cast_slice(&inverse_matrixes), inverse_matrixes.len(), json::accessor::Type::Mat4, None);
+		root.push(json::Skin {
+			name: Some("Skeleton".to_string()),
+			inverse_bind_matrices: Some(matrix_access),
+			joints: bone_nodes.clone(),
+			skeleton: None,
+			extensions: Default::default(),
+			extras: Default::default(),
+		});
+		
+		for (animation, sampled) in self.animations.iter().zip(self.sample()) {
+			let Some(sampled) = sampled else {continue};
+			
+			let times = (0..sampled.frame_count)
+				.map(|i| if sampled.frame_count > 1 {sampled.duration * i as f32 / (sampled.frame_count - 1) as f32} else {0.0})
+				.collect::<Vec<_>>();
+			let input = accessor(&mut root, buffer, &mut buf, bytemuck::cast_slice(&times), times.len(), json::accessor::Type::Scalar, Some((0.0, times[times.len() - 1])));
+			
+			let mut channels = Vec::new();
+			let mut samplers = Vec::new();
+			for track in &sampled.tracks {
+				let Some(node) = bone_nodes.get(track.bone) else {continue};
+				
+				let translations = track.translations.iter().map(|v| v.to_array()).collect::<Vec<_>>();
+				let rotations = track.rotations.iter().map(|v| v.to_array()).collect::<Vec<_>>();
+				let scales = track.scales.iter().map(|v| v.to_array()).collect::<Vec<_>>();
+				let outputs = [
+					(json::animation::Property::Translation, accessor(&mut root, buffer, &mut buf, bytemuck::cast_slice(&translations), translations.len(), json::accessor::Type::Vec3, None)),
+					(json::animation::Property::Rotation, accessor(&mut root, buffer, &mut buf, bytemuck::cast_slice(&rotations), rotations.len(), json::accessor::Type::Vec4, None)),
+					(json::animation::Property::Scale, accessor(&mut root, buffer, &mut buf, bytemuck::cast_slice(&scales), scales.len(), json::accessor::Type::Vec3, None)),
+				];
+				
+				for (path, output) in outputs {
+					channels.push(json::animation::Channel {
+						sampler: json::Index::new(samplers.len() as u32),
+						target: json::animation::Target {
+							node: *node,
+							path: Valid(path),
+							extensions: Default::default(),
+							extras: Default::default(),
+						},
+						extensions: Default::default(),
+						extras: Default::default(),
+					});
+					
+					samplers.push(json::animation::Sampler {
+						input,
+						interpolation: Valid(json::animation::Interpolation::Linear),
+						output,
+						extensions: Default::default(),
+						extras: Default::default(),
+					});
+				}
+			}
+			
+			root.push(json::Animation {
+				name: Some(animation.name.clone()),
+				channels,
+				samplers,
+				extensions: Default::default(),
+				extras: Default::default(),
+			});
+		}
+		
+		root.buffers[0].byte_length.0 = buf.len() as u64;
+		
+		let parent = root.push(json::Node {
+			name: Some("Skeleton".to_string()),
+			children: Some(root_nodes),
+			..Default::default()
+		});
+		
+		root.push(json::Scene {
+			name: None,
+			nodes: vec![parent],
+			extensions: Default::default(),
+			extras: Default::default(),
+		});
+		
+		let json_string = json::serialize::to_string(&root)?;
+		let json_offset = (json_string.len() + 3) & !3;
+		let glb = gltf::binary::Glb {
+			header: gltf::binary::Header {
+				magic: *b"glTF",
+				version: 2,
+				length: (json_offset + buf.len()) as u32,
+			},
+			bin: Some(std::borrow::Cow::Owned(buf)),
+			json: std::borrow::Cow::Owned(json_string.into_bytes()),
+		};
+		
+		glb.to_writer(writer)?;
+		
+		Ok(())
+	}
+}
+
+// ----------
+
+#[binrw]
+#[derive(Debug, Clone)]
+struct AnimationRaw {
+	#[br(try_map = |v: [u8; 32]| v.null_terminated())]
+	#[bw(try_map = |v: &String| v.null_terminated(32))]
+	name: String,
+	animation_type: u16,
+	havok_index: i16,
+	face: u32,
+}
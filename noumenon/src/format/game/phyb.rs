@@ -0,0 +1,610 @@
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use binrw::{binrw, BinRead, BinWrite};
+use serde::{Deserialize, Serialize};
+
+pub const EXT: &'static [&'static str] = &["phyb"];
+
+pub type Error = binrw::Error;
+
+/// Physics, the collision shapes and simulators (cloth, hair, ...) of a skeleton
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Phyb {
+	pub version: u32,
+	/// Only stored when the version isn't 0
+	pub data_type: u32,
+	pub collision: Collision,
+	pub simulation: Simulation,
+}
+
+/// Offsets in the simulation data are relative to the data itself, it can be moved around freely
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Simulation {
+	Simulators {
+		simulators: Vec<Simulator>,
+		/// Anything after the simulators, newer files store extended physics data there. Kept as is
+		#[serde(with = "hex")]
+		extra: Vec<u8>,
+	},
+	/// The simulators couldn't be read, the data is kept as is
+	Raw(#[serde(with = "hex")] Vec<u8>),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Collision {
+	pub capsules: Vec<Capsule>,
+	pub ellipsoids: Vec<Ellipsoid>,
+	pub normal_planes: Vec<NormalPlane>,
+	pub three_point_planes: Vec<ThreePointPlane>,
+	pub spheres: Vec<Sphere>,
+}
+
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capsule {
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub name: String,
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub start_bone: String,
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub end_bone: String,
+	pub start_offset: [f32; 3],
+	pub end_offset: [f32; 3],
+	pub radius: f32,
+}
+
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ellipsoid {
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub name: String,
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub bone: String,
+	pub bone_offset: [f32; 3],
+	pub unknown: [[f32; 3]; 4],
+}
+
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalPlane {
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub name: String,
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub bone: String,
+	pub bone_offset: [f32; 3],
+	pub normal: [f32; 3],
+	pub thickness: f32,
+}
+
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreePointPlane {
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub name: String,
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub bone: String,
+	pub bone_offset: [f32; 3],
+	pub unknown: [[f32; 3]; 3],
+	pub thickness: f32,
+}
+
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sphere {
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub name: String,
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub bone: String,
+	pub bone_offset: [f32; 3],
+	pub thickness: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Simulator {
+	pub params: SimulatorParams,
+	/// Names of the collision shapes the chains collide with
+	pub collisions: Vec<String>,
+	/// Names of the collision shapes the connectors collide with
+	pub collision_connectors: Vec<String>,
+	pub chains: Vec<Chain>,
+	pub connectors: Vec<Connector>,
+	pub attracts: Vec<Attract>,
+	pub pins: Vec<Pin>,
+	pub springs: Vec<Spring>,
+	pub post_alignments: Vec<PostAlignment>,
+}
+
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatorParams {
+	pub gravity: [f32; 3],
+	pub wind: [f32; 3],
+	/// Iterations per frame
+	pub constraint_loop: u16,
+	pub collision_loop: u16,
+	pub flags: u8,
+	#[brw(pad_after = 2)]
+	pub group: u8,
+}
+
+/// A chain of bones simulated together, eg. a strand of hair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chain {
+	pub dampening: f32,
+	pub max_speed: f32,
+	pub friction: f32,
+	pub collision_dampening: f32,
+	pub repulsion_strength: f32,
+	pub last_bone_offset: [f32; 3],
+	/// 0 is sphere, 1 is capsule
+	pub kind: u32,
+	/// Names of the collision shapes this chain collides with
+	pub collisions: Vec<String>,
+	pub nodes: Vec<ChainNode>,
+}
+
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainNode {
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub bone: String,
+	pub radius: f32,
+	pub attract_rate: f32,
+	pub attract_type: u32,
+	pub cone_max_angle: f32,
+	pub cone_axis_offset: [f32; 3],
+	pub constraint_offset: [f32; 3],
+	pub collision_flags: u32,
+	pub constraint_flags: u32,
+}
+
+/// Keeps two nodes of different chains apart
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Connector {
+	pub chain1: u16,
+	pub chain2: u16,
+	pub node1: u16,
+	pub node2: u16,
+	pub collision_radius: f32,
+	pub friction: f32,
+	pub dampening: f32,
+	pub repulsion: f32,
+	pub collision_flags: u32,
+	pub continuous_flags: u32,
+}
+
+/// Pulls a node towards a bone
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attract {
+	pub chain: u16,
+	pub node: u16,
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub bone: String,
+	pub bone_offset: [f32; 3],
+	pub stiffness: f32,
+}
+
+/// Fixes a node to a bone
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pin {
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub bone: String,
+	pub bone_offset: [f32; 3],
+	pub chain: u16,
+	pub node: u16,
+}
+
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Spring {
+	pub chain1: u16,
+	pub chain2: u16,
+	pub node1: u16,
+	pub node2: u16,
+	pub stretch_stiffness: f32,
+	pub compress_stiffness: f32,
+}
+
+/// Pushes a node out of a collision shape after simulating
+#[binrw]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostAlignment {
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	pub collision: String,
+	pub chain: u16,
+	pub node: u16,
+}
+
+#[binrw]
+struct Name(
+	#[br(map = read_name)]
+	#[bw(try_map = write_name)]
+	String,
+);
+
+// chains store their lists by offset like the simulators do
+#[binrw]
+struct RawChain {
+	dampening: f32,
+	max_speed: f32,
+	friction: f32,
+	collision_dampening: f32,
+	repulsion_strength: f32,
+	last_bone_offset: [f32; 3],
+	kind: u32,
+	collision_count: u16,
+	node_count: u16,
+	collision_offset: u32,
+	node_offset: u32,
+}
+
+// names are null terminated and padded with 0xFE
+fn read_name(v: [u8; 32]) -> String {
+	let end = v.iter().position(|v| *v == 0 || *v == 0xFE).unwrap_or(v.len());
+	String::from_utf8_lossy(&v[..end]).into_owned()
+}
+
+fn write_name(v: &String) -> Result<[u8; 32], crate::SizeError> {
+	if v.len() >= 32 {
+		return Err(crate::SizeError{len: v.len() as u32, max_len: 31});
+	}
+	
+	let mut name = [0xFE; 32];
+	name[..v.len()].copy_from_slice(v.as_bytes());
+	name[v.len()] = 0;
+	Ok(name)
+}
+
+impl Phyb {
+	/// Renames the bones the collision shapes and simulators use, those the mapping returns None for are kept.
+	/// Bones of raw simulation data are not touched
+	pub fn rename_bones(&mut self, map: impl Fn(&str) -> Option<String>) {
+		let collision = &mut self.collision;
+		let simulators = match &mut self.simulation {
+			Simulation::Simulators{simulators, ..} => simulators.as_mut_slice(),
+			Simulation::Raw(_) => &mut [],
+		};
+		
+		let bones = collision.capsules.iter_mut().flat_map(|v| [&mut v.start_bone, &mut v.end_bone])
+			.chain(collision.ellipsoids.iter_mut().map(|v| &mut v.bone))
+			.chain(collision.normal_planes.iter_mut().map(|v| &mut v.bone))
+			.chain(collision.three_point_planes.iter_mut().map(|v| &mut v.bone))
+			.chain(collision.spheres.iter_mut().map(|v| &mut v.bone))
+			.chain(simulators.iter_mut().flat_map(|v| v.chains.iter_mut().flat_map(|v| v.nodes.iter_mut().map(|v| &mut v.bone))
+				.chain(v.attracts.iter_mut().map(|v| &mut v.bone))
+				.chain(v.pins.iter_mut().map(|v| &mut v.bone))));
+		
+		for bone in bones {
+			if let Some(new) = map(bone) {
+				*bone = new;
+			}
+		}
+	}
+}
+
+impl BinRead for Phyb {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
+		
+		let version = r!(u32);
+		let data_type = if version != 0 {r!(u32)} else {0};
+		let collision_offset = r!(u32);
+		let simulation_offset = r!(u32);
+		
+		r!(seek collision_offset);
+		let capsule_count = r!(u8);
+		let ellipsoid_count = r!(u8);
+		let normal_plane_count = r!(u8);
+		let three_point_plane_count = r!(u8);
+		let sphere_count = r!(u8);
+		r!(move 3);
+		
+		let collision = Collision {
+			capsules: r!(Vec<Capsule>, capsule_count),
+			ellipsoids: r!(Vec<Ellipsoid>, ellipsoid_count),
+			normal_planes: r!(Vec<NormalPlane>, normal_plane_count),
+			three_point_planes: r!(Vec<ThreePointPlane>, three_point_plane_count),
+			spheres: r!(Vec<Sphere>, sphere_count),
+		};
+		
+		// we don't know the layout of every shape for certain, make sure we don't silently misread them
+		let end = collision_offset + 8 + capsule_count as u32 * 124 + ellipsoid_count as u32 * 124 + normal_plane_count as u32 * 92 + three_point_plane_count as u32 * 116 + sphere_count as u32 * 80;
+		if end != simulation_offset {
+			return Err(binrw::Error::AssertFail{pos: end as u64, message: format!("Collision data ends at {end} while the simulation data starts at {simulation_offset}")});
+		}
+		
+		// the simulator layouts aren't known for certain, keep the data as is instead of failing the whole file
+		let simulation = r!(eof);
+		let simulation = read_simulation(&mut Cursor::new(&simulation), endian).unwrap_or(Simulation::Raw(simulation));
+		
+		Ok(Self {
+			version,
+			data_type,
+			collision,
+			simulation,
+		})
+	}
+}
+
+impl BinWrite for Phyb {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		let collision = &self.collision;
+		for (kind, count) in [("capsules", collision.capsules.len()), ("ellipsoids", collision.ellipsoids.len()), ("normal planes", collision.normal_planes.len()), ("three point planes", collision.three_point_planes.len()), ("spheres", collision.spheres.len())] {
+			if count > u8::MAX as usize {
+				return Err(binrw::Error::AssertFail{pos: 0, message: format!("Phyb has {count} {kind} while the max is {}", u8::MAX)});
+			}
+		}
+		
+		self.version.write_options(writer, endian, ())?;
+		if self.version != 0 {
+			self.data_type.write_options(writer, endian, ())?;
+		}
+		
+		// offsets are filled in once we know them
+		let offsets_pos = writer.stream_position()?;
+		[0u32; 2].write_options(writer, endian, ())?;
+		
+		let collision_offset = writer.stream_position()? as u32;
+		(collision.capsules.len() as u8).write_options(writer, endian, ())?;
+		(collision.ellipsoids.len() as u8).write_options(writer, endian, ())?;
+		(collision.normal_planes.len() as u8).write_options(writer, endian, ())?;
+		(collision.three_point_planes.len() as u8).write_options(writer, endian, ())?;
+		(collision.spheres.len() as u8).write_options(writer, endian, ())?;
+		[0u8; 3].write_options(writer, endian, ())?;
+		collision.capsules.write_options(writer, endian, ())?;
+		collision.ellipsoids.write_options(writer, endian, ())?;
+		collision.normal_planes.write_options(writer, endian, ())?;
+		collision.three_point_planes.write_options(writer, endian, ())?;
+		collision.spheres.write_options(writer, endian, ())?;
+		
+		let simulation_offset = writer.stream_position()? as u32;
+		match &self.simulation {
+			Simulation::Simulators{simulators, extra} => write_simulation(writer, endian, simulators, extra)?,
+			Simulation::Raw(data) => data.write_options(writer, endian, ())?,
+		}
+		
+		let end = writer.stream_position()?;
+		writer.seek(SeekFrom::Start(offsets_pos))?;
+		[collision_offset, simulation_offset].write_options(writer, endian, ())?;
+		writer.seek(SeekFrom::Start(end))?;
+		
+		Ok(())
+	}
+}
+
+// params, list counts and list offsets
+const SIMULATOR_HEADER_SIZE: u64 = 72;
+
+fn read_simulation<R: Read + Seek>(reader: &mut R, endian: binrw::Endian) -> binrw::BinResult<Simulation> {
+	let simulator_count = u32::read_options(reader, endian, ())?;
+	// not preallocated, the count is garbage if the layout is wrong
+	let mut headers = Vec::new();
+	for _ in 0..simulator_count {
+		headers.push((SimulatorParams::read_options(reader, endian, ())?, <[u8; 8]>::read_options(reader, endian, ())?, <[u32; 8]>::read_options(reader, endian, ())?));
+	}
+	
+	let mut end = reader.stream_position()?;
+	let mut simulators = Vec::with_capacity(headers.len());
+	for (params, counts, offsets) in headers {
+		let names = |v: Vec<Name>| v.into_iter().map(|v| v.0).collect::<Vec<_>>();
+		let collisions = names(read_list(reader, endian, offsets[0], counts[0] as usize, &mut end)?);
+		let collision_connectors = names(read_list(reader, endian, offsets[1], counts[1] as usize, &mut end)?);
+		let raw_chains: Vec<RawChain> = read_list(reader, endian, offsets[2], counts[2] as usize, &mut end)?;
+		let mut chains = Vec::with_capacity(raw_chains.len());
+		for chain in raw_chains {
+			chains.push(Chain {
+				dampening: chain.dampening,
+				max_speed: chain.max_speed,
+				friction: chain.friction,
+				collision_dampening: chain.collision_dampening,
+				repulsion_strength: chain.repulsion_strength,
+				last_bone_offset: chain.last_bone_offset,
+				kind: chain.kind,
+				collisions: names(read_list(reader, endian, chain.collision_offset, chain.collision_count as usize, &mut end)?),
+				nodes: read_list(reader, endian, chain.node_offset, chain.node_count as usize, &mut end)?,
+			});
+		}
+		
+		simulators.push(Simulator {
+			params,
+			collisions,
+			collision_connectors,
+			chains,
+			connectors: read_list(reader, endian, offsets[3], counts[3] as usize, &mut end)?,
+			attracts: read_list(reader, endian, offsets[4], counts[4] as usize, &mut end)?,
+			pins: read_list(reader, endian, offsets[5], counts[5] as usize, &mut end)?,
+			springs: read_list(reader, endian, offsets[6], counts[6] as usize, &mut end)?,
+			post_alignments: read_list(reader, endian, offsets[7], counts[7] as usize, &mut end)?,
+		});
+	}
+	
+	reader.seek(SeekFrom::Start(end))?;
+	let mut extra = Vec::new();
+	reader.read_to_end(&mut extra)?;
+	
+	Ok(Simulation::Simulators {
+		simulators,
+		extra,
+	})
+}
+
+// offsets are relative to the end of the simulator count.
+// keeps track of the furthest point read so we know where the data after the simulators starts
+fn read_list<R: Read + Seek, T: for<'a> BinRead<Args<'a> = ()>>(reader: &mut R, endian: binrw::Endian, offset: u32, count: usize, end: &mut u64) -> binrw::BinResult<Vec<T>> {
+	if count == 0 {return Ok(Vec::new())}
+	
+	reader.seek(SeekFrom::Start(4 + offset as u64))?;
+	let mut list = Vec::with_capacity(count);
+	for _ in 0..count {
+		list.push(T::read_options(reader, endian, ())?);
+	}
+	*end = (*end).max(reader.stream_position()?);
+	
+	Ok(list)
+}
+
+// the headers are written last since they need the offsets of the lists
+fn write_simulation<W: Write + Seek>(writer: &mut W, endian: binrw::Endian, simulators: &[Simulator], extra: &[u8]) -> binrw::BinResult<()> {
+	for (i, simulator) in simulators.iter().enumerate() {
+		let lists = [("collisions", simulator.collisions.len()), ("collision connectors", simulator.collision_connectors.len()), ("chains", simulator.chains.len()), ("connectors", simulator.connectors.len()), ("attracts", simulator.attracts.len()), ("pins", simulator.pins.len()), ("springs", simulator.springs.len()), ("post alignments", simulator.post_alignments.len())];
+		for (kind, count) in lists {
+			if count > u8::MAX as usize {
+				return Err(binrw::Error::AssertFail{pos: 0, message: format!("Simulator {i} has {count} {kind} while the max is {}", u8::MAX)});
+			}
+		}
+		
+		for (j, chain) in simulator.chains.iter().enumerate() {
+			for (kind, count) in [("collisions", chain.collisions.len()), ("nodes", chain.nodes.len())] {
+				if count > u16::MAX as usize {
+					return Err(binrw::Error::AssertFail{pos: 0, message: format!("Chain {j} of simulator {i} has {count} {kind} while the max is {}", u16::MAX)});
+				}
+			}
+		}
+	}
+	
+	let base = writer.stream_position()? + 4;
+	(simulators.len() as u32).write_options(writer, endian, ())?;
+	writer.seek(SeekFrom::Current((simulators.len() as u64 * SIMULATOR_HEADER_SIZE) as i64))?;
+	
+	let names = |v: &[String]| v.iter().map(|v| Name(v.clone())).collect::<Vec<_>>();
+	let mut headers = Vec::with_capacity(simulators.len());
+	for simulator in simulators {
+		let mut offsets = [0u32; 8];
+		let offset = |writer: &mut W| -> binrw::BinResult<u32> {Ok((writer.stream_position()? - base) as u32)};
+		
+		offsets[0] = offset(writer)?;
+		names(&simulator.collisions).write_options(writer, endian, ())?;
+		offsets[1] = offset(writer)?;
+		names(&simulator.collision_connectors).write_options(writer, endian, ())?;
+		
+		let mut raw_chains = Vec::with_capacity(simulator.chains.len());
+		for chain in &simulator.chains {
+			let collision_offset = offset(writer)?;
+			names(&chain.collisions).write_options(writer, endian, ())?;
+			let node_offset = offset(writer)?;
+			chain.nodes.write_options(writer, endian, ())?;
+			raw_chains.push(RawChain {
+				dampening: chain.dampening,
+				max_speed: chain.max_speed,
+				friction: chain.friction,
+				collision_dampening: chain.collision_dampening,
+				repulsion_strength: chain.repulsion_strength,
+				last_bone_offset: chain.last_bone_offset,
+				kind: chain.kind,
+				collision_count: chain.collisions.len() as u16,
+				node_count: chain.nodes.len() as u16,
+				collision_offset,
+				node_offset,
+			});
+		}
+		
+		offsets[2] = offset(writer)?;
+		raw_chains.write_options(writer, endian, ())?;
+		offsets[3] = offset(writer)?;
+		simulator.connectors.write_options(writer, endian, ())?;
+		offsets[4] = offset(writer)?;
+		simulator.attracts.write_options(writer, endian, ())?;
+		offsets[5] = offset(writer)?;
+		simulator.pins.write_options(writer, endian, ())?;
+		offsets[6] = offset(writer)?;
+		simulator.springs.write_options(writer, endian, ())?;
+		offsets[7] = offset(writer)?;
+		simulator.post_alignments.write_options(writer, endian, ())?;
+		
+		let counts = [simulator.collisions.len(), simulator.collision_connectors.len(), simulator.chains.len(), simulator.connectors.len(), simulator.attracts.len(), simulator.pins.len(), simulator.springs.len(), simulator.post_alignments.len()];
+		headers.push((&simulator.params, counts.map(|v| v as u8), offsets));
+	}
+	
+	extra.write_options(writer, endian, ())?;
+	
+	let end = writer.stream_position()?;
+	writer.seek(SeekFrom::Start(base))?;
+	for (params, counts, offsets) in headers {
+		params.write_options(writer, endian, ())?;
+		counts.write_options(writer, endian, ())?;
+		offsets.write_options(writer, endian, ())?;
+	}
+	writer.seek(SeekFrom::Start(end))?;
+	
+	Ok(())
+}
+
+impl crate::File for Phyb {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Phyb::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Phyb {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Phyb {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Phyb::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Json for Phyb {
+	type Base = ();
+	
+	fn read<T>(reader: &mut T, _base: Self::Base) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(serde_json::from_reader(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		serde_json::to_writer_pretty(writer, self)?;
+		
+		Ok(())
+	}
+}
+
+// raw data as a hex string instead of a huge array of numbers
+mod hex {
+	use serde::{Deserialize, Deserializer, Serializer};
+	
+	pub fn serialize<S: Serializer>(v: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&v.iter().map(|v| format!("{v:02x}")).collect::<String>())
+	}
+	
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+		let v = String::deserialize(deserializer)?;
+		(0..v.len())
+			.step_by(2)
+			.map(|i| v.get(i..i + 2).and_then(|v| u8::from_str_radix(v, 16).ok()).ok_or_else(|| serde::de::Error::custom(format!("{v} is not valid hex"))))
+			.collect()
+	}
+}
\ No newline at end of file
@@ -0,0 +1,604 @@
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use binrw::{BinRead, BinWrite};
+
+pub const EXT: &'static [&'static str] = &["scd"];
+
+pub type Error = binrw::Error;
+
+/// Sound container. Only the sounds are exposed, the tables describing how they are played are kept as is
+#[derive(Debug, Clone)]
+pub struct Scd {
+	/// Sounds can be replaced but not added or removed, the tables reference them by index
+	pub sounds: Vec<Sound>,
+	
+	// everything before the first sound
+	header: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+	Empty,
+	Pcm,
+	Vorbis,
+	MsAdpcm,
+	Unknown(i32),
+}
+
+impl Codec {
+	fn from_id(id: i32) -> Self {
+		match id {
+			-1 => Self::Empty,
+			0x01 => Self::Pcm,
+			0x06 => Self::Vorbis,
+			0x0C => Self::MsAdpcm,
+			_ => Self::Unknown(id),
+		}
+	}
+	
+	fn id(&self) -> i32 {
+		match self {
+			Self::Empty => -1,
+			Self::Pcm => 0x01,
+			Self::Vorbis => 0x06,
+			Self::MsAdpcm => 0x0C,
+			Self::Unknown(id) => *id,
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct Sound {
+	pub codec: Codec,
+	pub channels: u32,
+	pub sample_rate: u32,
+	pub loop_start: u32,
+	pub loop_end: u32,
+	/// Codec specific header. A WAVEFORMATEX for ms-adpcm, the seek table and (possibly encrypted) vorbis header for vorbis
+	pub extra: Vec<u8>,
+	pub data: Vec<u8>,
+	
+	// markers and such, dropped when replacing the sound since they wouldn't line up anymore
+	aux_chunks: Vec<([u8; 4], Vec<u8>)>,
+	unknown: u16,
+}
+
+impl Sound {
+	pub fn from_ogg(data: &[u8]) -> Result<Self, crate::Error> {
+		let pages = ogg_pages(data)?;
+		
+		// the identification, comment and setup packets make up the header, audio always starts on a new page
+		let mut packets = 0;
+		let mut header_size = 0;
+		for page in &pages {
+			if packets >= 3 {break}
+			packets += page.packets;
+			header_size = page.offset + page.size;
+		}
+		
+		let identification = &data[pages.first().map_or(0, |v| v.body)..];
+		if packets < 3 || identification.get(0..7) != Some(b"\x01vorbis") || identification.len() < 16 {
+			return Err(crate::Error::InvalidData("Ogg does not contain a vorbis stream".to_string()));
+		}
+		
+		// byte offset of every audio page
+		let seek_table = pages
+			.iter()
+			.filter(|v| v.offset >= header_size)
+			.flat_map(|v| ((v.offset - header_size) as u32).to_le_bytes())
+			.collect::<Vec<_>>();
+		
+		let mut extra = Vec::with_capacity(0x20 + seek_table.len() + header_size);
+		// version 2 with a xor key of 0, so the header isn't encrypted
+		extra.extend_from_slice(&[2, 0x20, 0, 0]);
+		extra.extend_from_slice(&[0; 12]);
+		extra.extend_from_slice(&(seek_table.len() as u32).to_le_bytes());
+		extra.extend_from_slice(&(header_size as u32).to_le_bytes());
+		extra.extend_from_slice(&[0; 8]);
+		extra.extend_from_slice(&seek_table);
+		extra.extend_from_slice(&data[..header_size]);
+		
+		Ok(Self {
+			codec: Codec::Vorbis,
+			channels: identification[11] as u32,
+			sample_rate: u32::from_le_bytes(identification[12..16].try_into().unwrap()),
+			loop_start: 0,
+			loop_end: 0,
+			extra,
+			data: data[header_size..].to_vec(),
+			aux_chunks: Vec::new(),
+			unknown: 0,
+		})
+	}
+	
+	/// Accepts ms-adpcm and 16 bit pcm, pcm is encoded to ms-adpcm
+	pub fn from_wav(data: &[u8]) -> Result<Self, crate::Error> {
+		if data.get(0..4) != Some(b"RIFF") || data.get(8..12) != Some(b"WAVE") {
+			return Err(crate::Error::InvalidData("Not a wav file".to_string()));
+		}
+		
+		let mut format = None;
+		let mut samples = None;
+		let mut offset = 12;
+		while let Some(header) = data.get(offset..offset + 8) {
+			let size = u32::from_le_bytes(header[4..8].try_into().unwrap()) as usize;
+			let chunk = &data[offset + 8..(offset + 8 + size).min(data.len())];
+			match &header[0..4] {
+				b"fmt " => format = Some(chunk),
+				b"data" => samples = Some(chunk),
+				_ => {}
+			}
+			
+			offset += 8 + size + size % 2;
+		}
+		
+		let (Some(format), Some(samples)) = (format, samples) else {
+			return Err(crate::Error::InvalidData("Wav is missing the format or data chunk".to_string()));
+		};
+		
+		if format.len() < 16 {
+			return Err(crate::Error::InvalidData("Wav format chunk is too small".to_string()));
+		}
+		
+		let tag = u16::from_le_bytes([format[0], format[1]]);
+		let channels = u16::from_le_bytes([format[2], format[3]]);
+		let sample_rate = u32::from_le_bytes(format[4..8].try_into().unwrap());
+		let bits = u16::from_le_bytes([format[14], format[15]]);
+		// WAVE_FORMAT_EXTENSIBLE stores the actual format in the first 2 bytes of the guid
+		let pcm = tag == 1 || (tag == 0xFFFE && format.get(24..26) == Some(&[1, 0]));
+		
+		let (extra, data) = match tag {
+			_ if channels == 0 => return Err(crate::Error::InvalidData("Wav has no channels".to_string())),
+			2 => (format.to_vec(), samples.to_vec()),
+			_ if pcm && bits == 16 => {
+				let samples = samples
+					.chunks_exact(2)
+					.map(|v| i16::from_le_bytes([v[0], v[1]]))
+					.collect::<Vec<_>>();
+				encode_ms_adpcm(&samples, channels as usize, sample_rate)
+			}
+			_ => return Err(crate::Error::InvalidData("Only 16 bit pcm and ms-adpcm wavs are supported".to_string())),
+		};
+		
+		Ok(Self {
+			codec: Codec::MsAdpcm,
+			channels: channels as u32,
+			sample_rate,
+			loop_start: 0,
+			loop_end: 0,
+			extra,
+			data,
+			aux_chunks: Vec::new(),
+			unknown: 0,
+		})
+	}
+	
+	pub fn to_ogg(&self) -> Result<Vec<u8>, crate::Error> {
+		if self.codec != Codec::Vorbis {
+			return Err(crate::Error::InvalidData(format!("{:?} sounds can't be exported as ogg", self.codec)));
+		}
+		
+		let invalid = || crate::Error::InvalidData("Vorbis header is out of bounds".to_string());
+		let header = self.extra.get(0..0x20).ok_or_else(invalid)?;
+		let seek_table_size = u32::from_le_bytes(header[0x10..0x14].try_into().unwrap()) as usize;
+		let header_size = u32::from_le_bytes(header[0x14..0x18].try_into().unwrap()) as usize;
+		let mut ogg = self.extra.get(0x20 + seek_table_size..0x20 + seek_table_size + header_size).ok_or_else(invalid)?.to_vec();
+		match header[0] {
+			2 => ogg.iter_mut().for_each(|v| *v ^= header[2]),
+			3 => return Err(crate::Error::InvalidData("Sounds with fully encrypted vorbis data are not supported".to_string())),
+			_ => {}
+		}
+		
+		ogg.extend_from_slice(&self.data);
+		
+		Ok(ogg)
+	}
+	
+	pub fn to_wav(&self) -> Result<Vec<u8>, crate::Error> {
+		let format = match self.codec {
+			Codec::MsAdpcm => self.extra.clone(),
+			Codec::Pcm => {
+				let block_align = self.channels * 2;
+				let mut format = Vec::with_capacity(16);
+				format.extend_from_slice(&1u16.to_le_bytes());
+				format.extend_from_slice(&(self.channels as u16).to_le_bytes());
+				format.extend_from_slice(&self.sample_rate.to_le_bytes());
+				format.extend_from_slice(&(self.sample_rate * block_align).to_le_bytes());
+				format.extend_from_slice(&(block_align as u16).to_le_bytes());
+				format.extend_from_slice(&16u16.to_le_bytes());
+				format
+			}
+			_ => return Err(crate::Error::InvalidData(format!("{:?} sounds can't be exported as wav", self.codec))),
+		};
+		
+		let chunk_size = |v: &[u8]| 8 + v.len() + v.len() % 2;
+		let mut wav = Vec::with_capacity(12 + chunk_size(&format) + chunk_size(&self.data));
+		wav.extend_from_slice(b"RIFF");
+		wav.extend_from_slice(&((4 + chunk_size(&format) + chunk_size(&self.data)) as u32).to_le_bytes());
+		wav.extend_from_slice(b"WAVE");
+		for (id, chunk) in [(b"fmt ", &format), (b"data", &self.data)] {
+			wav.extend_from_slice(id);
+			wav.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+			wav.extend_from_slice(chunk);
+			if chunk.len() % 2 == 1 {
+				wav.push(0);
+			}
+		}
+		
+		Ok(wav)
+	}
+}
+
+impl BinRead for Sound {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
+		
+		let data_size = r!(u32);
+		let channels = r!(u32);
+		let sample_rate = r!(u32);
+		let codec = Codec::from_id(r!(i32));
+		let loop_start = r!(u32);
+		let loop_end = r!(u32);
+		let extra_size = r!(u32);
+		let aux_count = r!(u16);
+		let unknown = r!(u16);
+		
+		// aux chunks are part of the extra data, their size includes the id and size
+		let mut aux_chunks = Vec::with_capacity(aux_count as usize);
+		let mut aux_size = 0;
+		for _ in 0..aux_count {
+			let id = r!([u8; 4]);
+			let size = r!(u32);
+			aux_chunks.push((id, r!(Vec<u8>, size.saturating_sub(8))));
+			aux_size += size.max(8);
+		}
+		
+		let extra = r!(Vec<u8>, extra_size.saturating_sub(aux_size));
+		let data = r!(Vec<u8>, data_size);
+		
+		Ok(Self {
+			codec,
+			channels,
+			sample_rate,
+			loop_start,
+			loop_end,
+			extra,
+			data,
+			aux_chunks,
+			unknown,
+		})
+	}
+}
+
+impl BinWrite for Sound {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		let aux_size = self.aux_chunks.iter().map(|(_, v)| 8 + v.len()).sum::<usize>();
+		
+		(self.data.len() as u32).write_options(writer, endian, ())?;
+		self.channels.write_options(writer, endian, ())?;
+		self.sample_rate.write_options(writer, endian, ())?;
+		self.codec.id().write_options(writer, endian, ())?;
+		self.loop_start.write_options(writer, endian, ())?;
+		self.loop_end.write_options(writer, endian, ())?;
+		((aux_size + self.extra.len()) as u32).write_options(writer, endian, ())?;
+		(self.aux_chunks.len() as u16).write_options(writer, endian, ())?;
+		self.unknown.write_options(writer, endian, ())?;
+		for (id, data) in &self.aux_chunks {
+			id.write_options(writer, endian, ())?;
+			((8 + data.len()) as u32).write_options(writer, endian, ())?;
+			data.write_options(writer, endian, ())?;
+		}
+		
+		self.extra.write_options(writer, endian, ())?;
+		self.data.write_options(writer, endian, ())?;
+		
+		Ok(())
+	}
+}
+
+impl Scd {
+	/// The sound that gets replaced when importing an ogg or wav, the first one that isn't empty
+	pub fn main_sound(&self) -> Option<usize> {
+		self.sounds.iter().position(|v| v.codec != Codec::Empty)
+	}
+	
+	fn main_sound_checked(&self) -> Result<usize, crate::Error> {
+		self.main_sound().ok_or_else(|| crate::Error::InvalidData("Scd does not contain any sounds".to_string()))
+	}
+}
+
+impl BinRead for Scd {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		let size = reader.seek(SeekFrom::End(0))?;
+		reader.seek(SeekFrom::Start(0))?;
+		simple_reader!(reader, endian);
+		
+		let magic = r!([u8; 8]);
+		if &magic != b"SEDBSSCF" {
+			return Err(binrw::Error::BadMagic{pos: 0, found: Box::new(magic)});
+		}
+		
+		r!(seek 0x0E);
+		let tables_offset = r!(u16);
+		r!(seek tables_offset as u64 + 0x04);
+		let sound_count = r!(u16);
+		r!(seek tables_offset as u64 + 0x0C);
+		let sounds_offset = r!(u32);
+		r!(seek sounds_offset);
+		let offsets = r!(Vec<u32>, sound_count);
+		
+		// sounds are the last thing in the file
+		let header_size = offsets.iter().min().map_or(size, |v| *v as u64);
+		r!(seek 0);
+		let header = r!(Vec<u8>, header_size);
+		
+		let mut sounds = Vec::with_capacity(offsets.len());
+		for offset in offsets {
+			r!(seek offset);
+			sounds.push(r!(Sound));
+		}
+		
+		Ok(Self {
+			sounds,
+			header,
+		})
+	}
+}
+
+impl BinWrite for Scd {
+	type Args<'a> = ();
+	
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		let mut sounds = Vec::with_capacity(self.sounds.len());
+		for sound in &self.sounds {
+			let mut data = Cursor::new(Vec::new());
+			sound.write_options(&mut data, endian, ())?;
+			let mut data = data.into_inner();
+			data.resize(data.len().next_multiple_of(16), 0);
+			sounds.push(data);
+		}
+		
+		// update the sound offsets and file size in the header
+		let mut header = Cursor::new(self.header.clone());
+		header.seek(SeekFrom::Start(0x0E))?;
+		let tables_offset = u16::read_options(&mut header, endian, ())?;
+		header.seek(SeekFrom::Start(tables_offset as u64 + 0x04))?;
+		let sound_count = u16::read_options(&mut header, endian, ())?;
+		if sound_count as usize != self.sounds.len() {
+			return Err(binrw::Error::AssertFail{pos: 0, message: format!("Scd has {} sounds while its tables reference {sound_count}", self.sounds.len())});
+		}
+		
+		header.seek(SeekFrom::Start(tables_offset as u64 + 0x0C))?;
+		let sounds_offset = u32::read_options(&mut header, endian, ())?;
+		header.seek(SeekFrom::Start(sounds_offset as u64))?;
+		let mut offset = self.header.len();
+		for sound in &sounds {
+			(offset as u32).write_options(&mut header, endian, ())?;
+			offset += sound.len();
+		}
+		
+		header.seek(SeekFrom::Start(0x10))?;
+		(offset as u32).write_options(&mut header, endian, ())?;
+		
+		header.into_inner().write_options(writer, endian, ())?;
+		for sound in sounds {
+			sound.write_options(writer, endian, ())?;
+		}
+		
+		Ok(())
+	}
+}
+
+impl crate::File for Scd {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Scd::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Scd {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Scd {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Scd::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		self.write_le(writer)?;
+		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Ogg for Scd {
+	/// The scd to replace the main sound of
+	type Base = Scd;
+	
+	fn read<T>(reader: &mut T, base: Self::Base) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		let mut data = Vec::new();
+		reader.read_to_end(&mut data)?;
+		
+		let mut scd = base;
+		let index = scd.main_sound_checked()?;
+		scd.sounds[index] = Sound::from_ogg(&data)?;
+		
+		Ok(scd)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		writer.write_all(&self.sounds[self.main_sound_checked()?].to_ogg()?)?;
+		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Wav for Scd {
+	/// The scd to replace the main sound of
+	type Base = Scd;
+	
+	fn read<T>(reader: &mut T, base: Self::Base) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		let mut data = Vec::new();
+		reader.read_to_end(&mut data)?;
+		
+		let mut scd = base;
+		let index = scd.main_sound_checked()?;
+		scd.sounds[index] = Sound::from_wav(&data)?;
+		
+		Ok(scd)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		writer.write_all(&self.sounds[self.main_sound_checked()?].to_wav()?)?;
+		
+		Ok(())
+	}
+}
+
+// ----------
+
+struct OggPage {
+	offset: usize,
+	size: usize,
+	/// Offset of the data after the segment table
+	body: usize,
+	/// Amount of packets ending in this page
+	packets: usize,
+}
+
+fn ogg_pages(data: &[u8]) -> Result<Vec<OggPage>, crate::Error> {
+	let mut pages = Vec::new();
+	let mut offset = 0;
+	while offset < data.len() {
+		let invalid = || crate::Error::InvalidData(format!("Invalid ogg page at {offset}"));
+		let header = data.get(offset..offset + 27).ok_or_else(invalid)?;
+		if &header[0..4] != b"OggS" {
+			return Err(invalid());
+		}
+		
+		let segments = data.get(offset + 27..offset + 27 + header[26] as usize).ok_or_else(invalid)?;
+		let body = offset + 27 + segments.len();
+		let size = body - offset + segments.iter().map(|v| *v as usize).sum::<usize>();
+		if offset + size > data.len() {
+			return Err(invalid());
+		}
+		
+		pages.push(OggPage {
+			offset,
+			size,
+			body,
+			packets: segments.iter().filter(|v| **v < 255).count(),
+		});
+		
+		offset += size;
+	}
+	
+	Ok(pages)
+}
+
+// ----------
+
+const ADPCM_COEFFICIENTS: [[i32; 2]; 7] = [[256, 0], [512, -256], [0, 0], [192, 64], [240, 0], [460, -208], [392, -232]];
+const ADPCM_ADAPTATION: [i32; 16] = [230, 230, 230, 230, 307, 409, 512, 614, 768, 614, 512, 409, 307, 230, 230, 230];
+
+struct AdpcmBlock {
+	predictor: u8,
+	delta: i32,
+	sample1: i32,
+	sample2: i32,
+	nibbles: Vec<u8>,
+	error: i64,
+}
+
+/// Encodes interleaved pcm, returns the WAVEFORMATEX and the encoded blocks
+fn encode_ms_adpcm(samples: &[i16], channels: usize, sample_rate: u32) -> (Vec<u8>, Vec<u8>) {
+	let block_align = 256 * channels * (sample_rate as usize / 11025).max(1);
+	let block_samples = (block_align - 7 * channels) * 2 / channels + 2;
+	
+	let mut format = Vec::with_capacity(50);
+	format.extend_from_slice(&2u16.to_le_bytes());
+	format.extend_from_slice(&(channels as u16).to_le_bytes());
+	format.extend_from_slice(&sample_rate.to_le_bytes());
+	format.extend_from_slice(&((sample_rate as usize * block_align / block_samples) as u32).to_le_bytes());
+	format.extend_from_slice(&(block_align as u16).to_le_bytes());
+	format.extend_from_slice(&4u16.to_le_bytes());
+	format.extend_from_slice(&32u16.to_le_bytes());
+	format.extend_from_slice(&(block_samples as u16).to_le_bytes());
+	format.extend_from_slice(&(ADPCM_COEFFICIENTS.len() as u16).to_le_bytes());
+	for [coef1, coef2] in ADPCM_COEFFICIENTS {
+		format.extend_from_slice(&(coef1 as i16).to_le_bytes());
+		format.extend_from_slice(&(coef2 as i16).to_le_bytes());
+	}
+	
+	let frames = samples.len() / channels;
+	let mut data = Vec::with_capacity(frames.div_ceil(block_samples) * block_align);
+	for start in (0..frames).step_by(block_samples) {
+		// the last block is padded with silence, use whichever predictor gets the closest
+		let blocks = (0..channels)
+			.map(|channel| {
+				let samples = (start..start + block_samples)
+					.map(|i| if i < frames {samples[i * channels + channel] as i32} else {0})
+					.collect::<Vec<_>>();
+				(0..ADPCM_COEFFICIENTS.len())
+					.map(|predictor| encode_adpcm_block(&samples, predictor))
+					.min_by_key(|v| v.error)
+					.unwrap()
+			}).collect::<Vec<_>>();
+		
+		// sample1 is the second sample, decoders output sample2 first
+		data.extend(blocks.iter().map(|v| v.predictor));
+		blocks.iter().for_each(|v| data.extend_from_slice(&(v.delta as i16).to_le_bytes()));
+		blocks.iter().for_each(|v| data.extend_from_slice(&(v.sample1 as i16).to_le_bytes()));
+		blocks.iter().for_each(|v| data.extend_from_slice(&(v.sample2 as i16).to_le_bytes()));
+		
+		// channels are interleaved per nibble, high nibble first
+		let nibble = |i: usize| blocks[i % channels].nibbles[i / channels];
+		for i in (0..(block_samples - 2) * channels).step_by(2) {
+			data.push((nibble(i) << 4) | nibble(i + 1));
+		}
+	}
+	
+	(format, data)
+}
+
+fn encode_adpcm_block(samples: &[i32], predictor: usize) -> AdpcmBlock {
+	let [coef1, coef2] = ADPCM_COEFFICIENTS[predictor];
+	let mut sample1 = samples[1];
+	let mut sample2 = samples[0];
+	let initial_delta = ((samples[2] - ((sample1 * coef1 + sample2 * coef2) >> 8)).abs() / 4).clamp(16, i16::MAX as i32);
+	let mut delta = initial_delta;
+	let mut nibbles = Vec::with_capacity(samples.len() - 2);
+	let mut error = 0;
+	for &sample in &samples[2..] {
+		let predicted = (sample1 * coef1 + sample2 * coef2) >> 8;
+		let diff = sample - predicted;
+		let nibble = ((diff + diff.signum() * delta / 2) / delta).clamp(-8, 7);
+		let decoded = (predicted + nibble * delta).clamp(i16::MIN as i32, i16::MAX as i32);
+		error += ((sample - decoded) as i64).pow(2);
+		delta = ((ADPCM_ADAPTATION[(nibble & 0xF) as usize] * delta) >> 8).max(16);
+		sample2 = sample1;
+		sample1 = decoded;
+		nibbles.push((nibble & 0xF) as u8);
+	}
+	
+	AdpcmBlock {
+		predictor: predictor as u8,
+		delta: initial_delta,
+		sample1: samples[1],
+		sample2: samples[0],
+		nibbles,
+		error,
+	}
+}
\ No newline at end of file
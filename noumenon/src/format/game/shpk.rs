@@ -0,0 +1,340 @@
+use std::io::{Read, Seek, Write};
+use binrw::{binrw, BinRead};
+use crate::NullReader;
+
+pub const EXT: &'static [&'static str] = &["shpk"];
+
+pub type Error = binrw::Error;
+
+// https://github.com/Ottermandias/Penumbra.GameData/blob/main/Files/ShpkFile.cs
+const VERSION_DAWNTRAIL: u32 = 0x0D01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectX {
+	Dx9,
+	Dx11,
+}
+
+#[derive(Debug, Clone)]
+pub struct Shpk {
+	pub version: u32,
+	pub directx: DirectX,
+	pub vertex_shaders: Vec<Shader>,
+	pub pixel_shaders: Vec<Shader>,
+	pub material_params_size: u32,
+	pub material_params: Vec<MaterialParam>,
+	pub constants: Vec<Resource>,
+	pub samplers: Vec<Resource>,
+	pub textures: Vec<Resource>,
+	pub uavs: Vec<Resource>,
+	pub system_keys: Vec<Key>,
+	pub scene_keys: Vec<Key>,
+	pub material_keys: Vec<Key>,
+	pub sub_view_keys: Vec<Key>,
+	pub nodes: Vec<Node>,
+	/// (selector, node index)
+	pub node_aliases: Vec<(u32, u32)>,
+	
+	// dawntrail additions we dont know the meaning of yet
+	pub _unknown: [u32; 3],
+}
+
+#[derive(Debug, Clone)]
+pub struct Shader {
+	/// Compiled shader bytecode
+	pub blob: Vec<u8>,
+	pub constants: Vec<Resource>,
+	pub samplers: Vec<Resource>,
+	pub uavs: Vec<Resource>,
+	pub textures: Vec<Resource>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Resource {
+	pub id: u32,
+	pub name: String,
+	pub is_texture: bool,
+	pub slot: u16,
+	pub size: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct MaterialParam {
+	/// Crc32 of the parameter name, same as the id of a material constant
+	pub id: u32,
+	/// In bytes
+	pub offset: u16,
+	/// In bytes
+	pub size: u16,
+	pub default: Vec<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Key {
+	pub id: u32,
+	pub default: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+	pub selector: u32,
+	pub pass_indices: [u8; 16],
+	pub system_keys: Vec<u32>,
+	pub scene_keys: Vec<u32>,
+	pub material_keys: Vec<u32>,
+	pub sub_view_keys: Vec<u32>,
+	pub passes: Vec<Pass>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pass {
+	pub id: u32,
+	pub vertex_shader: u32,
+	pub pixel_shader: u32,
+}
+
+impl Shpk {
+	pub fn material_param(&self, id: u32) -> Option<&MaterialParam> {
+		self.material_params.iter().find(|v| v.id == id)
+	}
+	
+	/// All values the nodes of this shader use for a material key, the default is always included
+	pub fn material_key_values(&self, id: u32) -> Vec<u32> {
+		let Some(index) = self.material_keys.iter().position(|v| v.id == id) else {return Vec::new()};
+		
+		let mut values = vec![self.material_keys[index].default];
+		for node in &self.nodes {
+			let Some(value) = node.material_keys.get(index) else {continue};
+			if !values.contains(value) {
+				values.push(*value);
+			}
+		}
+		
+		values.sort();
+		values
+	}
+	
+	/// Name of a sampler used by any of the shaders, the id is the same as the one of a material sampler
+	pub fn sampler_name(&self, id: u32) -> Option<&str> {
+		self.samplers
+			.iter()
+			.chain(self.textures.iter())
+			.find(|v| v.id == id)
+			.map(|v| v.name.as_str())
+	}
+	
+	/// Names of all resources used by all shaders
+	pub fn resource_names(&self) -> impl Iterator<Item = &str> {
+		self.constants
+			.iter()
+			.chain(self.samplers.iter())
+			.chain(self.textures.iter())
+			.chain(self.uavs.iter())
+			.chain(self.vertex_shaders.iter().chain(self.pixel_shaders.iter()).flat_map(|v| v.constants.iter().chain(v.samplers.iter()).chain(v.uavs.iter()).chain(v.textures.iter())))
+			.map(|v| v.name.as_str())
+	}
+}
+
+impl BinRead for Shpk {
+	type Args<'a> = ();
+	
+	fn read_options<R: Read + Seek>(reader: &mut R, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<Self> {
+		simple_reader!(reader, endian);
+		
+		let _magic = r!(u32);
+		let version = r!(u32);
+		let directx = match &r!([u8; 4]) {
+			b"DX9\0" => DirectX::Dx9,
+			b"DX11" => DirectX::Dx11,
+			_ => return Err(binrw::Error::AssertFail{pos: 8, message: "Unknown DirectX version".to_string()}),
+		};
+		let _file_size = r!(u32);
+		let blobs_offset = r!(u32);
+		let strings_offset = r!(u32);
+		let vertex_shader_count = r!(u32);
+		let pixel_shader_count = r!(u32);
+		let material_params_size = r!(u32);
+		let material_param_count = r!(u16);
+		let has_material_param_defaults = r!(u16) != 0;
+		let constant_count = r!(u16);
+		let _unk = r!(u16);
+		let sampler_count = r!(u16);
+		let texture_count = r!(u16);
+		let uav_count = r!(u32);
+		let system_key_count = r!(u32);
+		let scene_key_count = r!(u32);
+		let material_key_count = r!(u32);
+		let node_count = r!(u32);
+		let node_alias_count = r!(u32);
+		let dawntrail = version >= VERSION_DAWNTRAIL;
+		let unknown = if dawntrail {r!([u32; 3])} else {[0; 3]};
+		
+		let blobs_size = strings_offset.checked_sub(blobs_offset).ok_or_else(|| binrw::Error::AssertFail{pos: 0x10, message: format!("Strings start at {strings_offset} before the blobs at {blobs_offset}")})?;
+		r!(seek blobs_offset);
+		let blobs = r!(Vec<u8>, blobs_size);
+		let strings = r!(eof);
+		r!(seek if dawntrail {0x54} else {0x48});
+		
+		let resources = |raw: Vec<ResourceRaw>| -> binrw::BinResult<Vec<Resource>> {
+			raw.into_iter()
+				.map(|v| Ok(Resource {
+					id: v.id,
+					name: strings.get(v.string_offset as usize..v.string_offset as usize + v.string_size as usize)
+						.ok_or_else(|| binrw::Error::AssertFail{pos: 0, message: "Resource name is out of bounds".to_string()})?
+						.null_terminated()
+						.map_err(|e| binrw::Error::Custom{pos: 0, err: Box::new(e)})?,
+					is_texture: v.is_texture != 0,
+					slot: v.slot,
+					size: v.size,
+				})).collect()
+		};
+		
+		let shader_count = vertex_shader_count.checked_add(pixel_shader_count).ok_or_else(|| binrw::Error::AssertFail{pos: 0x18, message: format!("Invalid shader counts {vertex_shader_count} and {pixel_shader_count}")})?;
+		let mut shaders = Vec::new();
+		for _ in 0..shader_count {
+			let blob_offset = r!(u32);
+			let blob_size = r!(u32);
+			let constant_count = r!(u16);
+			let sampler_count = r!(u16);
+			let uav_count = r!(u16);
+			let texture_count = r!(u16);
+			let unknown = if dawntrail {r!(u32)} else {0};
+			if unknown != 0 {
+				return Err(binrw::Error::AssertFail{pos: 0, message: "Shaders with unknown dawntrail data are not supported".to_string()});
+			}
+			
+			shaders.push(Shader {
+				blob: blob_offset.checked_add(blob_size).and_then(|end| blobs.get(blob_offset as usize..end as usize))
+					.ok_or_else(|| binrw::Error::AssertFail{pos: 0, message: "Shader blob is out of bounds".to_string()})?
+					.to_vec(),
+				constants: resources(r!(Vec<ResourceRaw>, constant_count))?,
+				samplers: resources(r!(Vec<ResourceRaw>, sampler_count))?,
+				uavs: resources(r!(Vec<ResourceRaw>, uav_count))?,
+				textures: resources(r!(Vec<ResourceRaw>, texture_count))?,
+			});
+		}
+		let pixel_shaders = shaders.split_off(vertex_shader_count as usize);
+		let vertex_shaders = shaders;
+		
+		let material_params_raw = r!(Vec<MaterialParamRaw>, material_param_count);
+		let defaults = if has_material_param_defaults {r!(Vec<f32>, material_params_size / 4)} else {vec![0.0; material_params_size as usize / 4]};
+		let material_params = material_params_raw
+			.into_iter()
+			.map(|v| MaterialParam {
+				id: v.id,
+				offset: v.offset,
+				size: v.size,
+				default: defaults.get(v.offset as usize / 4..(v.offset as usize + v.size as usize) / 4).map_or_else(|| vec![0.0; v.size as usize / 4], |v| v.to_vec()),
+			}).collect();
+		
+		let constants = resources(r!(Vec<ResourceRaw>, constant_count))?;
+		let samplers = resources(r!(Vec<ResourceRaw>, sampler_count))?;
+		let textures = resources(r!(Vec<ResourceRaw>, texture_count))?;
+		let uavs = resources(r!(Vec<ResourceRaw>, uav_count))?;
+		
+		let system_keys = r!(Vec<KeyRaw>, system_key_count).into_iter().map(|v| Key{id: v.id, default: v.default}).collect();
+		let scene_keys = r!(Vec<KeyRaw>, scene_key_count).into_iter().map(|v| Key{id: v.id, default: v.default}).collect();
+		let material_keys = r!(Vec<KeyRaw>, material_key_count).into_iter().map(|v| Key{id: v.id, default: v.default}).collect();
+		let sub_view_keys = vec![
+			Key{id: 1, default: r!(u32)},
+			Key{id: 2, default: r!(u32)},
+		];
+		
+		let mut nodes = Vec::with_capacity(node_count as usize);
+		for _ in 0..node_count {
+			let selector = r!(u32);
+			let pass_count = r!(u32);
+			nodes.push(Node {
+				selector,
+				pass_indices: r!([u8; 16]),
+				system_keys: r!(Vec<u32>, system_key_count),
+				scene_keys: r!(Vec<u32>, scene_key_count),
+				material_keys: r!(Vec<u32>, material_key_count),
+				sub_view_keys: r!(Vec<u32>, 2),
+				passes: r!(Vec<PassRaw>, pass_count).into_iter().map(|v| Pass{id: v.id, vertex_shader: v.vertex_shader, pixel_shader: v.pixel_shader}).collect(),
+			});
+		}
+		
+		let node_aliases = r!(Vec<(u32, u32)>, node_alias_count);
+		
+		Ok(Self {
+			version,
+			directx,
+			vertex_shaders,
+			pixel_shaders,
+			material_params_size,
+			material_params,
+			constants,
+			samplers,
+			textures,
+			uavs,
+			system_keys,
+			scene_keys,
+			material_keys,
+			sub_view_keys,
+			nodes,
+			node_aliases,
+			_unknown: unknown,
+		})
+	}
+}
+
+impl crate::File for Shpk {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Shpk::read_le(&mut reader)?)
+	}
+}
+
+impl super::Extension for Shpk {
+	const EXT: &[&str] = EXT;
+}
+
+impl crate::format::external::Bytes for Shpk {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error>
+	where T: Read + Seek {
+		Ok(Shpk::read_le(reader)?)
+	}
+	
+	fn write<T>(&self, _writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		// TODO: writing requires rebuilding the blob and string sections, nothing needs it yet
+		Err(crate::Error::InvalidFormatTo(EXT[0].to_string()))
+	}
+}
+
+// ----------
+
+#[binrw]
+#[derive(Debug, Clone)]
+struct ResourceRaw {
+	id: u32,
+	string_offset: u32,
+	string_size: u16,
+	is_texture: u16,
+	slot: u16,
+	size: u16,
+}
+
+#[binrw]
+#[derive(Debug, Clone)]
+struct MaterialParamRaw {
+	id: u32,
+	offset: u16,
+	size: u16,
+}
+
+#[binrw]
+#[derive(Debug, Clone)]
+struct KeyRaw {
+	id: u32,
+	default: u32,
+}
+
+#[binrw]
+#[derive(Debug, Clone)]
+struct PassRaw {
+	id: u32,
+	vertex_shader: u32,
+	pixel_shader: u32,
+}
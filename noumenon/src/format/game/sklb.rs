@@ -1,5 +1,6 @@
-use std::{fmt::Debug, io::{Read, Seek, Write}};
+use std::{collections::HashMap, fmt::Debug, io::{Read, Seek, Write}, sync::Arc};
 use binrw::{BinRead, BinWrite};
+use crate::havok::{HavokObject, HavokValue};
 
 pub const EXT: &'static [&'static str] = &["sklb"];
 
@@ -9,6 +10,60 @@ pub type Error = binrw::Error;
 #[derive(Debug, Clone)]
 pub struct Sklb {
 	pub bones: Vec<Bone>,
+	
+	// everything up to the havok data and the havok data itself, the skeleton in it gets updated when writing
+	header: Vec<u8>,
+	havok: Vec<u8>,
+}
+
+impl Sklb {
+	pub fn gltf_bones(&self) -> Vec<crate::format::external::gltf::Bone> {
+		self.bones
+			.iter()
+			.map(|bone| crate::format::external::gltf::Bone {
+				name: bone.name.clone(),
+				parent: if bone.parent >= 0 {Some(self.bones[bone.parent as usize].name.clone())} else {None},
+				translation: bone.translation,
+				rotation: bone.rotation,
+				scale: bone.scale,
+			}).collect()
+	}
+	
+	/// Replaces the bones with the ones of a glTF skin, bones that already exist keep their index
+	pub fn apply_gltf_bones(&mut self, bones: &[crate::format::external::gltf::Bone]) {
+		let mut names = self.bones.iter().map(|v| v.name.clone()).filter(|v| bones.iter().any(|b| &b.name == v)).collect::<Vec<_>>();
+		
+		// parents have to come before their children
+		let mut remaining = bones.iter().filter(|v| !names.contains(&v.name)).collect::<Vec<_>>();
+		while remaining.len() > 0 {
+			let count = remaining.len();
+			remaining.retain(|bone| {
+				let ready = bone.parent.as_ref().map_or(true, |v| names.contains(v) || !bones.iter().any(|b| &b.name == v));
+				if ready {
+					names.push(bone.name.clone());
+				}
+				!ready
+			});
+			
+			// parents that loop back onto themselves, nothing sensible we can do with those
+			if remaining.len() == count {
+				names.extend(remaining.drain(..).map(|v| v.name.clone()));
+			}
+		}
+		
+		self.bones = names
+			.iter()
+			.map(|name| {
+				let bone = bones.iter().find(|v| &v.name == name).unwrap();
+				Bone {
+					name: name.clone(),
+					parent: bone.parent.as_ref().and_then(|v| names.iter().position(|n| n == v)).map_or(-1, |v| v as i32),
+					translation: bone.translation,
+					rotation: bone.rotation,
+					scale: bone.scale,
+				}
+			}).collect();
+	}
 }
 
 impl BinRead for Sklb {
@@ -22,7 +77,8 @@ impl BinRead for Sklb {
 		let new_header = version >= 0x31333030; // 1300
 		r!(move if new_header {4} else {2});
 		let offset = if new_header {r!(u32)} else {r!(u16) as u32};
-		r!(seek offset);
+		r!(seek 0);
+		let header = r!(Vec<u8>, offset);
 		let havok_data = r!(eof);
 		
 		let root = crate::havok::HavokBinaryTagFileReader::read(&havok_data);
@@ -42,6 +98,8 @@ impl BinRead for Sklb {
 		
 		Ok(Self {
 			bones,
+			header,
+			havok: havok_data,
 		})
 	}
 }
@@ -50,13 +108,67 @@ impl BinWrite for Sklb {
 	type Args<'a> = ();
 	
 	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
-		todo!();
+		if self.havok.len() == 0 {
+			return Err(binrw::Error::AssertFail{pos: writer.stream_position()?, message: "Skeletons can only be written if they are based on an existing one".to_string()});
+		}
+		
+		let root = crate::havok::HavokBinaryTagFileReader::read(&self.havok);
+		let skeleton = root.find_object_by_type("hkaAnimationContainer").borrow().get("skeletons").as_array()[0].as_object();
+		
+		{
+			let mut skeleton = skeleton.borrow_mut();
+			
+			// reuse the existing bones so that we keep whatever else they store
+			let old_bones = skeleton.get("bones")
+				.as_array()
+				.iter()
+				.zip(skeleton.get("referencePose").as_array())
+				.map(|(bone, pose)| {
+					let bone = bone.as_object();
+					let name = bone.borrow().get("name").as_string().to_string();
+					(name, (bone, pose.as_vec().clone()))
+				}).collect::<HashMap<_, _>>();
+			let bone_type = old_bones.values().next().map(|v| v.0.borrow().object_type.clone()).or_else(|| root.find_type("hkaBone")).unwrap();
+			
+			let mut bones = Vec::new();
+			let mut parents = Vec::new();
+			let mut poses = Vec::new();
+			for bone in &self.bones {
+				let old = old_bones.get(&bone.name);
+				bones.push(HavokValue::Object(old.map_or_else(|| {
+					let mut object = HavokObject::new(bone_type.clone(), HashMap::new());
+					object.set_by_name("name", HavokValue::String(Arc::from(bone.name.as_str())));
+					Arc::new(std::cell::RefCell::new(object))
+				}, |v| v.0.clone())));
+				
+				parents.push(HavokValue::Integer(bone.parent));
+				
+				let (translation_w, scale_w) = old.map_or((0.0, 1.0), |v| (v.1[3], v.1[11]));
+				let mut pose = Vec::with_capacity(12);
+				pose.extend_from_slice(&bone.translation.to_array());
+				pose.push(translation_w);
+				pose.extend_from_slice(&bone.rotation.to_array());
+				pose.extend_from_slice(&bone.scale.to_array());
+				pose.push(scale_w);
+				poses.push(HavokValue::Vec(pose));
+			}
+			
+			skeleton.set_by_name("bones", HavokValue::Array(bones));
+			skeleton.set_by_name("parentIndices", HavokValue::Array(parents));
+			skeleton.set_by_name("referencePose", HavokValue::Array(poses));
+		}
+		
+		self.header.write_options(writer, endian, ())?;
+		crate::havok::HavokBinaryTagFileWriter::write(&root)?.write_options(writer, endian, ())?;
+		
+		Ok(())
 	}
 }
 
-impl ironworks::file::File for Sklb {
-	fn read(mut data: impl ironworks::FileStream) -> Result<Self, ironworks::Error> {
-		Sklb::read_le(&mut data).map_err(|e| ironworks::Error::Resource(e.into()))
+impl crate::File for Sklb {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Sklb::read_le(&mut reader)?)
 	}
 }
 
@@ -79,17 +79,57 @@ impl Format {
 		Ok(surface.decode_rgba8()?.data)
 	}
 	
-	pub fn convert_to(&self, width: u32, height: u32, depth: u32, mipmaps: u32, data: &[u8]) -> Vec<u8> {
+	pub fn convert_from_f32(&self, width: u32, height: u32, depth: u32, data: &[u8]) -> Result<Vec<f32>, image_dds::error::SurfaceError> {
+		let surface = image_dds::Surface {
+			width,
+			height,
+			depth,
+			layers: 1,
+			mipmaps: 1,
+			image_format: (*self).into(),
+			data,
+		};
+		
+		Ok(surface.decode_rgbaf32()?.data)
+	}
+	
+	pub fn convert_to_f32(&self, width: u32, height: u32, depth: u32, data: &[f32], quality: Quality) -> Result<Vec<u8>, image_dds::error::SurfaceError> {
+		let surface = image_dds::SurfaceRgba32Float {
+			width,
+			height,
+			depth,
+			layers: 1,
+			mipmaps: 1,
+			data,
+		};
+		
+		Ok(surface.encode((*self).into(), quality.into(), image_dds::Mipmaps::Disabled)?.data)
+	}
+	
+	pub fn convert_to(&self, width: u32, height: u32, depth: u32, data: &[u8], quality: Quality) -> Result<Vec<u8>, image_dds::error::SurfaceError> {
 		let surface = image_dds::SurfaceRgba8 {
 			width,
 			height,
 			depth,
 			layers: 1,
-			mipmaps,
+			mipmaps: 1,
 			data,
 		};
 		
-		surface.encode((*self).into(), image_dds::Quality::Normal, image_dds::Mipmaps::GeneratedExact(mipmaps)).unwrap().data
+		Ok(surface.encode((*self).into(), quality.into(), image_dds::Mipmaps::Disabled)?.data)
+	}
+	
+	/// The dds format, the srgb variant is used if there is one
+	pub fn image_format(&self, srgb: bool) -> ImageFormat {
+		match (self, srgb) {
+			(Format::A8R8G8B8, true) => ImageFormat::Bgra8UnormSrgb,
+			(Format::X8R8G8B8, true) => ImageFormat::Bgra8UnormSrgb,
+			(Format::Bc1, true)      => ImageFormat::BC1RgbaUnormSrgb,
+			(Format::Bc2, true)      => ImageFormat::BC2RgbaUnormSrgb,
+			(Format::Bc3, true)      => ImageFormat::BC3RgbaUnormSrgb,
+			(Format::Bc7, true)      => ImageFormat::BC7RgbaUnormSrgb,
+			_ => (*self).into(),
+		}
 	}
 	
 	pub fn bits_per_pixel(&self) -> usize {
@@ -112,6 +152,20 @@ impl Format {
 		}
 	}
 	
+	/// Formats with more precision or range than rgba8 can hold, these keep a float copy of their pixels
+	pub fn is_high_precision(&self) -> bool {
+		match self {
+			Format::R32 |
+			Format::R16G16 |
+			Format::R32G32 |
+			Format::A16B16G16R16 |
+			Format::A32B32G32R32 |
+			Format::Bc5 => true,
+			
+			_ => false,
+		}
+	}
+	
 	pub fn is_block(&self) -> bool {
 		match self {
 			Format::Bc1 |
@@ -123,6 +177,80 @@ impl Format {
 			_ => false,
 		}
 	}
+	
+	/// Size of a surface in the format, block formats are rounded up to full blocks
+	pub fn surface_size(&self, width: u32, height: u32, depth: u32) -> usize {
+		let (width, height) = if self.is_block() {
+			((width as usize).div_ceil(4) * 4, (height as usize).div_ceil(4) * 4)
+		} else {
+			(width as usize, height as usize)
+		};
+		
+		width * height * depth as usize * self.bits_per_pixel() / 8
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quality {
+	Fast,
+	#[default]
+	Normal,
+	Slow,
+}
+
+impl From<Quality> for image_dds::Quality {
+	fn from(value: Quality) -> Self {
+		match value {
+			Quality::Fast   => image_dds::Quality::Fast,
+			Quality::Normal => image_dds::Quality::Normal,
+			Quality::Slow   => image_dds::Quality::Slow,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mips {
+	/// Keep the mips the texture already has
+	#[default]
+	Keep,
+	/// Generate mips all the way down to 1x1
+	Full,
+	/// Generate this many mips, 1 being only the base image
+	Count(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MipFilter {
+	Nearest,
+	#[default]
+	Triangle,
+	CatmullRom,
+	Gaussian,
+	Lanczos3,
+}
+
+impl From<MipFilter> for image::imageops::FilterType {
+	fn from(value: MipFilter) -> Self {
+		match value {
+			MipFilter::Nearest    => image::imageops::FilterType::Nearest,
+			MipFilter::Triangle   => image::imageops::FilterType::Triangle,
+			MipFilter::CatmullRom => image::imageops::FilterType::CatmullRom,
+			MipFilter::Gaussian   => image::imageops::FilterType::Gaussian,
+			MipFilter::Lanczos3   => image::imageops::FilterType::Lanczos3,
+		}
+	}
+}
+
+/// How a texture gets encoded when written, the default keeps the format and mips of the texture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EncodeOptions {
+	/// Format to encode to, None keeps the format of the texture
+	pub format: Option<Format>,
+	pub quality: Quality,
+	pub mips: Mips,
+	pub mip_filter: MipFilter,
+	/// Color channels are srgb, mips are filtered in linear space and dds files use the srgb formats
+	pub srgb: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -131,16 +259,59 @@ pub struct Tex {
 	pub format: Format,
 	pub width: u32,
 	pub height: u32,
+	/// Slices of a volume texture, 1 for anything else
 	pub depth: u32,
+	/// Array layers, cubemaps have 6 per array layer
+	pub layers: u32,
 	pub mip_levels: u32,
 	pub lods: bool,
 	
+	/// Rgba8 pixels, ordered by mip, then layer, then depth slice
 	pub pixels: Vec<u8>,
+	/// Rgba32 float pixels of high precision formats in the same order as `pixels`.
+	/// Used when writing as long as `pixels` wasn't edited, surfaces where it was fall back to `pixels`
+	pub native: Option<Vec<f32>>,
 }
 
 impl Tex {
-	pub fn slice<'a>(&'a self, depth: u32, mip: u32) -> Slice<'a> {
-		let (width, height, offset, size) = self.slice_range(depth, mip);
+	pub const TYPE_1D: u32 = 0x00400000;
+	pub const TYPE_2D: u32 = 0x00800000;
+	pub const TYPE_3D: u32 = 0x01000000;
+	pub const TYPE_CUBE: u32 = 0x02000000;
+	pub const TYPE_2D_ARRAY: u32 = 0x10000000;
+	pub const TYPE_MASK: u32 = Self::TYPE_1D | Self::TYPE_2D | Self::TYPE_3D | Self::TYPE_CUBE | Self::TYPE_2D_ARRAY;
+	
+	pub const MAX_MIP_LEVELS: u32 = 13;
+	
+	pub fn is_cube(&self) -> bool {
+		self.flags & Self::TYPE_CUBE != 0
+	}
+	
+	pub fn is_volume(&self) -> bool {
+		self.flags & Self::TYPE_3D != 0 || self.depth > 1
+	}
+	
+	/// Layer index of a cubemap face, faces are ordered +x, -x, +y, -y, +z, -z
+	pub fn cube_layer(array_layer: u32, face: u32) -> u32 {
+		array_layer * 6 + face
+	}
+	
+	/// Width, height and depth of a mip level
+	pub fn mip_size(&self, mip: u32) -> (u32, u32, u32) {
+		(
+			(self.width >> mip).max(1),
+			(self.height >> mip).max(1),
+			(self.depth >> mip).max(1),
+		)
+	}
+	
+	/// Amount of mips needed to go all the way down to 1x1
+	pub fn full_mip_levels(&self) -> u32 {
+		(32 - self.width.max(self.height).max(self.depth).max(1).leading_zeros()).min(Self::MAX_MIP_LEVELS)
+	}
+	
+	pub fn slice<'a>(&'a self, layer: u32, depth: u32, mip: u32) -> Slice<'a> {
+		let (width, height, offset, size) = self.slice_range(layer, depth, mip);
 		
 		Slice {
 			width,
@@ -149,8 +320,8 @@ impl Tex {
 		}
 	}
 	
-	pub fn slice_mut<'a>(&'a mut self, depth: u32, mip: u32) -> SliceMut<'a> {
-		let (width, height, offset, size) = self.slice_range(depth, mip);
+	pub fn slice_mut<'a>(&'a mut self, layer: u32, depth: u32, mip: u32) -> SliceMut<'a> {
+		let (width, height, offset, size) = self.slice_range(layer, depth, mip);
 		
 		SliceMut {
 			width,
@@ -159,31 +330,147 @@ impl Tex {
 		}
 	}
 	
-	fn slice_range(&self, depth: u32, mip: u32) -> (u32, u32, usize, usize) {
-		let mip = mip as u32;
-		let width = self.width / 2u32.pow(mip);
-		let height = self.height / 2u32.pow(mip);
-		(
-			width as u32, height as u32,
-			(self.width as u64 * self.height as u64 * self.depth as u64 * (8u64.pow(mip) - 1) / if mip == 0 {1} else {7 * 8u64.pow(mip - 1)} + width as u64 * height as u64 * depth as u64 * 4) as usize,
-			(width * height * 4) as usize,
-		)
+	fn slice_range(&self, layer: u32, depth: u32, mip: u32) -> (u32, u32, usize, usize) {
+		assert!(mip < self.mip_levels && layer < self.layers, "Slice is out of bounds");
+		let (width, height, mip_depth) = self.mip_size(mip);
+		assert!(depth < mip_depth, "Slice is out of bounds");
+		let size = width as usize * height as usize * 4;
+		
+		(width, height, self.surface_offset(layer, mip) + depth as usize * size, size)
+	}
+	
+	// rgba8 size of a single layer including all of its depth slices
+	fn surface_size(&self, mip: u32) -> usize {
+		let (width, height, depth) = self.mip_size(mip);
+		width as usize * height as usize * depth as usize * 4
+	}
+	
+	fn surface_offset(&self, layer: u32, mip: u32) -> usize {
+		(0..mip).map(|mip| self.surface_size(mip) * self.layers as usize).sum::<usize>() + self.surface_size(mip) * layer as usize
+	}
+	
+	fn surface(&self, layer: u32, mip: u32) -> &[u8] {
+		let offset = self.surface_offset(layer, mip);
+		&self.pixels[offset..offset + self.surface_size(mip)]
+	}
+	
+	/// Float pixels of a slice, same layout as `slice`
+	pub fn native_slice(&self, layer: u32, depth: u32, mip: u32) -> Option<&[f32]> {
+		let (_, _, offset, size) = self.slice_range(layer, depth, mip);
+		self.native.as_ref().map(|v| &v[offset..offset + size])
+	}
+	
+	// the float pixels of a surface if the rgba8 ones haven't been edited since
+	fn native_surface(&self, layer: u32, mip: u32) -> Option<&[f32]> {
+		let offset = self.surface_offset(layer, mip);
+		let native = &self.native.as_ref()?[offset..offset + self.surface_size(mip)];
+		let pixels = self.surface(layer, mip);
+		native.iter().zip(pixels).all(|(a, b)| to_u8(*a) == *b).then_some(native)
+	}
+	
+	fn native_in_sync(&self) -> bool {
+		(0..self.mip_levels).all(|mip| (0..self.layers).all(|layer| self.native_surface(layer, mip).is_some()))
+	}
+	
+	/// Replaces all mips below the first with ones generated from it, capped to the full chain
+	pub fn generate_mips(&mut self, mip_levels: u32, filter: MipFilter, srgb: bool) {
+		let mip_levels = mip_levels.clamp(1, self.full_mip_levels());
+		let base_size = self.surface_size(0) * self.layers as usize;
+		
+		// float data is linear, no srgb handling for it
+		if (0..self.layers).all(|layer| self.native_surface(layer, 0).is_some()) {
+			let native = self.build_mips(self.native.as_ref().unwrap()[..base_size].to_vec(), mip_levels, |data, w, h, nw, nh| resize_f32(data, w, h, nw, nh, filter));
+			self.pixels = native.iter().map(|v| to_u8(*v)).collect();
+			self.native = Some(native);
+		} else {
+			self.pixels = self.build_mips(self.pixels[..base_size].to_vec(), mip_levels, |data, w, h, nw, nh| resize(data, w, h, nw, nh, filter, srgb));
+			self.native = None;
+		}
+		
+		self.mip_levels = mip_levels;
+	}
+	
+	fn build_mips<T>(&self, mut pixels: Vec<T>, mip_levels: u32, resize: impl Fn(&[T], u32, u32, u32, u32) -> Vec<T>) -> Vec<T> {
+		for mip in 1..mip_levels {
+			let (width, height, depth) = self.mip_size(mip);
+			let (prev_width, prev_height, prev_depth) = self.mip_size(mip - 1);
+			let prev_offset = pixels.len() - prev_width as usize * prev_height as usize * prev_depth as usize * 4 * self.layers as usize;
+			for layer in 0..self.layers as usize {
+				for z in 0..depth as usize {
+					// volumes just drop every other slice
+					let z = z * prev_depth as usize / depth as usize;
+					let size = prev_width as usize * prev_height as usize * 4;
+					let offset = prev_offset + (layer * prev_depth as usize + z) * size;
+					let new = resize(&pixels[offset..offset + size], prev_width, prev_height, width, height);
+					pixels.extend(new);
+				}
+			}
+		}
+		
+		pixels
+	}
+	
+	/// The texture as it would be written with the options, only cloned if something changes
+	pub fn with_options(&self, options: &EncodeOptions) -> std::borrow::Cow<'_, Tex> {
+		let format = options.format.unwrap_or(self.format);
+		let mip_levels = match options.mips {
+			Mips::Keep => self.mip_levels,
+			Mips::Full => self.full_mip_levels(),
+			Mips::Count(v) => v.clamp(1, self.full_mip_levels()),
+		};
+		
+		if format == self.format && mip_levels == self.mip_levels {
+			return std::borrow::Cow::Borrowed(self);
+		}
+		
+		let mut tex = self.clone();
+		tex.format = format;
+		if mip_levels != self.mip_levels {
+			tex.generate_mips(mip_levels, options.mip_filter, options.srgb);
+		}
+		
+		std::borrow::Cow::Owned(tex)
 	}
 	
 	pub fn new(width: u32, height: u32, pixels: impl Into<Vec<u8>>) -> Self {
 		let pixels = pixels.into();
 		assert!(width * height * 4 <= pixels.len() as u32, "Pixel buffer was too small");
 		
-		Tex {
-			flags: 0x00800000,
+		let mut tex = Tex {
+			flags: Self::TYPE_2D,
 			format: Format::A8R8G8B8,
 			width: width,
 			height: height,
 			depth: 1,
+			layers: 1,
 			mip_levels: 1,
 			lods: true,
 			pixels: pixels,
-		}
+			native: None,
+		};
+		tex.generate_mips(Self::MAX_MIP_LEVELS, MipFilter::default(), false);
+		tex
+	}
+	
+	/// Texture from rgba32 float pixels, the format is picked to keep the precision
+	pub fn new_native(width: u32, height: u32, native: impl Into<Vec<f32>>) -> Self {
+		let native = native.into();
+		assert!(width * height * 4 <= native.len() as u32, "Pixel buffer was too small");
+		
+		let mut tex = Tex {
+			flags: Self::TYPE_2D,
+			format: Format::A16B16G16R16,
+			width: width,
+			height: height,
+			depth: 1,
+			layers: 1,
+			mip_levels: 1,
+			lods: true,
+			pixels: native.iter().map(|v| to_u8(*v)).collect(),
+			native: Some(native),
+		};
+		tex.generate_mips(Self::MAX_MIP_LEVELS, MipFilter::default(), false);
+		tex
 	}
 	
 	pub fn resized(&self, width: u32, height: u32) -> Self {
@@ -191,6 +478,91 @@ impl Tex {
 		let new = image::imageops::resize(&img, width, height, image::imageops::FilterType::Lanczos3);
 		Self::new(width, height, new.into_raw())
 	}
+	
+	pub fn write_tex<T>(&self, writer: &mut T, options: &EncodeOptions) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		Ok(self.write_le_args(writer, *options)?)
+	}
+	
+	pub fn write_dds<T>(&self, writer: &mut T, options: &EncodeOptions) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		let tex = self.with_options(options);
+		
+		let format = tex.format.image_format(options.srgb);
+		
+		// dds stores all mips of a layer together
+		let dds = if tex.native_in_sync() {
+			let mut data = Vec::with_capacity(tex.pixels.len());
+			for layer in 0..tex.layers {
+				for mip in 0..tex.mip_levels {
+					data.extend_from_slice(tex.native_surface(layer, mip).unwrap());
+				}
+			}
+			
+			image_dds::SurfaceRgba32Float {
+				width: tex.width,
+				height: tex.height,
+				depth: tex.depth,
+				layers: tex.layers,
+				mipmaps: tex.mip_levels,
+				data: &data,
+			}.encode_dds(format, options.quality.into(), image_dds::Mipmaps::FromSurface)?
+		} else {
+			let mut data = Vec::with_capacity(tex.pixels.len());
+			for layer in 0..tex.layers {
+				for mip in 0..tex.mip_levels {
+					data.extend_from_slice(tex.surface(layer, mip));
+				}
+			}
+			
+			image_dds::SurfaceRgba8 {
+				width: tex.width,
+				height: tex.height,
+				depth: tex.depth,
+				layers: tex.layers,
+				mipmaps: tex.mip_levels,
+				data: &data,
+			}.encode_dds(format, options.quality.into(), image_dds::Mipmaps::FromSurface)?
+		};
+		
+		dds.write(writer)?;
+		
+		Ok(())
+	}
+}
+
+fn to_u8(v: f32) -> u8 {
+	(v * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+fn resize_f32(pixels: &[f32], width: u32, height: u32, new_width: u32, new_height: u32, filter: MipFilter) -> Vec<f32> {
+	let img: image::ImageBuffer<image::Rgba<f32>, &[f32]> = image::ImageBuffer::from_raw(width, height, pixels).unwrap();
+	image::imageops::resize(&img, new_width, new_height, filter.into()).into_raw()
+}
+
+// filtering in linear space keeps srgb mips from getting darker
+fn resize(pixels: &[u8], width: u32, height: u32, new_width: u32, new_height: u32, filter: MipFilter, srgb: bool) -> Vec<u8> {
+	if !srgb {
+		let img: image::ImageBuffer<image::Rgba<u8>, &[u8]> = image::ImageBuffer::from_raw(width, height, pixels).unwrap();
+		return image::imageops::resize(&img, new_width, new_height, filter.into()).into_raw();
+	}
+	
+	fn to_linear(v: u8) -> f32 {
+		let v = v as f32 / 255.0;
+		if v <= 0.04045 {v / 12.92} else {((v + 0.055) / 1.055).powf(2.4)}
+	}
+	
+	fn to_srgb(v: f32) -> u8 {
+		to_u8(if v <= 0.0031308 {v * 12.92} else {1.055 * v.powf(1.0 / 2.4) - 0.055})
+	}
+	
+	let linear = pixels.chunks_exact(4).flat_map(|v| [to_linear(v[0]), to_linear(v[1]), to_linear(v[2]), v[3] as f32 / 255.0]).collect::<Vec<_>>();
+	let img: image::ImageBuffer<image::Rgba<f32>, Vec<f32>> = image::ImageBuffer::from_raw(width, height, linear).unwrap();
+	image::imageops::resize(&img, new_width, new_height, filter.into())
+		.into_raw()
+		.chunks_exact(4)
+		.flat_map(|v| [to_srgb(v[0]), to_srgb(v[1]), to_srgb(v[2]), to_u8(v[3])])
+		.collect()
 }
 
 impl BinRead for Tex {
@@ -202,82 +574,113 @@ impl BinRead for Tex {
 		let width = u16::read_options(reader, endian, ())? as u32;
 		let height = u16::read_options(reader, endian, ())? as u32;
 		let depth = (u16::read_options(reader, endian, ())? as u32).max(1);
-		let mut mip_levels = (u16::read_options(reader, endian, ())? as u32).max(1);
+		// top bit of the mip count is a flag we don't care about
+		let mip_levels = ((u8::read_options(reader, endian, ())? & 0x7F) as u32).clamp(1, Tex::MAX_MIP_LEVELS);
+		let layers = (u8::read_options(reader, endian, ())? as u32).max(1);
 		let lod_offsets = <[u32; 3]>::read_options(reader, endian, ())?;
 		let mip_offsets = <[u32; 13]>::read_options(reader, endian, ())?;
+		let data_start = reader.stream_position()?;
 		let mut data = Vec::new();
 		reader.read_to_end(&mut data)?;
 		
-		// mips seem to be broken?
-		let mut pixels = Vec::new();
-		for mip_level in 0..mip_levels as usize {
-			let offset = mip_offsets[mip_level] as usize - 80;
-			let factor = 2u32.pow(mip_level as u32);
-			let width = width / factor;
-			let height = height / factor;
-			let depth = if depth == 1 {depth} else {depth / factor};
-			let seg_size = width as usize * height as usize * depth as usize * format.bits_per_pixel() / 8;
-			if if format.is_block() {width < 4 || height < 4} else {width == 0 || height == 0} || depth == 0 || offset + seg_size > data.len() {
-				mip_levels = mip_level as u32;
-				break;
-			};
-			
-			match (mip_level, format.convert_from(width, height, depth, &data[offset..offset + seg_size])) {
-				(_, Ok(data)) => pixels.extend(data),
-				(0, Err(err)) => return Err(binrw::Error::Custom{pos: reader.stream_position()?, err: Box::new(err)}),
-				_ => {
-					mip_levels = mip_level as u32;
-					break;
-				}
-			}
-		}
-		
-		Ok(Self {
+		let mut tex = Self {
 			flags,
 			format,
 			width,
 			height,
 			depth,
+			layers,
 			mip_levels,
 			lods: lod_offsets[2] != 0,
-			pixels,
-		})
+			pixels: Vec::new(),
+			native: format.is_high_precision().then(Vec::new),
+		};
+		
+		for mip in 0..mip_levels {
+			let (width, height, depth) = tex.mip_size(mip);
+			let size = format.surface_size(width, height, depth);
+			let offset = (mip_offsets[mip as usize] as u64).checked_sub(data_start);
+			
+			for layer in 0..layers as usize {
+				let raw = offset.and_then(|offset| {
+					let start = offset as usize + layer * size;
+					data.get(start..start + size)
+				});
+				
+				let Some(raw) = raw else {
+					// some files claim more mips than they contain, keep what we have as long as the first one is there
+					if mip == 0 {
+						return Err(binrw::Error::AssertFail{pos: data_start, message: "Texture data is out of bounds".to_string()});
+					}
+					
+					let len = tex.surface_offset(0, mip);
+					tex.pixels.truncate(len);
+					if let Some(native) = &mut tex.native {
+						native.truncate(len);
+					}
+					tex.mip_levels = mip;
+					return Ok(tex);
+				};
+				
+				let pos = data_start + offset.unwrap_or(0);
+				if let Some(native) = &mut tex.native {
+					let pixels = format.convert_from_f32(width, height, depth, raw).map_err(|err| binrw::Error::Custom{pos, err: Box::new(err)})?;
+					tex.pixels.extend(pixels.iter().map(|v| to_u8(*v)));
+					native.extend(pixels);
+				} else {
+					let pixels = format.convert_from(width, height, depth, raw).map_err(|err| binrw::Error::Custom{pos, err: Box::new(err)})?;
+					tex.pixels.extend(pixels);
+				}
+			}
+		}
+		
+		Ok(tex)
 	}
 }
 
 impl BinWrite for Tex {
-	type Args<'a> = ();
+	type Args<'a> = EncodeOptions;
 	
-	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, _args: Self::Args<'_>,) -> binrw::BinResult<()> {
+	fn write_options<W: Write + Seek>(&self, writer: &mut W, endian: binrw::Endian, args: Self::Args<'_>,) -> binrw::BinResult<()> {
+		let tex = self.with_options(&args);
+		let mip_levels = tex.mip_levels.clamp(1, Self::MAX_MIP_LEVELS);
 		let mut mip_offsets = [0u32; 13];
 		let mut offset = 80;
-		let mut mip_count = 0;
-		for i in 0..13 {
-			let w = self.width / 2u32.pow(i);
-			let h = self.height / 2u32.pow(i);
-			if if self.format.is_block() {w < 4 || h < 4} else {w == 0 || h == 0} {break}
-			mip_offsets[i as usize] = offset;
-			offset += w * h * self.format.bits_per_pixel() as u32 / 8;
-			mip_count += 1;
+		for mip in 0..mip_levels {
+			let (w, h, d) = tex.mip_size(mip);
+			mip_offsets[mip as usize] = offset;
+			offset += (tex.format.surface_size(w, h, d) * tex.layers as usize) as u32;
 		}
 		
-		self.flags.write_options(writer, endian, ())?;
-		self.format.write_options(writer, endian, ())?;
-		(self.width as u16).write_options(writer, endian, ())?;
-		(self.height as u16).write_options(writer, endian, ())?;
-		(self.depth as u16).write_options(writer, endian, ())?;
-		(mip_count as u16).write_options(writer, endian, ())?;
-		if self.lods {[0u32, 1, 2]} else {[0u32; 3]}.write_options(writer, endian, ())?;
+		tex.flags.write_options(writer, endian, ())?;
+		tex.format.write_options(writer, endian, ())?;
+		(tex.width as u16).write_options(writer, endian, ())?;
+		(tex.height as u16).write_options(writer, endian, ())?;
+		(tex.depth as u16).write_options(writer, endian, ())?;
+		(mip_levels as u8).write_options(writer, endian, ())?;
+		(tex.layers as u8).write_options(writer, endian, ())?;
+		if tex.lods {[0u32, 1, 2]} else {[0u32; 3]}.write_options(writer, endian, ())?;
 		mip_offsets.write_options(writer, endian, ())?;
-		writer.write_all(&self.format.convert_to(self.width, self.height, self.depth, mip_count, &self.pixels[..(self.width * self.height * self.depth * 4) as usize]))?;
+		for mip in 0..mip_levels {
+			let (w, h, d) = tex.mip_size(mip);
+			for layer in 0..tex.layers {
+				let pos = writer.stream_position()?;
+				let data = match tex.native_surface(layer, mip) {
+					Some(native) => tex.format.convert_to_f32(w, h, d, native, args.quality),
+					None => tex.format.convert_to(w, h, d, tex.surface(layer, mip), args.quality),
+				}.map_err(|err| binrw::Error::Custom{pos, err: Box::new(err)})?;
+				writer.write_all(&data)?;
+			}
+		}
 		
 		Ok(())
 	}
 }
 
-impl ironworks::file::File for Tex {
-	fn read(mut data: impl ironworks::FileStream) -> Result<Self, ironworks::Error> {
-		Tex::read_le(&mut data).map_err(|e| ironworks::Error::Resource(e.into()))
+impl crate::File for Tex {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Tex::read_le(&mut reader)?)
 	}
 }
 
@@ -304,50 +707,77 @@ impl crate::format::external::Dds for Tex {
 	T: Read + Seek {
 		let dds = image_dds::ddsfile::Dds::read(reader)?;
 		let data = image_dds::Surface::from_dds(&dds)?;
+		let cube = dds.header.caps2.contains(image_dds::ddsfile::Caps2::CUBEMAP);
 		
-		Ok(Self {
-			flags: 0x00800000, // TODO: care about other stuff like 3d textures
+		let mut tex = Self {
+			flags: if data.depth > 1 {
+				Tex::TYPE_3D
+			} else if cube {
+				Tex::TYPE_CUBE
+			} else if data.layers > 1 {
+				Tex::TYPE_2D_ARRAY
+			} else {
+				Tex::TYPE_2D
+			},
 			format: match data.image_format {
 				 ImageFormat::R8Unorm      => Format::L8,
 				 ImageFormat::Bgra4Unorm   => Format::A4R4G4B4,
 				 ImageFormat::Bgr5A1Unorm  => Format::A1R5G5B5,
-				 ImageFormat::Bgra8Unorm   => Format::A8R8G8B8,
+				 ImageFormat::Bgra8Unorm | ImageFormat::Bgra8UnormSrgb => Format::A8R8G8B8,
 				 ImageFormat::R32Float     => Format::R32,
 				 ImageFormat::Rg16Float    => Format::R16G16,
 				 ImageFormat::Rg32Float    => Format::R32G32,
 				 ImageFormat::Rgba16Float  => Format::A16B16G16R16,
 				 ImageFormat::Rgba32Float  => Format::A32B32G32R32,
-				 ImageFormat::BC1RgbaUnorm => Format::Bc1,
-				 ImageFormat::BC2RgbaUnorm => Format::Bc2,
-				 ImageFormat::BC3RgbaUnorm => Format::Bc3,
+				 ImageFormat::BC1RgbaUnorm | ImageFormat::BC1RgbaUnormSrgb => Format::Bc1,
+				 ImageFormat::BC2RgbaUnorm | ImageFormat::BC2RgbaUnormSrgb => Format::Bc2,
+				 ImageFormat::BC3RgbaUnorm | ImageFormat::BC3RgbaUnormSrgb => Format::Bc3,
 				 ImageFormat::BC5RgUnorm   => Format::Bc5,
-				 ImageFormat::BC7RgbaUnorm => Format::Bc7,
+				 ImageFormat::BC7RgbaUnorm | ImageFormat::BC7RgbaUnormSrgb => Format::Bc7,
 				 _                         => Format::A8R8G8B8,
 			},
-			width: dds.header.width,
-			height: dds.header.height,
-			depth: dds.header.depth.unwrap_or(1),
-			mip_levels: dds.header.mip_map_count.unwrap_or(1),
+			width: data.width,
+			height: data.height,
+			depth: data.depth.max(1),
+			layers: data.layers.max(1),
+			mip_levels: data.mipmaps.clamp(1, Tex::MAX_MIP_LEVELS),
 			lods: true,
-			pixels: data.decode_rgba8()?.data,
-		})
+			pixels: Vec::new(),
+			native: None,
+		};
+		
+		// dds stores all mips of a layer together, we store all layers of a mip together
+		let missing = |layer, depth, mip| crate::Error::InvalidData(format!("Dds is missing layer {layer} depth {depth} mip {mip}"));
+		if tex.format.is_high_precision() {
+			let float = data.decode_rgbaf32()?;
+			let mut native = Vec::new();
+			for mip in 0..tex.mip_levels {
+				for layer in 0..tex.layers {
+					for depth in 0..tex.mip_size(mip).2 {
+						native.extend_from_slice(float.get(layer, depth, mip).ok_or_else(|| missing(layer, depth, mip))?);
+					}
+				}
+			}
+			
+			tex.pixels = native.iter().map(|v| to_u8(*v)).collect();
+			tex.native = Some(native);
+		} else {
+			let rgba = data.decode_rgba8()?;
+			for mip in 0..tex.mip_levels {
+				for layer in 0..tex.layers {
+					for depth in 0..tex.mip_size(mip).2 {
+						tex.pixels.extend_from_slice(rgba.get(layer, depth, mip).ok_or_else(|| missing(layer, depth, mip))?);
+					}
+				}
+			}
+		}
+		
+		Ok(tex)
 	}
 	
 	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
 	T: Write + Seek {
-		let surface = image_dds::SurfaceRgba8 {
-			width: self.width as u32,
-			height: self.height as u32,
-			depth: self.depth as u32,
-			layers: 1,
-			mipmaps: 1,
-			data: &self.pixels,
-		};
-		
-		surface.encode_dds(self.format.into(), image_dds::Quality::Normal, image_dds::Mipmaps::Disabled)?
-			.write(writer)?;
-		
-		Ok(())
+		self.write_dds(writer, &EncodeOptions::default())
 	}
 }
 
@@ -357,16 +787,7 @@ impl crate::format::external::Png for Tex {
 		let img = image::ImageReader::with_format(BufReader::new(reader), image::ImageFormat::Png)
 			.decode()?;
 		
-		Ok(Self {
-			flags: 0x00800000,
-			format: Format::A8R8G8B8,
-			width: img.width(),
-			height: img.height(),
-			depth: 1,
-			mip_levels: 1,
-			lods: true,
-			pixels: img.into_rgba8().into_vec(),
-		})
+		Ok(Self::new(img.width(), img.height(), img.into_rgba8().into_vec()))
 	}
 	
 	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
@@ -389,21 +810,31 @@ impl crate::format::external::Tiff for Tex {
 		let img = image::ImageReader::with_format(BufReader::new(reader), image::ImageFormat::Tiff)
 			.decode()?;
 		
-		Ok(Self {
-			flags: 0x00800000,
-			format: Format::A8R8G8B8,
-			width: img.width(),
-			height: img.height(),
-			depth: 1,
-			mip_levels: 1,
-			lods: true,
-			pixels: img.into_rgba8().into_vec(),
-		})
+		// keep the precision of 16 bit and float images
+		if img.color().bytes_per_pixel() / img.color().channel_count() > 1 {
+			return Ok(Self::new_native(img.width(), img.height(), img.into_rgba32f().into_vec()));
+		}
+		
+		Ok(Self::new(img.width(), img.height(), img.into_rgba8().into_vec()))
 	}
 	
 	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
 	T: Write + Seek {
 		let img = image::codecs::tiff::TiffEncoder::new(writer);
+		
+		// high precision textures are written as 16 bit
+		if let Some(native) = self.native_surface(0, 0) {
+			let pixels = native[..(self.width * self.height * 4) as usize].iter().map(|v| (v.clamp(0.0, 1.0) * 65535.0).round() as u16).collect::<Vec<_>>();
+			img.write_image(
+				bytemuck::cast_slice(&pixels),
+				self.width as u32,
+				self.height as u32,
+				image::ColorType::Rgba16.into()
+			)?;
+			
+			return Ok(());
+		}
+		
 		img.write_image(
 			&self.pixels[0..(self.width * self.height * 4) as usize],
 			self.width as u32,
@@ -421,16 +852,7 @@ impl crate::format::external::Tga for Tex {
 		let img = image::ImageReader::with_format(BufReader::new(reader), image::ImageFormat::Tga)
 			.decode()?;
 		
-		Ok(Self {
-			flags: 0x00800000,
-			format: Format::A8R8G8B8,
-			width: img.width(),
-			height: img.height(),
-			depth: 1,
-			mip_levels: 1,
-			lods: true,
-			pixels: img.into_rgba8().into_vec(),
-		})
+		Ok(Self::new(img.width(), img.height(), img.into_rgba8().into_vec()))
 	}
 	
 	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
@@ -443,6 +865,106 @@ impl crate::format::external::Tga for Tex {
 			image::ColorType::Rgba8.into()
 		)?;
 		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Jpeg for Tex {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		let img = image::ImageReader::with_format(BufReader::new(reader), image::ImageFormat::Jpeg)
+			.decode()?;
+		
+		Ok(Self::new(img.width(), img.height(), img.into_rgba8().into_vec()))
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		// jpeg has no alpha
+		let pixels = self.pixels[0..(self.width * self.height * 4) as usize].chunks_exact(4).flat_map(|v| [v[0], v[1], v[2]]).collect::<Vec<_>>();
+		let img = image::codecs::jpeg::JpegEncoder::new_with_quality(writer, 95);
+		img.write_image(
+			&pixels,
+			self.width as u32,
+			self.height as u32,
+			image::ColorType::Rgb8.into()
+		)?;
+		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Webp for Tex {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		let img = image::ImageReader::with_format(BufReader::new(reader), image::ImageFormat::WebP)
+			.decode()?;
+		
+		Ok(Self::new(img.width(), img.height(), img.into_rgba8().into_vec()))
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		let img = image::codecs::webp::WebPEncoder::new_lossless(writer);
+		img.write_image(
+			&self.pixels[0..(self.width * self.height * 4) as usize],
+			self.width as u32,
+			self.height as u32,
+			image::ColorType::Rgba8.into()
+		)?;
+		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Bmp for Tex {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		let img = image::ImageReader::with_format(BufReader::new(reader), image::ImageFormat::Bmp)
+			.decode()?;
+		
+		Ok(Self::new(img.width(), img.height(), img.into_rgba8().into_vec()))
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		let img = image::codecs::bmp::BmpEncoder::new(writer);
+		img.write_image(
+			&self.pixels[0..(self.width * self.height * 4) as usize],
+			self.width as u32,
+			self.height as u32,
+			image::ColorType::Rgba8.into()
+		)?;
+		
+		Ok(())
+	}
+}
+
+impl crate::format::external::Exr for Tex {
+	fn read<T>(reader: &mut T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		let img = image::ImageReader::with_format(BufReader::new(reader), image::ImageFormat::OpenExr)
+			.decode()?;
+		
+		Ok(Self::new_native(img.width(), img.height(), img.into_rgba32f().into_vec()))
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		let size = (self.width * self.height * 4) as usize;
+		let pixels = match self.native_surface(0, 0) {
+			Some(native) => native[..size].to_vec(),
+			None => self.pixels[..size].iter().map(|v| *v as f32 / 255.0).collect(),
+		};
+		
+		let img = image::codecs::openexr::OpenExrEncoder::new(writer);
+		img.write_image(
+			bytemuck::cast_slice(&pixels),
+			self.width as u32,
+			self.height as u32,
+			image::ColorType::Rgba32F.into()
+		)?;
+		
 		Ok(())
 	}
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use std::{fmt::Debug, io::{Read, Seek, SeekFrom, Write}};
 use binrw::{binrw, BinRead, BinWrite};
+use serde::{Deserialize, Serialize};
 use crate::{NullReader, NullWriter};
 
 mod component;
@@ -8,13 +9,17 @@ mod timeline;
 pub use timeline::*;
 mod node;
 pub use node::*;
+mod atlas;
+pub use atlas::*;
+mod render;
+pub use render::*;
 
 pub const EXT: &'static [&'static str] = &["uld"];
 
 pub type Error = binrw::Error;
 
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Uld {
 	pub main_header: UldHeader,
 	pub primary_header: AtkHeader,
@@ -184,9 +189,10 @@ impl BinWrite for Uld {
 	}
 }
 
-impl ironworks::file::File for Uld {
-	fn read(mut data: impl ironworks::FileStream) -> Result<Self, ironworks::Error> {
-		Uld::read_le(&mut data).map_err(|e| ironworks::Error::Resource(e.into()))
+impl crate::File for Uld {
+	fn read<T>(mut reader: T) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(Uld::read_le(&mut reader)?)
 	}
 }
 
@@ -208,11 +214,55 @@ impl crate::format::external::Bytes for Uld {
 	}
 }
 
+impl crate::format::external::Json for Uld {
+	type Base = ();
+	
+	fn read<T>(reader: &mut T, _base: Self::Base) -> Result<Self, crate::Error> where
+	T: Read + Seek {
+		Ok(serde_json::from_reader(reader)?)
+	}
+	
+	fn write<T>(&self, writer: &mut T) -> Result<(), crate::Error> where
+	T: Write + Seek {
+		serde_json::to_writer_pretty(writer, self)?;
+		
+		Ok(())
+	}
+}
+
+// identifiers and versions as text, they are always ascii in game files
+mod fourcc {
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+	
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum Value {
+		Text(String),
+		Bytes([u8; 4]),
+	}
+	
+	pub fn serialize<S: Serializer>(value: &[u8; 4], serializer: S) -> Result<S::Ok, S::Error> {
+		match std::str::from_utf8(value) {
+			Ok(v) => serializer.serialize_str(v),
+			Err(_) => value.serialize(serializer),
+		}
+	}
+	
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 4], D::Error> {
+		match Value::deserialize(deserializer)? {
+			Value::Text(v) => v.as_bytes().try_into().map_err(|_| serde::de::Error::custom(format!("{v} is not 4 bytes long"))),
+			Value::Bytes(v) => Ok(v),
+		}
+	}
+}
+
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UldHeader {
+	#[serde(with = "fourcc")]
 	pub identifier: [u8; 4],
+	#[serde(with = "fourcc")]
 	pub version: [u8; 4],
 	
 	pub component_offset: u32,
@@ -221,9 +271,11 @@ pub struct UldHeader {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AtkHeader {
+	#[serde(with = "fourcc")]
 	pub identifier: [u8; 4],
+	#[serde(with = "fourcc")]
 	pub version: [u8; 4],
 	
 	pub asset_offset: u32,
@@ -237,11 +289,14 @@ pub struct AtkHeader {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListHeader {
+	#[serde(with = "fourcc")]
 	pub identifier: [u8; 4],
+	#[serde(with = "fourcc")]
 	pub version: [u8; 4],
 	
+	#[serde(skip)]
 	element_count: u32,
 	pub unk1: i32,
 }
@@ -251,7 +306,7 @@ pub struct ListHeader {
 #[binrw]
 #[brw(little)]
 #[brw(import(minor_version: u8))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UldTexture {
 	pub id: u32,
 	#[br(try_map = |v: [u8; 44]| v.null_terminated())]
@@ -266,7 +321,7 @@ pub struct UldTexture {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UldPartsList {
 	pub id: u32,
 	#[br(temp)]
@@ -281,7 +336,7 @@ pub struct UldPartsList {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UldPart {
 	pub texture_id: u32,
 	pub u: u16,
@@ -292,7 +347,7 @@ pub struct UldPart {
 
 // ---------------------------------------- //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UldComponent {
 	pub id: u32,
 	pub ignore_input: bool,
@@ -378,7 +433,7 @@ impl BinWrite for UldComponent {
 
 // ---------------------------------------- //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UldTimeline {
 	pub id: u32,
 	// _size: u32,
@@ -452,7 +507,7 @@ impl BinWrite for UldTimeline {
 
 // #[binrw]
 // #[brw(little, repr = u32)]
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u32)]
 pub enum AlignmentType {
 	#[default] TopLeft = 0x0,
@@ -467,7 +522,7 @@ pub enum AlignmentType {
 	Unk(u32),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WidgetData {
 	pub id: u32,
 	pub alignment_type: AlignmentType,
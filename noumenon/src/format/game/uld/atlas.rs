@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use crate::format::{external::Bytes, game::{tex, Tex}};
+use super::Uld;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Resolution {
+	#[default] Standard,
+	/// The _hr1 textures, twice the size of the standard ones
+	High,
+}
+
+impl Resolution {
+	pub fn scale(&self) -> u32 {
+		match self {
+			Resolution::Standard => 1,
+			Resolution::High => 2,
+		}
+	}
+	
+	pub fn other(&self) -> Self {
+		match self {
+			Resolution::Standard => Resolution::High,
+			Resolution::High => Resolution::Standard,
+		}
+	}
+	
+	/// Texture path for this resolution, the uld itself always references the standard one
+	pub fn path(&self, path: &str) -> String {
+		let path = path.replace("_hr1.", ".");
+		match self {
+			Resolution::Standard => path,
+			Resolution::High => match path.rsplit_once('.') {
+				Some((name, ext)) => format!("{name}_hr1.{ext}"),
+				None => format!("{path}_hr1"),
+			},
+		}
+	}
+}
+
+/// A single part cut out of its texture
+#[derive(Debug, Clone)]
+pub struct PartImage {
+	/// Id of the parts list
+	pub list_id: u32,
+	/// Index of the part in the parts list
+	pub part_index: usize,
+	pub texture_id: u32,
+	pub width: u32,
+	pub height: u32,
+	/// Rgba8
+	pub pixels: Vec<u8>,
+}
+
+impl Uld {
+	pub fn texture_path(&self, texture_id: u32, resolution: Resolution) -> Option<String> {
+		self.assets.iter().find(|v| v.id == texture_id).map(|v| resolution.path(&v.path))
+	}
+	
+	/// Cuts every part out of the textures they use, parts of textures that can't be loaded are skipped
+	pub fn export_parts(&self, resolution: Resolution, file_reader: impl Fn(&str) -> Option<Vec<u8>>) -> Result<Vec<PartImage>, crate::Error> {
+		let mut textures = HashMap::new();
+		let mut images = Vec::new();
+		for list in &self.parts_lists {
+			for (part_index, part) in list.parts.iter().enumerate() {
+				if !textures.contains_key(&part.texture_id) {
+					let tex = match self.texture_path(part.texture_id, resolution).and_then(|v| file_reader(&v)) {
+						Some(data) => Some(<Tex as Bytes>::read(&mut std::io::Cursor::new(data))?),
+						None => None,
+					};
+					textures.insert(part.texture_id, tex);
+				}
+				
+				let Some(tex) = &textures[&part.texture_id] else {continue};
+				let Some((x, y, width, height)) = part_rect(part, resolution, tex) else {continue};
+				
+				let slice = tex.slice(0, 0, 0);
+				let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+				for row in y..y + height {
+					let start = ((row * slice.width + x) * 4) as usize;
+					pixels.extend_from_slice(&slice.pixels[start..start + width as usize * 4]);
+				}
+				
+				images.push(PartImage {
+					list_id: list.id,
+					part_index,
+					texture_id: part.texture_id,
+					width,
+					height,
+					pixels,
+				});
+			}
+		}
+		
+		Ok(images)
+	}
+	
+	/// Draws the part images back into their textures, images are resized if they don't match the size of the part.
+	/// With `pair` the textures of the other resolution are updated as well, scaling the images by 2x or 0.5x.
+	/// Returns the changed textures by their game path
+	pub fn import_parts(&self, parts: &[PartImage], resolution: Resolution, pair: bool, file_reader: impl Fn(&str) -> Option<Vec<u8>>) -> Result<Vec<(String, Tex)>, crate::Error> {
+		let mut result = self.import_parts_resolution(parts, resolution, false, &file_reader)?;
+		if pair {
+			result.extend(self.import_parts_resolution(parts, resolution.other(), true, &file_reader)?);
+		}
+		
+		Ok(result)
+	}
+	
+	fn import_parts_resolution(&self, parts: &[PartImage], resolution: Resolution, optional: bool, file_reader: &impl Fn(&str) -> Option<Vec<u8>>) -> Result<Vec<(String, Tex)>, crate::Error> {
+		let mut textures = Vec::<(String, Tex)>::new();
+		for image in parts {
+			if image.pixels.len() < (image.width * image.height * 4) as usize {
+				return Err(crate::Error::InvalidData("Part image buffer is too small".to_string()));
+			}
+			
+			let Some(part) = self.parts_lists.iter().find(|v| v.id == image.list_id).and_then(|v| v.parts.get(image.part_index)) else {
+				return Err(crate::Error::InvalidData(format!("Part {} of list {} does not exist", image.part_index, image.list_id)));
+			};
+			
+			let path = self.texture_path(part.texture_id, resolution).ok_or_else(|| crate::Error::InvalidData(format!("Texture {} does not exist", part.texture_id)))?;
+			let tex = match textures.iter().position(|(v, _)| *v == path) {
+				Some(i) => &mut textures[i].1,
+				None => {
+					// not every texture has a hr version, so the pair is optional
+					let Some(data) = file_reader(&path) else {
+						if optional {continue}
+						return Err(crate::Error::NotFound(path));
+					};
+					
+					textures.push((path, <Tex as Bytes>::read(&mut std::io::Cursor::new(data))?));
+					&mut textures.last_mut().unwrap().1
+				}
+			};
+			
+			let Some((x, y, width, height)) = part_rect(part, resolution, tex) else {continue};
+			let resized;
+			let pixels = if image.width == width && image.height == height {
+				&image.pixels
+			} else {
+				let img: image::ImageBuffer<image::Rgba<u8>, &[u8]> = image::ImageBuffer::from_raw(image.width, image.height, &image.pixels[..]).unwrap();
+				resized = image::imageops::resize(&img, width, height, image::imageops::FilterType::Lanczos3).into_raw();
+				&resized
+			};
+			
+			let slice = tex.slice_mut(0, 0, 0);
+			for row in 0..height {
+				let start = (((y + row) * slice.width + x) * 4) as usize;
+				let src = (row * width * 4) as usize;
+				slice.pixels[start..start + width as usize * 4].copy_from_slice(&pixels[src..src + width as usize * 4]);
+			}
+		}
+		
+		for (_, tex) in &mut textures {
+			tex.generate_mips(tex.mip_levels, tex::MipFilter::default(), false);
+		}
+		
+		Ok(textures)
+	}
+}
+
+// the rect of a part scaled to the resolution, clamped to the texture
+pub(super) fn part_rect(part: &super::UldPart, resolution: Resolution, tex: &Tex) -> Option<(u32, u32, u32, u32)> {
+	let scale = resolution.scale();
+	let x = (part.u as u32 * scale).min(tex.width);
+	let y = (part.v as u32 * scale).min(tex.height);
+	let width = (part.w as u32 * scale).min(tex.width - x);
+	let height = (part.h as u32 * scale).min(tex.height - y);
+	if width == 0 || height == 0 {return None}
+	
+	Some((x, y, width, height))
+}
\ No newline at end of file
@@ -1,9 +1,10 @@
 use std::io::{Read, Seek, Write};
 use binrw::{binrw, BinRead, BinWrite};
+use serde::{Deserialize, Serialize};
 
 #[binrw]
 #[brw(little, repr = u8)]
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ComponentType {
 	#[default] Custom = 0x0,
 	Button = 0x1,
@@ -34,7 +35,7 @@ pub enum ComponentType {
 
 // ---------------------------------------- //
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Component {
 	Custom(Vec<u8>),
 	Button(ButtonComponent),
@@ -175,35 +176,35 @@ impl BinWrite for Component {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ButtonComponent {
 	pub unk: [u32; 2],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct WindowComponent {
 	pub unk: [u32; 8],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct CheckBoxComponent {
 	pub unk: [u32; 3],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct RadioButtonComponent {
 	pub unk: [u32; 4],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct GaugeComponent {
 	pub unk: [u32; 6],
 	pub vertical_margin: u16,
@@ -216,7 +217,7 @@ pub struct GaugeComponent {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SliderComponent {
 	pub unk: [u32; 4],
 	#[br(map = |v: u8| v != 0)]
@@ -229,7 +230,7 @@ pub struct SliderComponent {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TextInputComponent {
 	pub unk: [u32; 16],
 	pub color: u32,
@@ -238,7 +239,7 @@ pub struct TextInputComponent {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct NumericInputComponent {
 	pub unk: [u32; 5],
 	pub color: u32,
@@ -246,7 +247,7 @@ pub struct NumericInputComponent {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ListComponent {
 	pub unk: [u32; 5],
 	pub wrap: u8,
@@ -256,21 +257,21 @@ pub struct ListComponent {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct DropDownComponent {
 	pub unk: [u32; 2],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TabComponent {
 	pub unk: [u32; 4],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TreeListComponent {
 	pub unk: [u32; 5],
 	pub wrap: u8,
@@ -280,7 +281,7 @@ pub struct TreeListComponent {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ScrollBarComponent {
 	pub unk: [u32; 4],
 	pub margin: u16,
@@ -292,49 +293,49 @@ pub struct ScrollBarComponent {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ListItemComponent {
 	pub unk: [u32; 4],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct IconComponent {
 	pub unk: [u32; 8],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct IconWithTextComponent {
 	pub unk: [u32; 2],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct DragDropComponent {
 	pub unk: [u32; 1],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct LeveCardComponent {
 	pub unk: [u32; 3],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct NineGridComponent {
 	pub unk: [u32; 2],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct JournalComponent {
 	pub unk: [u32; 32],
 	pub margin: u16,
@@ -346,28 +347,28 @@ pub struct JournalComponent {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct MultipurposeComponent {
 	pub unk: [u32; 3],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct MapComponent {
 	pub unk: [u32; 10],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct PreviewComponent {
 	pub unk: [u32; 2],
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Unknown25Component {
 	pub unk: [u32; 3],
 }
\ No newline at end of file
@@ -1,9 +1,10 @@
 use std::io::{Read, Seek, Write, SeekFrom};
 use binrw::{binrw, BinRead, BinWrite};
+use serde::{Deserialize, Serialize};
 
 #[binrw]
 #[brw(little, repr = u8)]
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FontType {
 	#[default] Axis = 0x0,
 	MiedingerMed = 0x1,
@@ -16,7 +17,7 @@ pub enum FontType {
 
 #[binrw]
 #[brw(little, repr = u16)]
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CollisionType {
 	#[default] Hit = 0x0,
 	Focus = 0x1,
@@ -25,7 +26,7 @@ pub enum CollisionType {
 
 #[binrw]
 #[brw(little, repr = u8)]
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GridPartsType {
 	#[default] Divide = 0x0,
 	Compose = 0x1,
@@ -33,7 +34,7 @@ pub enum GridPartsType {
 
 #[binrw]
 #[brw(little, repr = u8)]
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GridRenderType {
 	#[default] Scale = 0x0,
 	Tile = 0x1,
@@ -41,7 +42,7 @@ pub enum GridRenderType {
 
 #[binrw]
 #[brw(little, repr = u8)]
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SheetType {
 	#[default] Addon = 0x0,
 	Lobby = 0x1,
@@ -49,7 +50,7 @@ pub enum SheetType {
 
 // ---------------------------------------- //
 
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct NodeData {
 	pub node_id: u32,
 	pub parent_id: i32,
@@ -243,7 +244,7 @@ impl BinWrite for NodeData {
 
 // ---------------------------------------- //
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Node {
 	Image(ImageNode),
 	Text(TextNode),
@@ -323,7 +324,7 @@ impl BinWrite for Node {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ComponentNodeNode {
 	Button(ButtonComponentNode),
 	Window(WindowComponentNode),
@@ -427,7 +428,7 @@ impl BinWrite for ComponentNodeNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ImageNode {
 	pub part_list_id: u32,
 	pub part_id: u32,
@@ -443,7 +444,7 @@ pub struct ImageNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TextNode {
 	pub text_id: u32,
 	pub color: u32,
@@ -488,7 +489,7 @@ pub struct TextNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct NineGridNode {
 	pub part_list_id: u32,
 	pub part_id: u32,
@@ -503,7 +504,7 @@ pub struct NineGridNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct CounterNode {
 	pub part_list_id: u32,
 	pub part_id: u8,
@@ -516,7 +517,7 @@ pub struct CounterNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct CollisionNode {
 	pub collision_type: CollisionType,
 	pub unk1: u16,
@@ -528,7 +529,7 @@ pub struct CollisionNode {
 #[binrw]
 #[brw(little)]
 #[br(import(parent_type: super::ComponentType, node_type: u32))]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ComponentNode {
 	pub index: u8,
 	pub up: u8,
@@ -579,14 +580,14 @@ pub struct ComponentNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ButtonComponentNode {
 	pub text_id: u32,
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct WindowComponentNode {
 	pub title_text_id: u32,
 	pub subtitle_text_id: u32,
@@ -606,14 +607,14 @@ pub struct WindowComponentNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct CheckBoxComponentNode {
 	pub text_id: u32,
 }
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct RadioButtonComponentNode {
 	pub text_id: u32,
 	pub group_id: u32,
@@ -621,7 +622,7 @@ pub struct RadioButtonComponentNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct GaugeComponentNode {
 	pub indicator: i32,
 	pub min: i32,
@@ -631,7 +632,7 @@ pub struct GaugeComponentNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SliderComponentNode {
 	pub min: i32,
 	pub max: i32,
@@ -640,7 +641,7 @@ pub struct SliderComponentNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TextInputComponentNode {
 	pub max_width: u32,
 	pub max_line: u32,
@@ -703,7 +704,7 @@ pub struct TextInputComponentNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct NumericInputComponentNode {
 	pub value: i32,
 	pub max: i32,
@@ -718,7 +719,7 @@ pub struct NumericInputComponentNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ListComponentNode {
 	pub row_num: u16,
 	pub column_num: u16,
@@ -726,7 +727,7 @@ pub struct ListComponentNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TabbedComponentNode {
 	pub text_id: u32,
 	pub group_id: u32,
@@ -734,7 +735,7 @@ pub struct TabbedComponentNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ListItemComponentNode {
 	#[br(map = |v: u8| v != 0)]
 	#[bw(map = |v: &bool| if *v {1u8} else {0})]
@@ -744,7 +745,7 @@ pub struct ListItemComponentNode {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct NineGridTextComponentNode {
 	pub text_id: u32,
 }
\ No newline at end of file
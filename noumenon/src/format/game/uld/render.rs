@@ -0,0 +1,481 @@
+use std::collections::HashMap;
+use crate::format::{external::Bytes, game::{Tex, Fdt, fdt::Glyph}};
+use super::{Uld, NodeData, Node, GridRenderType, Resolution, FontType, TextNode, NodeValues};
+
+// components can contain other components, this stops cycles
+const MAX_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+	/// Timeline frame the nodes are sampled at
+	pub frame: f32,
+	pub resolution: Resolution,
+	/// Text shown by counter nodes by node id, counters without one show 0
+	pub counters: HashMap<u32, String>,
+	/// Text shown by text nodes by node id, the addon sheet isn't loaded so text nodes without one are empty
+	pub texts: HashMap<u32, String>,
+}
+
+impl Uld {
+	/// Renders a widget into an image the size of its root nodes.
+	/// Text nodes are drawn with the game font closest to their size, edges are drawn but the other styles (bold, glare, ...) are not
+	pub fn render_widget(&self, widget_id: u32, options: &RenderOptions, file_reader: impl Fn(&str) -> Option<Vec<u8>>) -> Result<Tex, crate::Error> {
+		let widget = self.widgets.iter().find(|v| v.id == widget_id).ok_or_else(|| crate::Error::NotFound(format!("Widget {widget_id}")))?;
+		Renderer::new(self, options, file_reader).render(&widget.nodes)
+	}
+	
+	/// Renders a component on its own, see `render_widget`
+	pub fn render_component(&self, component_id: u32, options: &RenderOptions, file_reader: impl Fn(&str) -> Option<Vec<u8>>) -> Result<Tex, crate::Error> {
+		let component = self.components.iter().find(|v| v.id == component_id).ok_or_else(|| crate::Error::NotFound(format!("Component {component_id}")))?;
+		Renderer::new(self, options, file_reader).render(&component.nodes)
+	}
+}
+
+// ---------------------------------------- //
+
+// 2d affine transform, (x, y) -> (a * x + c * y + tx, b * x + d * y + ty)
+#[derive(Debug, Clone, Copy)]
+struct Transform([f32; 6]);
+
+impl Transform {
+	fn scale(x: f32, y: f32) -> Self {
+		Self([x, 0.0, 0.0, y, 0.0, 0.0])
+	}
+	
+	fn translate(x: f32, y: f32) -> Self {
+		Self([1.0, 0.0, 0.0, 1.0, x, y])
+	}
+	
+	fn rotate(r: f32) -> Self {
+		let (sin, cos) = r.sin_cos();
+		Self([cos, sin, -sin, cos, 0.0, 0.0])
+	}
+	
+	// self applied after other
+	fn mul(&self, other: &Self) -> Self {
+		let [a, b, c, d, tx, ty] = self.0;
+		let [a2, b2, c2, d2, tx2, ty2] = other.0;
+		Self([
+			a * a2 + c * b2,
+			b * a2 + d * b2,
+			a * c2 + c * d2,
+			b * c2 + d * d2,
+			a * tx2 + c * ty2 + tx,
+			b * tx2 + d * ty2 + ty,
+		])
+	}
+	
+	fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+		let [a, b, c, d, tx, ty] = self.0;
+		(a * x + c * y + tx, b * x + d * y + ty)
+	}
+	
+	fn inverse(&self) -> Option<Self> {
+		let [a, b, c, d, tx, ty] = self.0;
+		let det = a * d - b * c;
+		if det.abs() < f32::EPSILON {return None}
+		let (a, b, c, d) = (d / det, -b / det, -c / det, a / det);
+		Some(Self([a, b, c, d, -(a * tx + c * ty), -(b * tx + d * ty)]))
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+struct State {
+	transform: Transform,
+	alpha: f32,
+	multiply: [f32; 3],
+	add: [f32; 3],
+	// x1, y1, x2, y2 in output pixels
+	clip: [f32; 4],
+}
+
+struct Renderer<'a, F: Fn(&str) -> Option<Vec<u8>>> {
+	uld: &'a Uld,
+	options: &'a RenderOptions,
+	file_reader: F,
+	textures: HashMap<u32, Option<Tex>>,
+	fonts: HashMap<String, Option<Fdt>>,
+	// by texture file, not channel
+	font_textures: HashMap<u16, Option<Tex>>,
+	width: u32,
+	height: u32,
+	pixels: Vec<u8>,
+}
+
+impl<'a, F: Fn(&str) -> Option<Vec<u8>>> Renderer<'a, F> {
+	fn new(uld: &'a Uld, options: &'a RenderOptions, file_reader: F) -> Self {
+		Self {
+			uld,
+			options,
+			file_reader,
+			textures: HashMap::new(),
+			fonts: HashMap::new(),
+			font_textures: HashMap::new(),
+			width: 0,
+			height: 0,
+			pixels: Vec::new(),
+		}
+	}
+	
+	fn render(mut self, nodes: &[NodeData]) -> Result<Tex, crate::Error> {
+		let scale = self.options.resolution.scale() as f32;
+		let roots = roots(nodes);
+		let width = roots.iter().map(|v| v.x as i32 + v.w as i32).max().unwrap_or(0).max(0) as u32;
+		let height = roots.iter().map(|v| v.y as i32 + v.h as i32).max().unwrap_or(0).max(0) as u32;
+		if width == 0 || height == 0 {
+			return Err(crate::Error::InvalidData("Nothing to render".to_string()));
+		}
+		
+		self.width = width * scale as u32;
+		self.height = height * scale as u32;
+		self.pixels = vec![0; (self.width * self.height * 4) as usize];
+		
+		let state = State {
+			transform: Transform::scale(scale, scale),
+			alpha: 1.0,
+			multiply: [100.0; 3],
+			add: [0.0; 3],
+			clip: [0.0, 0.0, self.width as f32, self.height as f32],
+		};
+		
+		self.draw_siblings(nodes, &roots, &state, 0)?;
+		
+		Ok(Tex::new(self.width, self.height, self.pixels))
+	}
+	
+	// siblings earlier in the list are drawn on top
+	fn draw_siblings(&mut self, nodes: &[NodeData], siblings: &[&NodeData], state: &State, depth: usize) -> Result<(), crate::Error> {
+		for node in siblings.iter().rev() {
+			self.draw_node(nodes, node, state, depth)?;
+		}
+		
+		Ok(())
+	}
+	
+	fn draw_node(&mut self, nodes: &[NodeData], node: &NodeData, parent: &State, depth: usize) -> Result<(), crate::Error> {
+		if !node.visible {return Ok(())}
+		
+		let values = self.uld.node_values(node, self.options.frame);
+		let origin_x = node.origin_x as f32;
+		let origin_y = node.origin_y as f32;
+		let local = Transform::translate(values.x + origin_x, values.y + origin_y)
+			.mul(&Transform::rotate(values.rotation))
+			.mul(&Transform::scale(values.scale_x, values.scale_y))
+			.mul(&Transform::translate(-origin_x, -origin_y));
+		
+		let mut state = State {
+			transform: parent.transform.mul(&local),
+			alpha: parent.alpha * values.alpha / 255.0,
+			multiply: [0, 1, 2].map(|i| parent.multiply[i] * values.multiply[i] / 100.0),
+			add: [0, 1, 2].map(|i| parent.add[i] + values.add[i]),
+			clip: parent.clip,
+		};
+		
+		let w = node.w as f32;
+		let h = node.h as f32;
+		match &node.node {
+			Node::Image(v) => {
+				let wrap = v.wrap == 1;
+				let (flip_h, flip_v) = (v.flip_h, v.flip_v);
+				self.draw_part(v.part_list_id, v.part_id as usize, &state, [0.0, 0.0, w, h], |x, y, pw, ph| {
+					let x = if flip_h {w - x} else {x};
+					let y = if flip_v {h - y} else {y};
+					// wrap 1 tiles the part, everything else stretches it
+					if wrap {(x % pw, y % ph)} else {(x * pw / w, y * ph / h)}
+				});
+			}
+			
+			Node::NineGrid(v) => {
+				let tile = v.grid_render_type == GridRenderType::Tile;
+				let (top, bottom, left, right) = (v.top_offset as f32, v.bottom_offset as f32, v.left_offset as f32, v.right_offset as f32);
+				self.draw_part(v.part_list_id, v.part_id as usize, &state, [0.0, 0.0, w, h], |x, y, pw, ph| {
+					(grid_coord(x, w, pw, left, right, tile), grid_coord(y, h, ph, top, bottom, tile))
+				});
+			}
+			
+			Node::Counter(v) => {
+				let text = self.options.counters.get(&node.node_id).map_or("0", |v| v.as_str());
+				let glyphs = text.chars().map(|c| match c {
+					'0'..='9' => (Some(c as usize - '0' as usize), v.number_width as f32),
+					',' | '.' => (Some(10), v.comma_width as f32),
+					_ => (None, v.space_width as f32),
+				}).collect::<Vec<_>>();
+				
+				let total = glyphs.iter().map(|v| v.1).sum::<f32>();
+				let mut x = match v.alignment % 3 {
+					0 => 0.0,
+					1 => (w - total) / 2.0,
+					_ => w - total,
+				};
+				
+				for (part, width) in glyphs {
+					if let Some(part) = part {
+						let index = v.part_id as usize + part;
+						if let Some((pw, ph)) = self.part_size(v.part_list_id, index) {
+							let y = match v.alignment / 3 {
+								0 => 0.0,
+								1 => (h - ph) / 2.0,
+								_ => h - ph,
+							};
+							self.draw_part(v.part_list_id, index, &state, [x, y, pw, ph], |x, y, _, _| (x, y));
+						}
+					}
+					x += width;
+				}
+			}
+			
+			Node::Text(v) => {
+				if let Some(text) = self.options.texts.get(&node.node_id) {
+					self.draw_text(v, text, &values, &state, w, h);
+				}
+			}
+			
+			_ => {}
+		}
+		
+		if node.clip {
+			let corners = [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)].map(|(x, y)| state.transform.apply(x, y));
+			state.clip = [
+				state.clip[0].max(corners.iter().map(|v| v.0).fold(f32::MAX, f32::min)),
+				state.clip[1].max(corners.iter().map(|v| v.1).fold(f32::MAX, f32::min)),
+				state.clip[2].min(corners.iter().map(|v| v.0).fold(f32::MIN, f32::max)),
+				state.clip[3].min(corners.iter().map(|v| v.1).fold(f32::MIN, f32::max)),
+			];
+		}
+		
+		if let Node::Component(v) = &node.node {
+			if depth < MAX_DEPTH {
+				if let Some(component) = self.uld.components.iter().find(|c| c.id == v.component_id) {
+					self.draw_siblings(&component.nodes, &roots(&component.nodes), &state, depth + 1)?;
+				}
+			}
+		}
+		
+		let children = nodes.iter().filter(|v| v.parent_id >= 0 && v.parent_id as u32 == node.node_id).collect::<Vec<_>>();
+		self.draw_siblings(nodes, &children, &state, depth)
+	}
+	
+	fn load_texture(&mut self, texture_id: u32) {
+		if self.textures.contains_key(&texture_id) {return}
+		
+		let tex = self.uld.texture_path(texture_id, self.options.resolution)
+			.and_then(|v| (self.file_reader)(&v))
+			.and_then(|v| <Tex as Bytes>::read(&mut std::io::Cursor::new(v)).ok());
+		self.textures.insert(texture_id, tex);
+	}
+	
+	// size of the part in node units
+	fn part_size(&self, list_id: u32, index: usize) -> Option<(f32, f32)> {
+		let part = self.uld.parts_lists.iter().find(|v| v.id == list_id)?.parts.get(index)?;
+		Some((part.w as f32, part.h as f32))
+	}
+	
+	// draws a rect of the node, `map` turns a position in the rect into a position in the part (both in node units)
+	fn draw_part(&mut self, list_id: u32, index: usize, state: &State, [x, y, w, h]: [f32; 4], map: impl Fn(f32, f32, f32, f32) -> (f32, f32)) {
+		let uld = self.uld;
+		let Some(part) = uld.parts_lists.iter().find(|v| v.id == list_id).and_then(|v| v.parts.get(index)) else {return};
+		let resolution = self.options.resolution;
+		let scale = resolution.scale() as f32;
+		self.load_texture(part.texture_id);
+		let Some(tex) = &self.textures[&part.texture_id] else {return};
+		let Some((px, py, pw, ph)) = super::atlas::part_rect(part, resolution, tex) else {return};
+		let slice = tex.slice(0, 0, 0);
+		
+		let transform = state.transform.mul(&Transform::translate(x, y));
+		let Some(inverse) = transform.inverse() else {return};
+		let [x1, y1, x2, y2] = pixel_bounds(&transform, w, h, state.clip, self.width, self.height);
+		
+		let part_w = pw as f32 / scale;
+		let part_h = ph as f32 / scale;
+		for oy in y1..y2 {
+			for ox in x1..x2 {
+				let (lx, ly) = inverse.apply(ox as f32 + 0.5, oy as f32 + 0.5);
+				if lx < 0.0 || ly < 0.0 || lx >= w || ly >= h {continue}
+				
+				let (sx, sy) = map(lx, ly, part_w, part_h);
+				let tx = px + ((sx * scale) as u32).min(pw - 1);
+				let ty = py + ((sy * scale) as u32).min(ph - 1);
+				let i = ((ty * slice.width + tx) * 4) as usize;
+				let src = &slice.pixels[i..i + 4];
+				
+				let color = [0, 1, 2].map(|c| (src[c] as f32 * state.multiply[c] / 100.0 + state.add[c]).clamp(0.0, 255.0) / 255.0);
+				let alpha = src[3] as f32 / 255.0 * state.alpha;
+				if alpha <= 0.0 {continue}
+				
+				let o = ((oy * self.width + ox) * 4) as usize;
+				blend(&mut self.pixels[o..o + 4], color, alpha);
+			}
+		}
+	}
+	
+	fn load_font(&mut self, path: &str) {
+		if self.fonts.contains_key(path) {return}
+		
+		let fdt = (self.file_reader)(path)
+			.and_then(|v| <Fdt as Bytes>::read(&mut std::io::Cursor::new(v)).ok());
+		self.fonts.insert(path.to_string(), fdt);
+	}
+	
+	// lays out the text in node units and draws every glyph, the edge first if the node has one
+	fn draw_text(&mut self, node: &TextNode, text: &str, values: &NodeValues, state: &State, w: f32, h: f32) {
+		let (path, font_size) = font_path(node.font, node.font_size as u32 * self.options.resolution.scale());
+		self.load_font(&path);
+		let Some(fdt) = &self.fonts[&path] else {return};
+		
+		// font pixels per node unit
+		let ratio = font_size as f32 / node.font_size.max(1) as f32;
+		let lines = text.split('\n').map(|line| {
+			let mut x = 0;
+			let mut prev = None;
+			let mut glyphs = Vec::new();
+			for c in line.chars() {
+				let Some(glyph) = fdt.glyph(c) else {continue};
+				if let Some(prev) = prev {x += fdt.kerning(prev, c)}
+				glyphs.push((x, *glyph));
+				x += glyph.advance() + node.char_spacing as i32;
+				prev = Some(c);
+			}
+			(x as f32 / ratio, glyphs)
+		}).collect::<Vec<_>>();
+		
+		let line_height = fdt.line_height as f32 / ratio;
+		let total = lines.len() as f32 * line_height;
+		let mut y = match node.alignment / 3 {
+			0 => 0.0,
+			1 => (h - total) / 2.0,
+			_ => h - total,
+		};
+		
+		let mut glyphs = Vec::new();
+		for (width, line) in lines {
+			let x = match node.alignment % 3 {
+				0 => 0.0,
+				1 => (w - width) / 2.0,
+				_ => w - width,
+			};
+			
+			for (gx, glyph) in line {
+				glyphs.push((x + gx as f32 / ratio, y + glyph.offset_y as f32 / ratio, glyph));
+			}
+			y += line_height;
+		}
+		
+		// colors are stored as rgba bytes, timelines only animate the rgb part
+		let color = |color: u32, animated: Option<[f32; 3]>| {
+			let [r, g, b, a] = color.to_le_bytes().map(|v| v as f32);
+			let [r, g, b] = animated.unwrap_or([r, g, b]);
+			[r, g, b, a]
+		};
+		
+		let mut passes = Vec::new();
+		if node.edge {
+			let edge = color(node.edge_color, values.edge_color);
+			for (x, y) in [(-1.0, -1.0), (0.0, -1.0), (1.0, -1.0), (-1.0, 0.0), (1.0, 0.0), (-1.0, 1.0), (0.0, 1.0), (1.0, 1.0)] {
+				passes.push((x / ratio, y / ratio, edge));
+			}
+		}
+		passes.push((0.0, 0.0, color(node.color, values.text_color)));
+		
+		for (ox, oy, color) in passes {
+			for (x, y, glyph) in &glyphs {
+				self.draw_glyph(glyph, ratio, state, [x + ox, y + oy], color);
+			}
+		}
+	}
+	
+	// glyphs only use a single channel of their texture as coverage
+	fn draw_glyph(&mut self, glyph: &Glyph, ratio: f32, state: &State, [x, y]: [f32; 2], color: [f32; 4]) {
+		if glyph.width == 0 || glyph.height == 0 {return}
+		
+		let file = glyph.texture_index / 4;
+		if !self.font_textures.contains_key(&file) {
+			let tex = (self.file_reader)(&Fdt::texture_path(glyph.texture_index, false))
+				.and_then(|v| <Tex as Bytes>::read(&mut std::io::Cursor::new(v)).ok());
+			self.font_textures.insert(file, tex);
+		}
+		let Some(tex) = &self.font_textures[&file] else {return};
+		let slice = tex.slice(0, 0, 0);
+		let channel = (glyph.texture_index % 4) as usize;
+		
+		let w = glyph.width as f32 / ratio;
+		let h = glyph.height as f32 / ratio;
+		let transform = state.transform.mul(&Transform::translate(x, y));
+		let Some(inverse) = transform.inverse() else {return};
+		let [x1, y1, x2, y2] = pixel_bounds(&transform, w, h, state.clip, self.width, self.height);
+		
+		let rgb = [0, 1, 2].map(|c| (color[c] * state.multiply[c] / 100.0 + state.add[c]).clamp(0.0, 255.0) / 255.0);
+		for oy in y1..y2 {
+			for ox in x1..x2 {
+				let (lx, ly) = inverse.apply(ox as f32 + 0.5, oy as f32 + 0.5);
+				if lx < 0.0 || ly < 0.0 || lx >= w || ly >= h {continue}
+				
+				let tx = glyph.x as u32 + ((lx * ratio) as u32).min(glyph.width as u32 - 1);
+				let ty = glyph.y as u32 + ((ly * ratio) as u32).min(glyph.height as u32 - 1);
+				if tx >= slice.width || ty >= slice.height {continue}
+				
+				let coverage = slice.pixels[((ty * slice.width + tx) * 4) as usize + channel] as f32 / 255.0;
+				let alpha = coverage * color[3] / 255.0 * state.alpha;
+				if alpha <= 0.0 {continue}
+				
+				let o = ((oy * self.width + ox) * 4) as usize;
+				blend(&mut self.pixels[o..o + 4], rgb, alpha);
+			}
+		}
+	}
+}
+
+// the fdt files the game has for each font, named by their size. picks the smallest one that is at least the size
+fn font_path(font: FontType, size: u32) -> (String, u32) {
+	let (name, sizes): (&str, &[u32]) = match font {
+		FontType::Axis => ("AXIS", &[12, 14, 18, 36]),
+		FontType::MiedingerMed => ("MiedingerMid", &[10, 12, 14, 18, 36]),
+		FontType::Miedinger => ("Meidinger", &[16, 20, 40]),
+		FontType::TrumpGothic => ("TrumpGothic", &[23, 34, 68]),
+		FontType::Jupiter => ("Jupiter", &[16, 20, 23, 46]),
+		FontType::JupiterLarge => ("Jupiter", &[45, 90]),
+	};
+	
+	let size = sizes.iter().copied().find(|v| *v >= size).unwrap_or(sizes[sizes.len() - 1]);
+	(format!("common/font/{name}_{size}.fdt"), size)
+}
+
+// output pixels the transformed rect covers, limited to the clip rect and image
+fn pixel_bounds(transform: &Transform, w: f32, h: f32, clip: [f32; 4], width: u32, height: u32) -> [u32; 4] {
+	let corners = [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)].map(|(x, y)| transform.apply(x, y));
+	[
+		corners.iter().map(|v| v.0).fold(f32::MAX, f32::min).max(clip[0]).max(0.0).floor() as u32,
+		corners.iter().map(|v| v.1).fold(f32::MAX, f32::min).max(clip[1]).max(0.0).floor() as u32,
+		corners.iter().map(|v| v.0).fold(f32::MIN, f32::max).min(clip[2]).min(width as f32).ceil() as u32,
+		corners.iter().map(|v| v.1).fold(f32::MIN, f32::max).min(clip[3]).min(height as f32).ceil() as u32,
+	]
+}
+
+// alpha blends the color over the rgba8 pixel, color and alpha are 0-1
+fn blend(dst: &mut [u8], color: [f32; 3], alpha: f32) {
+	let dst_alpha = dst[3] as f32 / 255.0;
+	let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+	for c in 0..3 {
+		let v = (color[c] * alpha + dst[c] as f32 / 255.0 * dst_alpha * (1.0 - alpha)) / out_alpha;
+		dst[c] = (v * 255.0).round() as u8;
+	}
+	dst[3] = (out_alpha * 255.0).round() as u8;
+}
+
+// nodes without a parent in the list
+fn roots(nodes: &[NodeData]) -> Vec<&NodeData> {
+	nodes.iter().filter(|v| v.parent_id < 0 || !nodes.iter().any(|p| p.node_id as i32 == v.parent_id)).collect()
+}
+
+// maps a position in a nine grid to a position in its part, the corners keep their size and the center scales or tiles
+fn grid_coord(pos: f32, size: f32, part: f32, start: f32, end: f32, tile: bool) -> f32 {
+	if pos < start {return pos}
+	if pos >= size - end {return (part - (size - pos)).max(0.0)}
+	
+	let center = part - start - end;
+	if center <= 0.0 {return start.min(part)}
+	if tile {
+		start + (pos - start) % center
+	} else {
+		start + (pos - start) * center / (size - start - end)
+	}
+}
\ No newline at end of file
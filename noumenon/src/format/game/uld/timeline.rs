@@ -1,7 +1,8 @@
 use std::io::{Read, Seek, Write, SeekFrom};
 use binrw::{binrw, BinRead, BinWrite};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct FrameData {
 	pub start_frame: u32,
 	pub end_frame: u32,
@@ -10,6 +11,12 @@ pub struct FrameData {
 	pub keygroups: Vec<KeyGroup>,
 }
 
+impl FrameData {
+	pub fn contains(&self, frame: f32) -> bool {
+		self.start_frame as f32 <= frame && frame <= self.end_frame as f32
+	}
+}
+
 impl BinRead for FrameData {
 	type Args<'a> = ();
 	
@@ -59,7 +66,7 @@ impl BinWrite for FrameData {
 	}
 }
 
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct KeyGroup {
 	pub usage: KeyUsage,
 	// keygroup_type: KeyGroupType,
@@ -68,6 +75,13 @@ pub struct KeyGroup {
 	pub frames: Keyframes,
 }
 
+impl KeyGroup {
+	/// Value at the frame, see `Keyframes::sample`
+	pub fn sample(&self, frame: f32) -> Option<Vec<f32>> {
+		self.frames.sample(frame)
+	}
+}
+
 impl BinRead for KeyGroup {
 	type Args<'a> = ();
 	
@@ -124,7 +138,7 @@ impl BinWrite for KeyGroup {
 
 #[binrw]
 #[brw(little, repr = u16)]
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum KeyUsage {
 	#[default] Position = 0x0,
 	Rotation = 0x1,
@@ -138,7 +152,7 @@ pub enum KeyUsage {
 
 #[binrw]
 #[brw(little, repr = u16)]
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum KeyGroupType {
 	#[default] Float1 = 0x0,
 	Float2 = 0x1,
@@ -172,7 +186,7 @@ pub enum KeyGroupType {
 
 // ---------------------------------------- //
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Keyframes {
 	Float1(Vec<Float1Keyframe>),
 	Float2(Vec<Float2Keyframe>),
@@ -235,6 +249,273 @@ impl Keyframes {
 			// Self::Number(_) => KeyGroupType::Number,
 		}
 	}
+	
+	pub fn len(&self) -> usize {
+		match self {
+			Self::Float1(v) => v.len(),
+			Self::Float2(v) => v.len(),
+			Self::Float3(v) => v.len(),
+			Self::SByte1(v) => v.len(),
+			Self::SByte2(v) => v.len(),
+			Self::SByte3(v) => v.len(),
+			Self::Byte1(v) => v.len(),
+			Self::Byte2(v) => v.len(),
+			Self::Byte3(v) => v.len(),
+			Self::Short1(v) => v.len(),
+			Self::Short2(v) => v.len(),
+			Self::Short3(v) => v.len(),
+			Self::UShort1(v) => v.len(),
+			Self::UShort2(v) => v.len(),
+			Self::UShort3(v) => v.len(),
+			Self::Int1(v) => v.len(),
+			Self::Int2(v) => v.len(),
+			Self::Int3(v) => v.len(),
+			Self::UInt1(v) => v.len(),
+			Self::UInt2(v) => v.len(),
+			Self::UInt3(v) => v.len(),
+			Self::Bool1(v) => v.len(),
+			Self::Bool2(v) => v.len(),
+			Self::Bool3(v) => v.len(),
+			Self::Color(v) => v.len(),
+			Self::Label(v) => v.len(),
+		}
+	}
+	
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+	
+	/// Value at the frame interpolated between the surrounding keyframes, None if there are no keyframes.
+	/// Bools are 0 or 1, colors are the multiply rgb followed by the add rgb and labels are the label id, command and jump id.
+	/// Bools and labels are never interpolated, before the first and after the last keyframe the value is held
+	pub fn sample(&self, frame: f32) -> Option<Vec<f32>> {
+		match self {
+			Self::Float1(v) => sample(v, frame, false),
+			Self::Float2(v) => sample(v, frame, false),
+			Self::Float3(v) => sample(v, frame, false),
+			Self::SByte1(v) => sample(v, frame, false),
+			Self::SByte2(v) => sample(v, frame, false),
+			Self::SByte3(v) => sample(v, frame, false),
+			Self::Byte1(v) => sample(v, frame, false),
+			Self::Byte2(v) => sample(v, frame, false),
+			Self::Byte3(v) => sample(v, frame, false),
+			Self::Short1(v) => sample(v, frame, false),
+			Self::Short2(v) => sample(v, frame, false),
+			Self::Short3(v) => sample(v, frame, false),
+			Self::UShort1(v) => sample(v, frame, false),
+			Self::UShort2(v) => sample(v, frame, false),
+			Self::UShort3(v) => sample(v, frame, false),
+			Self::Int1(v) => sample(v, frame, false),
+			Self::Int2(v) => sample(v, frame, false),
+			Self::Int3(v) => sample(v, frame, false),
+			Self::UInt1(v) => sample(v, frame, false),
+			Self::UInt2(v) => sample(v, frame, false),
+			Self::UInt3(v) => sample(v, frame, false),
+			Self::Bool1(v) => sample(v, frame, true),
+			Self::Bool2(v) => sample(v, frame, true),
+			Self::Bool3(v) => sample(v, frame, true),
+			Self::Color(v) => sample(v, frame, false),
+			Self::Label(v) => sample(v, frame, true),
+		}
+	}
+}
+
+fn sample<T: Keyframe>(keyframes: &[T], frame: f32, step: bool) -> Option<Vec<f32>> {
+	let first = keyframes.first()?;
+	let next = keyframes.iter().position(|v| v.base().time as f32 > frame);
+	let (from, to) = match next {
+		Some(0) => return Some(first.values()),
+		Some(i) => (&keyframes[i - 1], &keyframes[i]),
+		None => return Some(keyframes.last()?.values()),
+	};
+	
+	if step {return Some(from.values())}
+	
+	let base = from.base();
+	let duration = (to.base().time - base.time) as f32;
+	let t = if duration > 0.0 {(frame - base.time as f32) / duration} else {1.0};
+	let t = base.interpolation().apply(t);
+	Some(from.values().into_iter().zip(to.values()).map(|(a, b)| a + (b - a) * t).collect())
+}
+
+// ---------------------------------------- //
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+	/// Holds the value until the next keyframe
+	None,
+	Linear,
+	EaseOut,
+	EaseIn,
+	Unknown(u8),
+}
+
+impl Interpolation {
+	/// Eases t (0-1) between two keyframes
+	pub fn apply(&self, t: f32) -> f32 {
+		let t = t.clamp(0.0, 1.0);
+		match self {
+			Interpolation::None => 0.0,
+			Interpolation::Linear | Interpolation::Unknown(_) => t,
+			Interpolation::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+			Interpolation::EaseIn => t * t,
+		}
+	}
+}
+
+impl From<u8> for Interpolation {
+	fn from(value: u8) -> Self {
+		match value {
+			0 => Interpolation::None,
+			1 => Interpolation::Linear,
+			2 => Interpolation::EaseOut,
+			3 => Interpolation::EaseIn,
+			v => Interpolation::Unknown(v),
+		}
+	}
+}
+
+impl BaseKeyframeData {
+	pub fn interpolation(&self) -> Interpolation {
+		self.interpolation.into()
+	}
+}
+
+// common access to the keyframe structs for sampling
+trait Keyframe {
+	fn base(&self) -> &BaseKeyframeData;
+	fn values(&self) -> Vec<f32>;
+}
+
+macro_rules! keyframe {
+	($typ:ty, $v:ident => $values:expr) => {
+		impl Keyframe for $typ {
+			fn base(&self) -> &BaseKeyframeData {
+				&self.keyframe
+			}
+			
+			fn values(&self) -> Vec<f32> {
+				let $v = self;
+				$values
+			}
+		}
+	};
+}
+
+keyframe!(Float1Keyframe, v => vec![v.value]);
+keyframe!(Float2Keyframe, v => v.value.to_vec());
+keyframe!(Float3Keyframe, v => v.value.to_vec());
+keyframe!(SByte1Keyframe, v => vec![v.value as f32]);
+keyframe!(SByte2Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(SByte3Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(Byte1Keyframe, v => vec![v.value as f32]);
+keyframe!(Byte2Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(Byte3Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(Short1Keyframe, v => vec![v.value as f32]);
+keyframe!(Short2Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(Short3Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(UShort1Keyframe, v => vec![v.value as f32]);
+keyframe!(UShort2Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(UShort3Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(Int1Keyframe, v => vec![v.value as f32]);
+keyframe!(Int2Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(Int3Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(UInt1Keyframe, v => vec![v.value as f32]);
+keyframe!(UInt2Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(UInt3Keyframe, v => v.value.map(|v| v as f32).to_vec());
+keyframe!(Bool1Keyframe, v => vec![v.value as u8 as f32]);
+keyframe!(Bool2Keyframe, v => v.value.map(|v| v as u8 as f32).to_vec());
+keyframe!(Bool3Keyframe, v => v.value.map(|v| v as u8 as f32).to_vec());
+keyframe!(ColorKeyframe, v => vec![v.multiply_red as f32, v.multiply_green as f32, v.multiply_blue as f32, v.add_red as f32, v.add_green as f32, v.add_blue as f32]);
+keyframe!(LabelKeyframe, v => vec![v.label_id as f32, v.label_command as f32, v.jump_id as f32]);
+
+// ---------------------------------------- //
+
+/// Animatable values of a node at a point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeValues {
+	pub x: f32,
+	pub y: f32,
+	pub rotation: f32,
+	pub scale_x: f32,
+	pub scale_y: f32,
+	/// 0-255
+	pub alpha: f32,
+	/// Percentages, 100 leaves the color as is
+	pub multiply: [f32; 3],
+	pub add: [f32; 3],
+	/// Only set if the timeline animates them, the text node colors are used otherwise
+	pub text_color: Option<[f32; 3]>,
+	pub edge_color: Option<[f32; 3]>,
+	pub number: Option<f32>,
+}
+
+impl NodeValues {
+	/// The values of the node without any animation applied
+	pub fn new(node: &super::NodeData) -> Self {
+		Self {
+			x: node.x as f32,
+			y: node.y as f32,
+			rotation: node.rotation,
+			scale_x: node.scale_x,
+			scale_y: node.scale_y,
+			alpha: node.alpha as f32,
+			multiply: [node.multiply_red as f32, node.multiply_green as f32, node.multiply_blue as f32],
+			add: [node.add_red as f32, node.add_green as f32, node.add_blue as f32],
+			text_color: None,
+			edge_color: None,
+			number: None,
+		}
+	}
+	
+	/// Overrides the values animated by the keygroup with its value at the frame
+	pub fn apply(&mut self, keygroup: &KeyGroup, frame: f32) {
+		let Some(v) = keygroup.sample(frame) else {return};
+		let get = |i: usize| v.get(i).copied().unwrap_or(0.0);
+		match keygroup.usage {
+			KeyUsage::Position => {
+				self.x = get(0);
+				self.y = get(1);
+			}
+			
+			KeyUsage::Rotation => self.rotation = get(0),
+			
+			KeyUsage::Scale => {
+				self.scale_x = get(0);
+				self.scale_y = get(1);
+			}
+			
+			KeyUsage::Alpha => self.alpha = get(0),
+			
+			KeyUsage::NodeColor => {
+				self.multiply = [get(0), get(1), get(2)];
+				self.add = [get(3), get(4), get(5)];
+			}
+			
+			KeyUsage::TextColor => self.text_color = Some([get(0), get(1), get(2)]),
+			KeyUsage::EdgeColor => self.edge_color = Some([get(0), get(1), get(2)]),
+			KeyUsage::Number => self.number = Some(get(0)),
+		}
+	}
+}
+
+impl super::Uld {
+	pub fn node_timeline(&self, node: &super::NodeData) -> Option<&super::UldTimeline> {
+		if node.timeline_id == 0 {return None}
+		self.timelines.iter().find(|v| v.id == node.timeline_id as u32)
+	}
+	
+	/// Values of the node at the frame, the first frame data of its timeline containing the frame is used
+	pub fn node_values(&self, node: &super::NodeData, frame: f32) -> NodeValues {
+		let mut values = NodeValues::new(node);
+		let Some(timeline) = self.node_timeline(node) else {return values};
+		let Some(frames) = timeline.frames1.iter().chain(&timeline.frames2).find(|v| v.contains(frame)) else {return values};
+		for keygroup in &frames.keygroups {
+			values.apply(keygroup, frame);
+		}
+		
+		values
+	}
 }
 
 impl Default for Keyframes {
@@ -321,7 +602,7 @@ impl BinWrite for Keyframes {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct BaseKeyframeData {
 	pub time: u32,
 	pub offset: u16,
@@ -333,7 +614,7 @@ pub struct BaseKeyframeData {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Float1Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: f32,
@@ -341,7 +622,7 @@ pub struct Float1Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Float2Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [f32; 2],
@@ -349,7 +630,7 @@ pub struct Float2Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Float3Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [f32; 3],
@@ -357,7 +638,7 @@ pub struct Float3Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SByte1Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: i8,
@@ -366,7 +647,7 @@ pub struct SByte1Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SByte2Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [i8; 2],
@@ -374,7 +655,7 @@ pub struct SByte2Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SByte3Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [i8; 3],
@@ -383,7 +664,7 @@ pub struct SByte3Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Byte1Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: u8,
@@ -392,7 +673,7 @@ pub struct Byte1Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Byte2Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [u8; 2],
@@ -401,7 +682,7 @@ pub struct Byte2Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Byte3Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [u8; 3],
@@ -410,7 +691,7 @@ pub struct Byte3Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Short1Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: i16,
@@ -419,7 +700,7 @@ pub struct Short1Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Short2Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [i16; 2],
@@ -427,7 +708,7 @@ pub struct Short2Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Short3Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [i16; 3],
@@ -436,7 +717,7 @@ pub struct Short3Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct UShort1Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: u16,
@@ -445,7 +726,7 @@ pub struct UShort1Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct UShort2Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [u16; 2],
@@ -453,7 +734,7 @@ pub struct UShort2Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct UShort3Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [u16; 3],
@@ -462,7 +743,7 @@ pub struct UShort3Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Int1Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: i32,
@@ -470,7 +751,7 @@ pub struct Int1Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Int2Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [i32; 2],
@@ -478,7 +759,7 @@ pub struct Int2Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Int3Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [i32; 3],
@@ -486,7 +767,7 @@ pub struct Int3Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct UInt1Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: u32,
@@ -494,7 +775,7 @@ pub struct UInt1Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct UInt2Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [u32; 2],
@@ -502,7 +783,7 @@ pub struct UInt2Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct UInt3Keyframe {
 	pub keyframe: BaseKeyframeData,
 	pub value: [u32; 3],
@@ -510,7 +791,7 @@ pub struct UInt3Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Bool1Keyframe {
 	pub keyframe: BaseKeyframeData,
 	#[br(map = |v: u8| v != 0)]
@@ -521,7 +802,7 @@ pub struct Bool1Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Bool2Keyframe {
 	pub keyframe: BaseKeyframeData,
 	#[br(map = |v: [u8; 2]| [v[0] != 0, v[1] != 0])]
@@ -532,7 +813,7 @@ pub struct Bool2Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Bool3Keyframe {
 	pub keyframe: BaseKeyframeData,
 	#[br(map = |v: [u8; 3]| [v[0] != 0, v[1] != 0, v[2] != 0])]
@@ -543,7 +824,7 @@ pub struct Bool3Keyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ColorKeyframe {
 	pub keyframe: BaseKeyframeData,
 	pub multiply_red: i16,
@@ -556,7 +837,7 @@ pub struct ColorKeyframe {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct LabelKeyframe {
 	pub keyframe: BaseKeyframeData,
 	pub label_id: u16,
@@ -7,5 +7,6 @@ use crate::havok::transform::HavokTransform;
 
 pub trait HavokAnimation {
     fn duration(&self) -> f32;
+    fn frame_count(&self) -> usize;
     fn sample(&self, time: f32) -> Vec<HavokTransform>;
 }
@@ -64,6 +64,7 @@ impl<'a> HavokBinaryTagFileReader<'a> {
         let remembered_strings = vec![Arc::from("string"), Arc::from("")];
         let remembered_types = vec![Arc::new(HavokObjectType::new(
             Arc::from("object"),
+            0,
             None,
             Vec::new(),
         ))];
@@ -122,7 +123,10 @@ impl<'a> HavokBinaryTagFileReader<'a> {
             self.fill_object_reference(&mut object.borrow_mut());
         }
 
-        HavokRootObject::new(self.remembered_objects[1].clone())
+        HavokRootObject::new(
+            self.remembered_objects[1].clone(),
+            self.remembered_types[1..].to_vec(),
+        )
     }
 
     fn read_object(&mut self) -> HavokObject {
@@ -256,7 +260,8 @@ impl<'a> HavokBinaryTagFileReader<'a> {
                 .collect::<Vec<_>>(),
             HavokValueType::INT => {
                 if self.file_version >= 3 {
-                    self.read_packed_int(); // type?
+                    let width = self.read_packed_int(); // bytes per item
+                    member.set_int_array_width(width);
                 }
                 (0..array_len)
                     .map(|_| HavokValue::Integer(self.read_packed_int()))
@@ -290,7 +295,7 @@ impl<'a> HavokBinaryTagFileReader<'a> {
 
     fn read_type(&mut self) -> HavokObjectType {
         let name = self.read_string();
-        let _version = self.read_packed_int();
+        let version = self.read_packed_int();
         let parent = self.read_packed_int();
         let member_count = self.read_packed_int();
 
@@ -317,7 +322,7 @@ impl<'a> HavokBinaryTagFileReader<'a> {
             })
             .collect::<Vec<_>>();
 
-        HavokObjectType::new(name, Some(parent), members)
+        HavokObjectType::new(name, version, Some(parent), members)
     }
 
     fn read_string(&mut self) -> Arc<str> {
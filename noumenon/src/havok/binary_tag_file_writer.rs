@@ -0,0 +1,444 @@
+// SPDX-License-Identifier: MIT
+
+use crate::havok::object::{
+    HavokInteger, HavokObject, HavokObjectType, HavokObjectTypeMember, HavokRootObject, HavokValue,
+    HavokValueType,
+};
+use core::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const FILE_VERSION: HavokInteger = 3;
+
+// the inverse of HavokBinaryTagFileReader, members that hold their default value are left out
+pub struct HavokBinaryTagFileWriter {
+    remembered_strings: HashMap<Arc<str>, usize>,
+    remembered_string_count: usize,
+    remembered_types: Vec<Arc<HavokObjectType>>,
+    remembered_objects: HashMap<*const RefCell<HavokObject>, usize>,
+    output: Vec<u8>,
+}
+
+impl HavokBinaryTagFileWriter {
+    pub fn write(root: &HavokRootObject) -> binrw::BinResult<Vec<u8>> {
+        let mut writer = Self::new();
+
+        writer.do_write(root)?;
+        Ok(writer.output)
+    }
+
+    fn new() -> Self {
+        // the reader starts out with these, "string" can't be referenced as its index is 0
+        let remembered_strings = HashMap::from([(Arc::from(""), 1)]);
+
+        Self {
+            remembered_strings,
+            remembered_string_count: 2,
+            remembered_types: Vec::new(),
+            remembered_objects: HashMap::new(),
+            output: Vec::new(),
+        }
+    }
+
+    fn do_write(&mut self, root: &HavokRootObject) -> binrw::BinResult<()> {
+        self.output.extend_from_slice(&0xCAB0_0D1Eu32.to_le_bytes());
+        self.output.extend_from_slice(&0xD011_FACEu32.to_le_bytes());
+
+        self.write_packed_int(1); // file info
+        self.write_packed_int(FILE_VERSION);
+
+        for object_type in root.types() {
+            self.write_type(object_type);
+        }
+
+        // index 0 is the null object, the root has to come first
+        let mut objects = Vec::new();
+        self.collect_objects(&root.object(), &mut objects);
+
+        for object in objects {
+            let object = object.borrow();
+            self.write_type(&object.object_type);
+
+            self.write_packed_int(4); // object remember
+            let type_index = self.type_index(&object.object_type);
+            self.write_packed_int(type_index as HavokInteger);
+            self.write_object_members(&object)?;
+        }
+
+        self.write_packed_int(7); // file end
+
+        Ok(())
+    }
+
+    fn collect_objects(
+        &mut self,
+        object: &Arc<RefCell<HavokObject>>,
+        objects: &mut Vec<Arc<RefCell<HavokObject>>>,
+    ) {
+        if Self::is_null(object) || self.remembered_objects.contains_key(&Arc::as_ptr(object)) {
+            return;
+        }
+
+        self.remembered_objects
+            .insert(Arc::as_ptr(object), objects.len() + 1);
+        objects.push(object.clone());
+        self.collect_references(object, objects);
+    }
+
+    // structs are written inline, so also look inside of them for references
+    fn collect_references(
+        &mut self,
+        object: &Arc<RefCell<HavokObject>>,
+        objects: &mut Vec<Arc<RefCell<HavokObject>>>,
+    ) {
+        let object_type = object.borrow().object_type.clone();
+        for (index, member) in object_type.members().into_iter().enumerate() {
+            let base_type = member.type_.base_type();
+            if base_type != HavokValueType::OBJECT && base_type != HavokValueType::STRUCT {
+                continue;
+            }
+
+            let values = match object.borrow().get_by_index(index) {
+                Some(HavokValue::Object(x)) => vec![x.clone()],
+                Some(HavokValue::Array(x)) => x
+                    .iter()
+                    .filter_map(|x| match x {
+                        HavokValue::Object(x) => Some(x.clone()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>(),
+                _ => Vec::new(),
+            };
+
+            for value in values {
+                if base_type == HavokValueType::OBJECT {
+                    self.collect_objects(&value, objects);
+                } else {
+                    self.collect_references(&value, objects);
+                }
+            }
+        }
+    }
+
+    fn write_type(&mut self, object_type: &Arc<HavokObjectType>) {
+        if object_type.parent().is_none() || self.type_index(object_type) != 0 {
+            return;
+        }
+
+        let parent = object_type.parent().unwrap();
+        self.write_type(parent);
+
+        self.write_packed_int(2); // type
+        self.write_string(&object_type.name);
+        self.write_packed_int(object_type.version);
+        let parent_index = self.type_index(parent);
+        self.write_packed_int(parent_index as HavokInteger);
+        self.write_packed_int(object_type.own_members().len() as HavokInteger);
+        for member in object_type.own_members() {
+            self.write_string(&member.name);
+            self.write_packed_int(member.type_.bits() as HavokInteger);
+            if member.type_.is_tuple() {
+                self.write_packed_int(member.tuple_size as HavokInteger);
+            }
+            if member.type_.base_type() == HavokValueType::OBJECT
+                || member.type_.base_type() == HavokValueType::STRUCT
+            {
+                self.write_string(member.class_name.as_deref().unwrap_or(""));
+            }
+        }
+
+        self.remembered_types.push(object_type.clone());
+    }
+
+    // 0 is the builtin object type, which is also what anything unknown ends up as
+    fn type_index(&self, object_type: &Arc<HavokObjectType>) -> usize {
+        self.remembered_types
+            .iter()
+            .position(|x| Arc::ptr_eq(x, object_type))
+            .map_or(0, |x| x + 1)
+    }
+
+    fn find_type(&self, type_name: &str) -> binrw::BinResult<Arc<HavokObjectType>> {
+        self.remembered_types
+            .iter()
+            .find(|&x| &*x.name == type_name)
+            .cloned()
+            .ok_or_else(|| self.error(format!("Unknown type {type_name}")))
+    }
+
+    fn error(&self, message: String) -> binrw::Error {
+        binrw::Error::AssertFail {
+            pos: self.output.len() as u64,
+            message,
+        }
+    }
+
+    fn write_object_members(&mut self, object: &HavokObject) -> binrw::BinResult<()> {
+        let members = object.object_type.members();
+        let values = (0..members.len())
+            .map(|index| object.get_by_index(index))
+            .collect::<Vec<_>>();
+
+        self.write_struct_members(&members, &[values], false)
+    }
+
+    // writes the existence bit field followed by the members, for arrays of structs each member is written as an array
+    fn write_struct_members(
+        &mut self,
+        members: &[&HavokObjectTypeMember],
+        items: &[Vec<Option<&HavokValue>>],
+        as_array: bool,
+    ) -> binrw::BinResult<()> {
+        let existence = (0..members.len())
+            .map(|index| {
+                items
+                    .iter()
+                    .any(|x| !Self::is_default(members[index], x[index]))
+            })
+            .collect::<Vec<_>>();
+        self.write_bit_field(&existence);
+
+        for (index, member) in members.iter().enumerate() {
+            if !existence[index] {
+                continue;
+            }
+
+            if as_array {
+                let column = items.iter().map(|x| x[index]).collect::<Vec<_>>();
+                self.write_array(member, &column)?;
+            } else {
+                self.write_object_member_value(member, items[0][index])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_object_member_value(
+        &mut self,
+        member: &HavokObjectTypeMember,
+        value: Option<&HavokValue>,
+    ) -> binrw::BinResult<()> {
+        if member.type_.is_array() || member.type_.is_tuple() {
+            let items = match value {
+                Some(HavokValue::Array(x)) => x.iter().map(Some).collect::<Vec<_>>(),
+                _ => Vec::new(),
+            };
+
+            if member.type_.is_array() {
+                self.write_packed_int(items.len() as HavokInteger);
+                return self.write_array(member, &items);
+            }
+
+            let mut items = items;
+            items.resize(member.tuple_size as usize, None);
+            return self.write_array(member, &items);
+        }
+
+        match member.type_.base_type() {
+            HavokValueType::STRUCT => {
+                let object = match value {
+                    Some(HavokValue::Object(x)) => Some(x.clone()),
+                    _ => None,
+                };
+                let target_type = match &object {
+                    Some(x) => x.borrow().object_type.clone(),
+                    None => self.find_type(member.class_name.as_deref().unwrap_or(""))?,
+                };
+
+                let object = object.as_ref().map(|x| x.borrow());
+                let members = target_type.members();
+                let values = (0..members.len())
+                    .map(|index| object.as_ref().and_then(|x| x.get_by_index(index)))
+                    .collect::<Vec<_>>();
+
+                self.write_struct_members(&members[..target_type.member_count()], &[values], false)
+            }
+            _ => self.write_value(member, value),
+        }
+    }
+
+    fn write_array(
+        &mut self,
+        member: &HavokObjectTypeMember,
+        items: &[Option<&HavokValue>],
+    ) -> binrw::BinResult<()> {
+        match member.type_.base_type() {
+            HavokValueType::STRUCT => {
+                let target_type = self.find_type(member.class_name.as_deref().unwrap_or(""))?;
+                let members = target_type.members();
+
+                let objects = items
+                    .iter()
+                    .map(|x| match x {
+                        Some(HavokValue::Object(x)) => Some(x.borrow()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                let values = objects
+                    .iter()
+                    .map(|object| {
+                        (0..members.len())
+                            .map(|index| object.as_ref().and_then(|x| x.get_by_index(index)))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+
+                self.write_struct_members(&members[..target_type.member_count()], &values, true)
+            }
+            HavokValueType::INT => {
+                // bytes per item, whatever the reader saw so hkInt16 arrays stay hkInt16
+                if FILE_VERSION >= 3 {
+                    self.write_packed_int(member.int_array_width());
+                }
+
+                items
+                    .iter()
+                    .try_for_each(|item| self.write_value(member, *item))
+            }
+            _ => items
+                .iter()
+                .try_for_each(|item| self.write_value(member, *item)),
+        }
+    }
+
+    fn write_value(
+        &mut self,
+        member: &HavokObjectTypeMember,
+        value: Option<&HavokValue>,
+    ) -> binrw::BinResult<()> {
+        let mismatch = |writer: &Self| {
+            writer.error(format!(
+                "Member {} of type {} holds a mismatched value",
+                member.name,
+                member.type_.bits()
+            ))
+        };
+
+        match member.type_.base_type() {
+            HavokValueType::BYTE => {
+                let value = match value {
+                    Some(HavokValue::Integer(x)) => *x,
+                    None => 0,
+                    _ => return Err(mismatch(self)),
+                };
+                self.output.push(value as u8);
+            }
+            HavokValueType::INT => {
+                let value = match value {
+                    Some(HavokValue::Integer(x)) => *x,
+                    None => 0,
+                    _ => return Err(mismatch(self)),
+                };
+                self.write_packed_int(value);
+            }
+            HavokValueType::REAL => {
+                let value = match value {
+                    Some(HavokValue::Real(x)) => *x,
+                    None => 0.0,
+                    _ => return Err(mismatch(self)),
+                };
+                self.output.extend_from_slice(&value.to_le_bytes());
+            }
+            HavokValueType::STRING => {
+                let value = match value {
+                    Some(HavokValue::String(x)) => &**x,
+                    None => "",
+                    _ => return Err(mismatch(self)),
+                };
+                self.write_string(value);
+            }
+            HavokValueType::OBJECT => {
+                let index = match value {
+                    Some(HavokValue::Object(x)) => self
+                        .remembered_objects
+                        .get(&Arc::as_ptr(x))
+                        .copied()
+                        .unwrap_or(0),
+                    _ => 0,
+                };
+                self.write_packed_int(index as HavokInteger);
+            }
+            HavokValueType::VEC4
+            | HavokValueType::VEC8
+            | HavokValueType::VEC12
+            | HavokValueType::VEC16 => {
+                let vec_size = member.type_.base_type().vec_size() as usize;
+                for i in 0..vec_size {
+                    let value = match value {
+                        Some(HavokValue::Vec(x)) => x.get(i).copied().unwrap_or(0.0),
+                        _ => 0.0,
+                    };
+                    self.output.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            _ => {
+                return Err(self.error(format!(
+                    "Member {} has the unsupported type {}",
+                    member.name,
+                    member.type_.bits()
+                )))
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_string(&mut self, value: &str) {
+        if let Some(&index) = self.remembered_strings.get(value) {
+            self.write_packed_int(-(index as HavokInteger));
+            return;
+        }
+
+        self.write_packed_int(value.len() as HavokInteger);
+        self.output.extend_from_slice(value.as_bytes());
+        self.remembered_strings
+            .insert(Arc::from(value), self.remembered_string_count);
+        self.remembered_string_count += 1;
+    }
+
+    fn write_bit_field(&mut self, bits: &[bool]) {
+        for chunk in bits.chunks(8) {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, &bit)| byte | ((bit as u8) << i));
+            self.output.push(byte);
+        }
+    }
+
+    fn write_packed_int(&mut self, value: HavokInteger) {
+        let neg = value < 0;
+        let mut value = value.unsigned_abs();
+
+        let mut byte = (((value & 0x3f) << 1) as u8) | neg as u8;
+        value >>= 6;
+        while value != 0 {
+            self.output.push(byte | 0x80);
+            byte = (value & 0x7f) as u8;
+            value >>= 7;
+        }
+        self.output.push(byte);
+    }
+
+    fn is_null(object: &Arc<RefCell<HavokObject>>) -> bool {
+        let object = object.borrow();
+        object.object_type.parent().is_none()
+    }
+
+    // what the reader fills in for members that aren't in the file
+    fn is_default(member: &HavokObjectTypeMember, value: Option<&HavokValue>) -> bool {
+        match value {
+            None => true,
+            Some(HavokValue::Array(x)) => {
+                (member.type_.is_array() || member.type_.is_tuple()) && x.is_empty()
+            }
+            Some(HavokValue::Integer(x)) => *x == 0,
+            Some(HavokValue::Object(x)) => {
+                member.type_.base_type() == HavokValueType::OBJECT && Self::is_null(x)
+            }
+            Some(HavokValue::ObjectReference(x)) => *x == 0,
+            _ => false,
+        }
+    }
+}
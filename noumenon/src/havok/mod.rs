@@ -7,6 +7,7 @@ mod animation;
 mod animation_binding;
 mod animation_container;
 mod binary_tag_file_reader;
+mod binary_tag_file_writer;
 mod byte_reader;
 mod object;
 mod skeleton;
@@ -17,3 +18,5 @@ mod transform;
 pub use animation::HavokAnimation;
 pub use animation_container::HavokAnimationContainer;
 pub use binary_tag_file_reader::HavokBinaryTagFileReader;
+pub use binary_tag_file_writer::HavokBinaryTagFileWriter;
+pub use object::{HavokObject, HavokValue};
@@ -4,7 +4,7 @@
 #![allow(clippy::bad_bit_mask)]
 #![allow(dead_code)]
 
-use core::cell::RefCell;
+use core::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -145,11 +145,25 @@ impl HavokValue {
 
 pub struct HavokRootObject {
     object: Arc<RefCell<HavokObject>>,
+    types: Vec<Arc<HavokObjectType>>,
 }
 
 impl HavokRootObject {
-    pub fn new(object: Arc<RefCell<HavokObject>>) -> Self {
-        Self { object }
+    pub fn new(object: Arc<RefCell<HavokObject>>, types: Vec<Arc<HavokObjectType>>) -> Self {
+        Self { object, types }
+    }
+
+    pub fn object(&self) -> Arc<RefCell<HavokObject>> {
+        self.object.clone()
+    }
+
+    /// All types the file defined, including the ones no object uses
+    pub fn types(&self) -> &[Arc<HavokObjectType>] {
+        &self.types
+    }
+
+    pub fn find_type(&self, type_name: &str) -> Option<Arc<HavokObjectType>> {
+        self.types.iter().find(|x| &*x.name == type_name).cloned()
     }
 
     pub fn find_object_by_type(&self, type_name: &'static str) -> Arc<RefCell<HavokObject>> {
@@ -171,6 +185,8 @@ pub struct HavokObjectTypeMember {
     pub type_: HavokValueType,
     pub tuple_size: u32,
     pub class_name: Option<Arc<str>>,
+    // bytes per item of int arrays (hkInt16, hkInt32, ...), the tag file only stores it in front of the items
+    int_array_width: Cell<HavokInteger>,
 }
 
 impl HavokObjectTypeMember {
@@ -185,12 +201,22 @@ impl HavokObjectTypeMember {
             type_,
             tuple_size,
             class_name: type_name,
+            int_array_width: Cell::new(4),
         }
     }
+
+    pub fn int_array_width(&self) -> HavokInteger {
+        self.int_array_width.get()
+    }
+
+    pub(crate) fn set_int_array_width(&self, width: HavokInteger) {
+        self.int_array_width.set(width);
+    }
 }
 
 pub struct HavokObjectType {
     pub name: Arc<str>,
+    pub version: HavokInteger,
     parent: Option<Arc<HavokObjectType>>,
     members: Vec<HavokObjectTypeMember>,
 }
@@ -198,16 +224,27 @@ pub struct HavokObjectType {
 impl HavokObjectType {
     pub fn new(
         name: Arc<str>,
+        version: HavokInteger,
         parent: Option<Arc<HavokObjectType>>,
         members: Vec<HavokObjectTypeMember>,
     ) -> Self {
         Self {
             name,
+            version,
             parent,
             members,
         }
     }
 
+    pub fn parent(&self) -> Option<&Arc<HavokObjectType>> {
+        self.parent.as_ref()
+    }
+
+    /// Members declared by this type itself, without the ones of the parent
+    pub fn own_members(&self) -> &[HavokObjectTypeMember] {
+        &self.members
+    }
+
     pub fn members(&self) -> Vec<&HavokObjectTypeMember> {
         if let Some(x) = &self.parent {
             x.members()
@@ -243,14 +280,24 @@ impl HavokObject {
     }
 
     pub fn get(&self, member_name: &str) -> &HavokValue {
-        let member_index = self
-            .object_type
+        self.data.get(&self.member_index(member_name)).unwrap()
+    }
+
+    pub fn set_by_name(&mut self, member_name: &str, value: HavokValue) {
+        self.data.insert(self.member_index(member_name), value);
+    }
+
+    /// None if the member wasn't present in the file
+    pub fn get_by_index(&self, index: usize) -> Option<&HavokValue> {
+        self.data.get(&index)
+    }
+
+    fn member_index(&self, member_name: &str) -> usize {
+        self.object_type
             .members()
             .iter()
             .position(|&x| &*x.name == member_name)
-            .unwrap();
-
-        self.data.get(&member_index).unwrap()
+            .unwrap()
     }
 
     pub(crate) fn members_mut(&mut self) -> impl Iterator<Item = (&usize, &mut HavokValue)> {
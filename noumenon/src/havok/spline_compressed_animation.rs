@@ -639,6 +639,10 @@ impl HavokAnimation for HavokSplineCompressedAnimation {
         self.duration
     }
 
+    fn frame_count(&self) -> usize {
+        self.num_frames
+    }
+
     fn sample(&self, time: f32) -> Vec<HavokTransform> {
         let frame_float = ((time / 1000.) / self.duration) * (self.num_frames as f32 - 1.);
         let frame = frame_float as usize;
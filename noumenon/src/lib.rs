@@ -1,5 +1,4 @@
-use std::{io::Cursor, ops::{Deref, DerefMut}, path::{Path, PathBuf}};
-pub use ironworks::file::File;
+use std::{io::{Cursor, Read, Seek}, path::{Path, PathBuf}};
 
 macro_rules! simple_reader {
 	($y:expr, $z:expr) => {
@@ -48,6 +47,13 @@ macro_rules! simple_reader {
 
 pub mod format;
 
+pub mod sqpack;
+
+pub mod diff;
+
+mod sheet;
+pub use sheet::Sheet;
+
 // https://github.com/redstrate/Physis
 // i fucking love you for making this redstrate <3
 mod havok;
@@ -59,13 +65,36 @@ mod havok;
 /// 
 /// Mdl
 /// - mdl
-/// - gltf
+/// - gltf (buffers in seperate files require `from_ext_with_base`)
 /// 
 /// Mtrl
 /// - mtrl
 /// 
+/// Pap
+/// - pap
+/// - gltf (export only)
+/// 
 /// Uld
 /// - uld
+/// - json
+/// 
+/// Phyb
+/// - phyb
+/// - json
+/// 
+/// Atch
+/// - atch
+/// - json
+/// 
+/// Scd
+/// - scd
+/// - ogg (only vorbis sounds, importing requires the path and file reader to replace the sound of the game file)
+/// - wav (only ms-adpcm and pcm sounds, same as ogg)
+/// 
+/// Exd / Exh (requires the path and file reader, the whole sheet is converted for the language of the path)
+/// - exd / exh
+/// - csv
+/// - json
 /// 
 /// Tex
 /// - tex / atex
@@ -73,41 +102,90 @@ mod havok;
 /// - png
 /// - tiff / tif
 /// - tga
+/// - jpg / jpeg
+/// - webp
+/// - bmp
+/// - exr
+/// 
+/// Hwc
+/// - hwc
+/// - png
+/// - tiff / tif
+/// - tga
+/// - jpg / jpeg
+/// - webp
+/// - bmp
 pub enum Convert {
 	Mdl(format::game::Mdl),
 	Mtrl(format::game::Mtrl),
 	Tex(format::game::Tex),
 	Hwc(format::game::Hwc),
 	Uld(format::game::Uld),
+	Pap(format::game::Pap),
+	Scd(format::game::Scd),
+	Phyb(format::game::Phyb),
+	Atch(format::game::Atch),
+	Exd(Vec<u8>),
+	Exh(Vec<u8>),
 	
 	Dds(Vec<u8>),
 	Png(Vec<u8>),
 	Tiff(Vec<u8>),
 	Tga(Vec<u8>),
+	Jpeg(Vec<u8>),
+	Webp(Vec<u8>),
+	Bmp(Vec<u8>),
+	Exr(Vec<u8>),
+	Csv(Vec<u8>),
+	Json(Vec<u8>),
+	Ogg(Vec<u8>),
+	Wav(Vec<u8>),
 }
 
 impl Convert {
 	pub fn from_ext<R>(ext: &str, reader: &mut R) -> Result<Self, Error> where
+	R: std::io::Read + std::io::Seek {
+		Self::from_ext_with_base(ext, reader, None)
+	}
+	
+	/// `base_path` is the directory the file is in, for formats that reference other files next to it
+	pub fn from_ext_with_base<R>(ext: &str, reader: &mut R, base_path: Option<&Path>) -> Result<Self, Error> where
 	R: std::io::Read + std::io::Seek {
 		use format::{game::*, external::*};
 		
 		if mdl::EXT.contains(&ext) {return Ok(Self::Mdl(<Mdl as Bytes>::read(reader)?))}
+		if gltf::EXT.contains(&ext) {return Ok(Self::Mdl(<Mdl as Gltf>::read(reader, base_path)?))}
 		if mtrl::EXT.contains(&ext) {return Ok(Self::Mtrl(<Mtrl as Bytes>::read(reader)?))}
 		if tex::EXT.contains(&ext) {return Ok(Self::Tex(<Tex as Bytes>::read(reader)?))}
 		if hwc::EXT.contains(&ext) {return Ok(Self::Hwc(<Hwc as Bytes>::read(reader)?))}
 		if uld::EXT.contains(&ext) {return Ok(Self::Uld(<Uld as Bytes>::read(reader)?))}
+		if pap::EXT.contains(&ext) {return Ok(Self::Pap(<Pap as Bytes>::read(reader)?))}
+		if scd::EXT.contains(&ext) {return Ok(Self::Scd(<Scd as Bytes>::read(reader)?))}
+		if phyb::EXT.contains(&ext) {return Ok(Self::Phyb(<Phyb as Bytes>::read(reader)?))}
+		if atch::EXT.contains(&ext) {return Ok(Self::Atch(<Atch as Bytes>::read(reader)?))}
 		
 		let mut data = Vec::new();
 		reader.read_to_end(&mut data)?;
+		if exd::EXT.contains(&ext) {return Ok(Self::Exd(data))}
+		if exh::EXT.contains(&ext) {return Ok(Self::Exh(data))}
 		if dds::EXT.contains(&ext) {return Ok(Self::Dds(data))}
 		if png::EXT.contains(&ext) {return Ok(Self::Png(data))}
 		if tiff::EXT.contains(&ext) {return Ok(Self::Tiff(data))}
 		if tga::EXT.contains(&ext) {return Ok(Self::Tga(data))}
+		if jpeg::EXT.contains(&ext) {return Ok(Self::Jpeg(data))}
+		if webp::EXT.contains(&ext) {return Ok(Self::Webp(data))}
+		if bmp::EXT.contains(&ext) {return Ok(Self::Bmp(data))}
+		if exr::EXT.contains(&ext) {return Ok(Self::Exr(data))}
+		if csv::EXT.contains(&ext) {return Ok(Self::Csv(data))}
+		if json::EXT.contains(&ext) {return Ok(Self::Json(data))}
+		if ogg::EXT.contains(&ext) {return Ok(Self::Ogg(data))}
+		if wav::EXT.contains(&ext) {return Ok(Self::Wav(data))}
 		
 		Err(Error::InvalidFormatFrom(ext.to_string()))
 	}
 	
-	pub fn convert<W>(&self, ext: &str, writer: &mut W, file_path: Option<&str>, file_reader: Option<impl Fn(&str) -> Option<Vec<u8>>>) -> Result<(), Error> where
+	/// `tex_options` is used when writing tex and dds files
+	pub fn convert<W>(&self, ext: &str, writer: &mut W, file_path: Option<&str>, file_reader: Option<impl Fn(&str) -> Option<Vec<u8>>>, tex_options: &format::game::tex::EncodeOptions) -> Result<(), Error> where
 	W: std::io::Write + std::io::Seek {
 		use format::{game::*, external::*};
 		
@@ -123,16 +201,7 @@ impl Convert {
 						.flat_map(|v| {
 							let sklb_data = file_reader(&v).unwrap();
 							let sklb = <Sklb as Bytes>::read(&mut std::io::Cursor::new(sklb_data)).unwrap();
-							
-							sklb.bones
-								.iter()
-								.map(|bone| gltf::Bone {
-									name: bone.name.clone(),
-									parent: if bone.parent >= 0 {Some(sklb.bones[bone.parent as usize].name.clone())} else {None},
-									translation: bone.translation,
-									rotation: bone.rotation,
-									scale: bone.scale,
-								}).collect::<Vec<_>>()
+							sklb.gltf_bones()
 						}).collect::<Vec<_>>();
 					let materials = v.bake_materials(file_reader);
 					
@@ -149,11 +218,15 @@ impl Convert {
 			}
 			
 			Convert::Tex(v) => {
-				if tex::EXT.contains(&ext) {return Ok(<Tex as Bytes>::write(v, writer)?)}
-				if dds::EXT.contains(&ext) {return Ok(<Tex as Dds>::write(v, writer)?)}
+				if tex::EXT.contains(&ext) {return Ok(v.write_tex(writer, tex_options)?)}
+				if dds::EXT.contains(&ext) {return Ok(v.write_dds(writer, tex_options)?)}
 				if png::EXT.contains(&ext) {return Ok(<Tex as Png>::write(v, writer)?)}
 				if tiff::EXT.contains(&ext) {return Ok(<Tex as Tiff>::write(v, writer)?)}
 				if tga::EXT.contains(&ext) {return Ok(<Tex as Tga>::write(v, writer)?)}
+				if jpeg::EXT.contains(&ext) {return Ok(<Tex as Jpeg>::write(v, writer)?)}
+				if webp::EXT.contains(&ext) {return Ok(<Tex as Webp>::write(v, writer)?)}
+				if bmp::EXT.contains(&ext) {return Ok(<Tex as Bmp>::write(v, writer)?)}
+				if exr::EXT.contains(&ext) {return Ok(<Tex as Exr>::write(v, writer)?)}
 				
 				Err(Error::InvalidFormatTo(ext.to_string()))
 			}
@@ -163,42 +236,194 @@ impl Convert {
 				if png::EXT.contains(&ext) {return Ok(<Hwc as Png>::write(v, writer)?)}
 				if tiff::EXT.contains(&ext) {return Ok(<Hwc as Tiff>::write(v, writer)?)}
 				if tga::EXT.contains(&ext) {return Ok(<Hwc as Tga>::write(v, writer)?)}
+				if jpeg::EXT.contains(&ext) {return Ok(<Hwc as Jpeg>::write(v, writer)?)}
+				if webp::EXT.contains(&ext) {return Ok(<Hwc as Webp>::write(v, writer)?)}
+				if bmp::EXT.contains(&ext) {return Ok(<Hwc as Bmp>::write(v, writer)?)}
 				
 				Err(Error::InvalidFormatTo(ext.to_string()))
 			}
 			
 			Convert::Uld(v) => {
 				if uld::EXT.contains(&ext) {return Ok(<Uld as Bytes>::write(v, writer)?)}
+				if json::EXT.contains(&ext) {return Ok(<Uld as Json>::write(v, writer)?)}
+				
+				Err(Error::InvalidFormatTo(ext.to_string()))
+			}
+			
+			Convert::Pap(v) => {
+				if pap::EXT.contains(&ext) {return Ok(<Pap as Bytes>::write(v, writer)?)}
+				if gltf::EXT.contains(&ext) {
+					let Some(file_reader) = file_reader else {return Err(Error::ParametersRequires)};
+					let Some(sklb_data) = v.skeleton_path().and_then(|v| file_reader(&v)) else {return Err(Error::InvalidData("Failed to find the skeleton of the animation".to_string()))};
+					let sklb = <Sklb as Bytes>::read(&mut std::io::Cursor::new(sklb_data))?;
+					
+					return Ok(<Pap as Gltf>::write(v, writer, std::collections::HashMap::new(), sklb.gltf_bones())?)
+				}
+				
+				Err(Error::InvalidFormatTo(ext.to_string()))
+			}
+			
+			Convert::Scd(v) => {
+				if scd::EXT.contains(&ext) {return Ok(<Scd as Bytes>::write(v, writer)?)}
+				if ogg::EXT.contains(&ext) {return Ok(<Scd as Ogg>::write(v, writer)?)}
+				if wav::EXT.contains(&ext) {return Ok(<Scd as Wav>::write(v, writer)?)}
+				
+				Err(Error::InvalidFormatTo(ext.to_string()))
+			}
+			
+			Convert::Phyb(v) => {
+				if phyb::EXT.contains(&ext) {return Ok(<Phyb as Bytes>::write(v, writer)?)}
+				if json::EXT.contains(&ext) {return Ok(<Phyb as Json>::write(v, writer)?)}
+				
+				Err(Error::InvalidFormatTo(ext.to_string()))
+			}
+			
+			Convert::Atch(v) => {
+				if atch::EXT.contains(&ext) {return Ok(<Atch as Bytes>::write(v, writer)?)}
+				if json::EXT.contains(&ext) {return Ok(<Atch as Json>::write(v, writer)?)}
+				
+				Err(Error::InvalidFormatTo(ext.to_string()))
+			}
+			
+			Convert::Exd(v) | Convert::Exh(v) => {
+				if (matches!(self, Convert::Exd(_)) && exd::EXT.contains(&ext)) || (matches!(self, Convert::Exh(_)) && exh::EXT.contains(&ext)) {
+					writer.write_all(v)?;
+					return Ok(());
+				}
+				
+				if csv::EXT.contains(&ext) || json::EXT.contains(&ext) {
+					let Some(file_path) = file_path else {return Err(Error::ParametersRequires)};
+					let Some(file_reader) = file_reader else {return Err(Error::ParametersRequires)};
+					let excel = Excel::load_path(file_path, file_reader)?;
+					
+					if csv::EXT.contains(&ext) {return Ok(<Excel as Csv>::write(&excel, writer)?)}
+					return Ok(<Excel as Json>::write(&excel, writer)?)
+				}
 				
 				Err(Error::InvalidFormatTo(ext.to_string()))
 			}
 			
 			Convert::Dds(v) => {
-				if tex::EXT.contains(&ext) {return Ok(<Tex as Bytes>::write(&<Tex as Dds>::read(&mut Cursor::new(v))?, writer)?)}
+				if tex::EXT.contains(&ext) {return Ok(<Tex as Dds>::read(&mut Cursor::new(v))?.write_tex(writer, tex_options)?)}
 				
 				Err(Error::InvalidFormatTo(ext.to_string()))
 			}
 			
 			Convert::Png(v) => {
-				if tex::EXT.contains(&ext) {return Ok(<Tex as Bytes>::write(&<Tex as Png>::read(&mut Cursor::new(v))?, writer)?)}
+				if tex::EXT.contains(&ext) {return Ok(<Tex as Png>::read(&mut Cursor::new(v))?.write_tex(writer, tex_options)?)}
 				if hwc::EXT.contains(&ext) {return Ok(<Hwc as Bytes>::write(&<Hwc as Png>::read(&mut Cursor::new(v))?, writer)?)}
 				
 				Err(Error::InvalidFormatTo(ext.to_string()))
 			}
 			
 			Convert::Tiff(v) => {
-				if tex::EXT.contains(&ext) {return Ok(<Tex as Bytes>::write(&<Tex as Tiff>::read(&mut Cursor::new(v))?, writer)?)}
+				if tex::EXT.contains(&ext) {return Ok(<Tex as Tiff>::read(&mut Cursor::new(v))?.write_tex(writer, tex_options)?)}
 				if hwc::EXT.contains(&ext) {return Ok(<Hwc as Bytes>::write(&<Hwc as Tiff>::read(&mut Cursor::new(v))?, writer)?)}
 				
 				Err(Error::InvalidFormatTo(ext.to_string()))
 			}
 			
 			Convert::Tga(v) => {
-				if tex::EXT.contains(&ext) {return Ok(<Tex as Bytes>::write(&<Tex as Tga>::read(&mut Cursor::new(v))?, writer)?)}
+				if tex::EXT.contains(&ext) {return Ok(<Tex as Tga>::read(&mut Cursor::new(v))?.write_tex(writer, tex_options)?)}
 				if hwc::EXT.contains(&ext) {return Ok(<Hwc as Bytes>::write(&<Hwc as Tga>::read(&mut Cursor::new(v))?, writer)?)}
 				
 				Err(Error::InvalidFormatTo(ext.to_string()))
 			}
+			
+			Convert::Jpeg(v) => {
+				if tex::EXT.contains(&ext) {return Ok(<Tex as Jpeg>::read(&mut Cursor::new(v))?.write_tex(writer, tex_options)?)}
+				if hwc::EXT.contains(&ext) {return Ok(<Hwc as Bytes>::write(&<Hwc as Jpeg>::read(&mut Cursor::new(v))?, writer)?)}
+				
+				Err(Error::InvalidFormatTo(ext.to_string()))
+			}
+			
+			Convert::Webp(v) => {
+				if tex::EXT.contains(&ext) {return Ok(<Tex as Webp>::read(&mut Cursor::new(v))?.write_tex(writer, tex_options)?)}
+				if hwc::EXT.contains(&ext) {return Ok(<Hwc as Bytes>::write(&<Hwc as Webp>::read(&mut Cursor::new(v))?, writer)?)}
+				
+				Err(Error::InvalidFormatTo(ext.to_string()))
+			}
+			
+			Convert::Bmp(v) => {
+				if tex::EXT.contains(&ext) {return Ok(<Tex as Bmp>::read(&mut Cursor::new(v))?.write_tex(writer, tex_options)?)}
+				if hwc::EXT.contains(&ext) {return Ok(<Hwc as Bytes>::write(&<Hwc as Bmp>::read(&mut Cursor::new(v))?, writer)?)}
+				
+				Err(Error::InvalidFormatTo(ext.to_string()))
+			}
+			
+			Convert::Exr(v) => {
+				if tex::EXT.contains(&ext) {return Ok(<Tex as Exr>::read(&mut Cursor::new(v))?.write_tex(writer, tex_options)?)}
+				
+				Err(Error::InvalidFormatTo(ext.to_string()))
+			}
+			
+			Convert::Csv(v) | Convert::Json(v) => {
+				if exd::EXT.contains(&ext) {
+					// the path is the page to write, the whole sheet is needed for the header and to verify the rows fit in the pages
+					let Some(file_path) = file_path else {return Err(Error::ParametersRequires)};
+					let Some(file_reader) = file_reader else {return Err(Error::ParametersRequires)};
+					let (name, language) = Excel::parse_path(file_path).ok_or_else(|| Error::InvalidData(format!("{file_path} is not a sheet path")))?;
+					let base = Excel::load(&name, language, file_reader)?;
+					let base_pages = base.pages()?;
+					let base_row_count = base.sheet.rows.len();
+					let excel = match self {
+						Convert::Csv(_) => <Excel as Csv>::read(&mut Cursor::new(v), base)?,
+						_ => <Excel as Json>::read(&mut Cursor::new(v), base)?,
+					};
+					
+					// only a single file can be written, changes that need other pages or the header can't be represented
+					if excel.sheet.rows.len() != base_row_count {
+						return Err(Error::InvalidData(format!("The row count of sheet {name} changed which requires writing exd/{name}.exh as well, only a single page can be converted")));
+					}
+					
+					let mut page = None;
+					for ((path, new_page), (_, base_page)) in excel.pages()?.into_iter().zip(base_pages) {
+						if path == file_path {
+							page = Some(new_page);
+						} else if new_page.rows != base_page.rows {
+							return Err(Error::InvalidData(format!("Rows of page {path} changed, the input may only change rows of {file_path} since a single page is written")));
+						}
+					}
+					
+					let Some(page) = page else {
+						return Err(Error::InvalidData(format!("{file_path} is not a page of sheet {name}")));
+					};
+					
+					return Ok(page.write(writer, &excel.header)?)
+				}
+				
+				if let (Convert::Json(_), true) = (self, uld::EXT.contains(&ext)) {
+					return Ok(<Uld as Bytes>::write(&<Uld as Json>::read(&mut Cursor::new(v), ())?, writer)?)
+				}
+				
+				if let (Convert::Json(_), true) = (self, phyb::EXT.contains(&ext)) {
+					return Ok(<Phyb as Bytes>::write(&<Phyb as Json>::read(&mut Cursor::new(v), ())?, writer)?)
+				}
+				
+				if let (Convert::Json(_), true) = (self, atch::EXT.contains(&ext)) {
+					return Ok(<Atch as Bytes>::write(&<Atch as Json>::read(&mut Cursor::new(v), ())?, writer)?)
+				}
+				
+				Err(Error::InvalidFormatTo(ext.to_string()))
+			}
+			
+			Convert::Ogg(v) | Convert::Wav(v) => {
+				if scd::EXT.contains(&ext) {
+					// the sound replaces the one of the game file, it holds the tables describing how to play it
+					let Some(file_path) = file_path else {return Err(Error::ParametersRequires)};
+					let Some(file_reader) = file_reader else {return Err(Error::ParametersRequires)};
+					let base_data = file_reader(file_path).ok_or_else(|| Error::InvalidData(format!("Failed to load {file_path}")))?;
+					let base = <Scd as Bytes>::read(&mut Cursor::new(base_data))?;
+					let scd = match self {
+						Convert::Ogg(_) => <Scd as Ogg>::read(&mut Cursor::new(v), base)?,
+						_ => <Scd as Wav>::read(&mut Cursor::new(v), base)?,
+					};
+					
+					return Ok(<Scd as Bytes>::write(&scd, writer)?)
+				}
+				
+				Err(Error::InvalidFormatTo(ext.to_string()))
+			}
 		}
 	}
 }
@@ -260,6 +485,8 @@ pub enum Error {
 	#[error("{0:?}")] DdsCreate(#[from] image_dds::CreateDdsError),
 	#[error("{0:?}")] DdsSurface(#[from] image_dds::error::SurfaceError),
 	#[error("{0:?}")] Image(#[from] image::ImageError),
+	#[error("{0:?}")] Gltf(#[from] gltf::Error),
+	#[error("{0:?}")] Json(#[from] serde_json::Error),
 	
 	#[error("Invalid format to convert from {0:?}")]
 	InvalidFormatFrom(String),
@@ -267,6 +494,10 @@ pub enum Error {
 	InvalidFormatTo(String),
 	#[error("Arguments path and file_reading are not set but are needed for this file")]
 	ParametersRequires,
+	#[error("Invalid data: {0}")]
+	InvalidData(String),
+	#[error("File not found {0:?}")]
+	NotFound(String),
 }
 
 // ----------
@@ -277,33 +508,45 @@ pub(crate) fn crc32(buf: &[u8]) -> u32 {
 	!hasher.finalize()
 }
 
-struct VoidReader;
-impl ironworks::file::File for VoidReader {
-	fn read(_data: impl ironworks::FileStream) -> Result<Self, ironworks::Error> {
-		Ok(VoidReader)
+/// Files that can be read from the game data
+pub trait File: Sized {
+	fn read<T>(reader: T) -> Result<Self, Error> where T: Read + Seek;
+}
+
+impl File for Vec<u8> {
+	fn read<T>(mut reader: T) -> Result<Self, Error> where
+	T: Read + Seek {
+		let mut data = Vec::new();
+		reader.read_to_end(&mut data)?;
+		Ok(data)
 	}
 }
 
-// TODO: own game data reader, drop ironworks as it is barely used
-pub struct Noumenon(ironworks::Ironworks);
+pub struct Noumenon(sqpack::SqPack);
 
 impl Noumenon {
 	pub fn exists(&self, path: &str) -> bool {
-		self.file::<VoidReader>(path).is_ok()
+		self.0.exists(path)
 	}
-}
-
-impl Deref for Noumenon {
-	type Target = ironworks::Ironworks;
 	
-	fn deref(&self) -> &Self::Target {
-		&self.0
+	pub fn file<T>(&self, path: &str) -> Result<T, Error> where
+	T: File {
+		T::read(self.0.stream(path)?)
 	}
-}
-
-impl DerefMut for Noumenon {
-	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.0
+	
+	/// Decompresses blocks as they are read, use this to only read part of large files
+	pub fn stream(&self, path: &str) -> Result<sqpack::FileStream, Error> {
+		self.0.stream(path)
+	}
+	
+	/// Drops the loaded indexes, needed after the game updates while we are running
+	pub fn clear_cache(&self) {
+		self.0.clear_cache();
+	}
+	
+	/// Sheet by name (such as `item` or `quest/000/clsarc001_00084`), sheets that aren't localized ignore the language
+	pub fn sheet(&self, name: &str, language: format::game::exh::Language) -> Result<Sheet<'_>, Error> {
+		Sheet::new(self, name, language)
 	}
 }
 
@@ -313,8 +556,7 @@ pub fn get_noumenon<P>(gamepath: Option<P>) -> Option<Noumenon> where
 P: AsRef<Path> {
 	if let Some(gamepath) = gamepath {
 		if gamepath.as_ref().exists() && gamepath.as_ref().join("game").exists() {
-			return Some(Noumenon(ironworks::Ironworks::new()
-				.with_resource(ironworks::sqpack::SqPack::new(ironworks::sqpack::Install::at(gamepath.as_ref())))));
+			return Some(Noumenon(sqpack::SqPack::new(gamepath.as_ref())));
 		}
 	} else {
 		// super basic windows autodetect
@@ -329,8 +571,7 @@ P: AsRef<Path> {
 			":/SteamLibrary/steamapps/common/FINAL FANTASY XIV - A Realm Reborn"] {
 				let try_path = PathBuf::from(format!("{drive_letter}{path}"));
 				if try_path.exists() {
-					return Some(Noumenon(ironworks::Ironworks::new()
-						.with_resource(ironworks::sqpack::SqPack::new(ironworks::sqpack::Install::at(try_path.as_ref())))));
+					return Some(Noumenon(sqpack::SqPack::new(&try_path)));
 				}
 			}
 		}
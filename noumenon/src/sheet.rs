@@ -0,0 +1,91 @@
+use std::{cell::OnceCell, io::Cursor};
+use crate::format::game::{exd::{Field, Row}, exh::Language, Excel, Exd, Exh};
+
+/// A sheet of the game data, pages are only loaded once a row inside of them is requested
+pub struct Sheet<'a> {
+	noumenon: &'a crate::Noumenon,
+	name: String,
+	language: Language,
+	header: Exh,
+	pages: Vec<OnceCell<Exd>>,
+}
+
+impl<'a> Sheet<'a> {
+	pub(crate) fn new(noumenon: &'a crate::Noumenon, name: &str, language: Language) -> Result<Self, crate::Error> {
+		let header = noumenon.file::<Exh>(&format!("exd/{name}.exh"))
+			.map_err(|err| crate::Error::InvalidData(format!("Failed to load the header of sheet {name}: {err}")))?;
+		let language = header.resolve_language(language)
+			.ok_or_else(|| crate::Error::InvalidData(format!("Sheet {name} does not exist in {language:?}")))?;
+		
+		Ok(Self {
+			noumenon,
+			name: name.to_string(),
+			language,
+			pages: header.pages.iter().map(|_| OnceCell::new()).collect(),
+			header,
+		})
+	}
+	
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+	
+	/// The language pages are loaded in, None if the sheet isn't localized
+	pub fn language(&self) -> Language {
+		self.language
+	}
+	
+	pub fn header(&self) -> &Exh {
+		&self.header
+	}
+	
+	/// Err if the page containing the row failed to load
+	pub fn row(&self, id: u32) -> Result<Option<&Row>, crate::Error> {
+		let Some(index) = self.header.page_index(id) else {return Ok(None)};
+		Ok(self.page(index)?.row(id))
+	}
+	
+	pub fn sub_row(&self, id: u32, sub_id: u16) -> Result<Option<&Vec<Field>>, crate::Error> {
+		Ok(self.row(id)?.and_then(|v| v.sub_rows.iter().find(|v| v.id == sub_id)).map(|v| &v.fields))
+	}
+	
+	/// All rows of all pages in order, loading each page once the previous one is done
+	pub fn rows(&self) -> impl Iterator<Item = Result<&Row, crate::Error>> {
+		(0..self.pages.len()).flat_map(move |index| {
+			let (rows, err) = match self.page(index) {
+				Ok(page) => (page.rows.as_slice(), None),
+				Err(err) => (&[][..], Some(Err(err))),
+			};
+			
+			rows.iter().map(Ok).chain(err)
+		})
+	}
+	
+	/// Loads every page into a single owned sheet, used for editing
+	pub fn to_excel(&self) -> Result<Excel, crate::Error> {
+		let mut sheet = Exd::new();
+		for row in self.rows() {
+			sheet.rows.push(row?.clone());
+		}
+		
+		Ok(Excel {
+			name: self.name.clone(),
+			language: self.language,
+			header: self.header.clone(),
+			sheet,
+		})
+	}
+	
+	fn page(&self, index: usize) -> Result<&Exd, crate::Error> {
+		if let Some(page) = self.pages[index].get() {
+			return Ok(page);
+		}
+		
+		let path = Excel::page_path(&self.name, self.header.pages[index].start_id, self.language);
+		let data = self.noumenon.file::<Vec<u8>>(&path)
+			.map_err(|err| crate::Error::InvalidData(format!("Failed to load {path}: {err}")))?;
+		let page = Exd::read(&mut Cursor::new(data), &self.header)?;
+		
+		Ok(self.pages[index].get_or_init(|| page))
+	}
+}
@@ -0,0 +1,104 @@
+use std::{collections::HashMap, path::{Path, PathBuf}, sync::{Arc, RwLock}};
+
+mod index;
+pub use index::{hash, hash_index1, hash_index2, Index, IndexKind, Location};
+mod dat;
+pub use dat::FileStream;
+
+fn category_id(name: &str) -> Option<u8> {
+	Some(match name {
+		"common" => 0x00,
+		"bgcommon" => 0x01,
+		"bg" => 0x02,
+		"cut" => 0x03,
+		"chara" => 0x04,
+		"shader" => 0x05,
+		"ui" => 0x06,
+		"sound" => 0x07,
+		"vfx" => 0x08,
+		"ui_script" => 0x09,
+		"exd" => 0x0A,
+		"game_script" => 0x0B,
+		"music" => 0x0C,
+		"sqpack_test" => 0x12,
+		"debug" => 0x13,
+		_ => return None,
+	})
+}
+
+/// Game data reader, indexes are loaded once they are first needed and kept around until `clear_cache` is called.
+/// Files are opened per read so multiple threads can read at the same time
+pub struct SqPack {
+	path: PathBuf,
+	// None if the index doesn't exist, so we don't keep hitting the disk for missing chunks
+	indexes: RwLock<HashMap<String, Option<Arc<Index>>>>,
+}
+
+impl SqPack {
+	/// Path of the game install, the directory containing 'boot' and 'game'
+	pub fn new(install_path: &Path) -> Self {
+		Self {
+			path: install_path.join("game").join("sqpack"),
+			indexes: RwLock::new(HashMap::new()),
+		}
+	}
+	
+	pub fn clear_cache(&self) {
+		self.indexes.write().unwrap().clear();
+	}
+	
+	pub fn exists(&self, path: &str) -> bool {
+		self.find(path).is_some()
+	}
+	
+	/// The dat and offset a file is stored at
+	pub fn find(&self, path: &str) -> Option<(PathBuf, Location)> {
+		let path = path.to_ascii_lowercase();
+		let mut segments = path.split('/');
+		let category = category_id(segments.next()?)?;
+		let (repository, expansion) = match segments.next() {
+			Some(v) if v.starts_with("ex") && v[2..].parse::<u8>().is_ok() => (v, v[2..].parse::<u8>().unwrap()),
+			_ => ("ffxiv", 0),
+		};
+		
+		for chunk in 0..=255u8 {
+			let name = format!("{repository}/{category:02x}{expansion:02x}{chunk:02x}.win32");
+			let Some(index) = self.index(&name) else {break};
+			if let Some(location) = index.find(&path) {
+				return Some((self.path.join(format!("{name}.dat{}", location.data_file)), location));
+			}
+		}
+		
+		None
+	}
+	
+	pub fn stream(&self, path: &str) -> Result<FileStream, crate::Error> {
+		let (dat_path, location) = self.find(path).ok_or_else(|| crate::Error::NotFound(path.to_string()))?;
+		FileStream::open(&dat_path, location.offset)
+	}
+	
+	pub fn file(&self, path: &str) -> Result<Vec<u8>, crate::Error> {
+		let mut stream = self.stream(path)?;
+		let mut data = Vec::with_capacity(stream.size() as usize);
+		std::io::Read::read_to_end(&mut stream, &mut data)?;
+		
+		Ok(data)
+	}
+	
+	fn index(&self, name: &str) -> Option<Arc<Index>> {
+		if let Some(index) = self.indexes.read().unwrap().get(name) {
+			return index.clone();
+		}
+		
+		// prefer .index, .index2 is only used if that's all there is
+		let index1 = self.path.join(format!("{name}.index"));
+		let index = if index1.exists() {
+			Index::load(&index1, IndexKind::Index1).ok()
+		} else {
+			Index::load(&self.path.join(format!("{name}.index2")), IndexKind::Index2).ok()
+		}.map(Arc::new);
+		
+		self.indexes.write().unwrap().insert(name.to_string(), index.clone());
+		index
+	}
+}
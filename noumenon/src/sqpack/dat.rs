@@ -0,0 +1,279 @@
+use std::{fs::File, io::{BufReader, Read, Seek, SeekFrom}, path::Path};
+use binrw::{binrw, BinRead};
+
+// blocks with this compressed size are stored uncompressed
+const UNCOMPRESSED_MARKER: u32 = 32000;
+const MDL_HEADER_SIZE: u64 = 0x44;
+
+#[derive(Debug, Clone)]
+struct Block {
+	/// Offset of the block header in the dat file
+	offset: u64,
+	/// Offset in the output
+	output_offset: u64,
+	compressed_size: u32,
+	decompressed_size: u32,
+}
+
+/// A file inside of a dat, blocks are decompressed as they are read.
+/// Seeking allows reading only part of a file, such as the header or the smaller mips of a texture
+pub struct FileStream {
+	reader: BufReader<File>,
+	/// Uncompressed data at the start of the output (texture header, rebuilt model header)
+	prefix: Vec<u8>,
+	blocks: Vec<Block>,
+	size: u64,
+	pos: u64,
+	// the last decompressed block, reads tend to be sequential
+	cache: Option<(usize, Vec<u8>)>,
+}
+
+impl FileStream {
+	pub fn open(path: &Path, offset: u64) -> Result<Self, crate::Error> {
+		let mut reader = BufReader::new(File::open(path)?);
+		reader.seek(SeekFrom::Start(offset))?;
+		let header = FileHeaderRaw::read_le(&mut reader)?;
+		let data_offset = offset + header.header_size as u64;
+		
+		let mut stream = Self {
+			reader,
+			prefix: Vec::new(),
+			blocks: Vec::new(),
+			size: 0,
+			pos: 0,
+			cache: None,
+		};
+		
+		match header.kind {
+			2 => {
+				let entries = (0..header.block_count).map(|_| StandardBlockRaw::read_le(&mut stream.reader)).collect::<Result<Vec<_>, _>>()?;
+				for entry in entries {
+					stream.add_block(data_offset + entry.offset as u64)?;
+				}
+			}
+			
+			3 => {
+				let model = ModelHeaderRaw::read_le(&mut stream.reader)?;
+				let total_blocks = model.block_index.iter().zip(&model.block_count).map(|(i, c)| i + c).max().unwrap_or(0);
+				let block_sizes = (0..total_blocks).map(|_| u16::read_le(&mut stream.reader)).collect::<Result<Vec<_>, _>>()?;
+				
+				// the model header isn't stored, it gets rebuilt from the block info
+				stream.size = MDL_HEADER_SIZE;
+				let section = |stream: &mut Self, index: usize| -> Result<(u64, u64), crate::Error> {
+					let start = stream.size;
+					let mut offset = data_offset + model.offset[index] as u64;
+					let block_index = model.block_index[index] as usize;
+					for i in block_index..block_index + model.block_count[index] as usize {
+						stream.add_block(offset)?;
+						offset += *block_sizes.get(i).ok_or_else(|| crate::Error::InvalidData("Model block is out of bounds".to_string()))? as u64;
+					}
+					
+					Ok((start, stream.size - start))
+				};
+				
+				let (_, stack_size) = section(&mut stream, 0)?;
+				let (_, runtime_size) = section(&mut stream, 1)?;
+				let mut vertex_offsets = [0u32; 3];
+				let mut vertex_sizes = [0u32; 3];
+				let mut index_offsets = [0u32; 3];
+				let mut index_sizes = [0u32; 3];
+				for lod in 0..3 {
+					let (offset, size) = section(&mut stream, 2 + lod)?;
+					if size > 0 {
+						vertex_offsets[lod] = offset as u32;
+						vertex_sizes[lod] = size as u32;
+					}
+					
+					section(&mut stream, 5 + lod)?;
+					
+					let (offset, size) = section(&mut stream, 8 + lod)?;
+					if size > 0 {
+						index_offsets[lod] = offset as u32;
+						index_sizes[lod] = size as u32;
+					}
+				}
+				
+				let mut prefix = Vec::with_capacity(MDL_HEADER_SIZE as usize);
+				prefix.extend_from_slice(&header.block_count.to_le_bytes()); // version for models
+				prefix.extend_from_slice(&(stack_size as u32).to_le_bytes());
+				prefix.extend_from_slice(&(runtime_size as u32).to_le_bytes());
+				prefix.extend_from_slice(&model.vertex_declaration_count.to_le_bytes());
+				prefix.extend_from_slice(&model.material_count.to_le_bytes());
+				for v in vertex_offsets.iter().chain(&index_offsets).chain(&vertex_sizes).chain(&index_sizes) {
+					prefix.extend_from_slice(&v.to_le_bytes());
+				}
+				prefix.extend_from_slice(&[model.lod_count, model.index_buffer_streaming, model.edge_geometry, 0]);
+				stream.prefix = prefix;
+			}
+			
+			4 => {
+				let lods = (0..header.block_count).map(|_| TextureLodRaw::read_le(&mut stream.reader)).collect::<Result<Vec<_>, _>>()?;
+				let total_blocks = lods.iter().map(|v| v.block_offset + v.block_count).max().unwrap_or(0);
+				let block_sizes = (0..total_blocks).map(|_| u16::read_le(&mut stream.reader)).collect::<Result<Vec<_>, _>>()?;
+				
+				// the texture header is stored uncompressed before the first lod
+				let header_size = lods.first().map_or(0, |v| v.compressed_offset);
+				let mut prefix = vec![0; header_size as usize];
+				stream.reader.seek(SeekFrom::Start(data_offset))?;
+				stream.reader.read_exact(&mut prefix)?;
+				stream.size = prefix.len() as u64;
+				stream.prefix = prefix;
+				
+				for lod in lods {
+					let mut offset = data_offset + lod.compressed_offset as u64;
+					for i in lod.block_offset..lod.block_offset + lod.block_count {
+						stream.add_block(offset)?;
+						offset += *block_sizes.get(i as usize).ok_or_else(|| crate::Error::InvalidData("Texture block is out of bounds".to_string()))? as u64;
+					}
+				}
+			}
+			
+			1 => return Err(crate::Error::NotFound("File is empty".to_string())),
+			kind => return Err(crate::Error::InvalidData(format!("Unknown dat file kind {kind}"))),
+		}
+		
+		Ok(stream)
+	}
+	
+	/// Decompressed size of the file
+	pub fn size(&self) -> u64 {
+		self.size
+	}
+	
+	// reads the header of a block and appends it to the end of the output
+	fn add_block(&mut self, offset: u64) -> Result<(), crate::Error> {
+		self.reader.seek(SeekFrom::Start(offset))?;
+		let header = BlockHeaderRaw::read_le(&mut self.reader)?;
+		self.blocks.push(Block {
+			offset: offset + header.header_size as u64,
+			output_offset: self.size,
+			compressed_size: header.compressed_size,
+			decompressed_size: header.decompressed_size,
+		});
+		self.size += header.decompressed_size as u64;
+		
+		Ok(())
+	}
+	
+	fn block_data(&mut self, index: usize) -> std::io::Result<&[u8]> {
+		if self.cache.as_ref().map(|v| v.0) != Some(index) {
+			let block = &self.blocks[index];
+			self.reader.seek(SeekFrom::Start(block.offset))?;
+			let mut data = Vec::with_capacity(block.decompressed_size as usize);
+			if block.compressed_size == UNCOMPRESSED_MARKER {
+				(&mut self.reader).take(block.decompressed_size as u64).read_to_end(&mut data)?;
+			} else {
+				flate2::read::DeflateDecoder::new((&mut self.reader).take(block.compressed_size as u64)).read_to_end(&mut data)?;
+			}
+			data.resize(block.decompressed_size as usize, 0);
+			
+			self.cache = Some((index, data));
+		}
+		
+		Ok(&self.cache.as_ref().unwrap().1)
+	}
+}
+
+impl Read for FileStream {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		if self.pos >= self.size || buf.is_empty() {
+			return Ok(0);
+		}
+		
+		let pos = self.pos;
+		let len = if pos < self.prefix.len() as u64 {
+			let data = &self.prefix[pos as usize..];
+			let len = data.len().min(buf.len());
+			buf[..len].copy_from_slice(&data[..len]);
+			len
+		} else {
+			let index = self.blocks.partition_point(|v| v.output_offset <= pos) - 1;
+			let start = (pos - self.blocks[index].output_offset) as usize;
+			let data = &self.block_data(index)?[start..];
+			let len = data.len().min(buf.len());
+			buf[..len].copy_from_slice(&data[..len]);
+			len
+		};
+		
+		self.pos += len as u64;
+		Ok(len)
+	}
+}
+
+impl Seek for FileStream {
+	fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+		let pos = match pos {
+			SeekFrom::Start(v) => v as i64,
+			SeekFrom::End(v) => self.size as i64 + v,
+			SeekFrom::Current(v) => self.pos as i64 + v,
+		};
+		
+		if pos < 0 {
+			return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Seek to a negative position"));
+		}
+		
+		self.pos = pos as u64;
+		Ok(self.pos)
+	}
+}
+
+// ----------
+
+#[binrw]
+#[brw(little)]
+#[derive(Debug, Clone)]
+struct FileHeaderRaw {
+	header_size: u32,
+	kind: u32,
+	_raw_size: u32,
+	_unk: [u32; 2],
+	block_count: u32,
+}
+
+#[binrw]
+#[brw(little)]
+#[derive(Debug, Clone)]
+struct BlockHeaderRaw {
+	header_size: u32,
+	_version: u32,
+	compressed_size: u32,
+	decompressed_size: u32,
+}
+
+#[binrw]
+#[brw(little)]
+#[derive(Debug, Clone)]
+struct StandardBlockRaw {
+	offset: u32,
+	_compressed_size: u16,
+	_decompressed_size: u16,
+}
+
+#[binrw]
+#[brw(little)]
+#[derive(Debug, Clone)]
+struct TextureLodRaw {
+	compressed_offset: u32,
+	_compressed_size: u32,
+	_decompressed_size: u32,
+	block_offset: u32,
+	block_count: u32,
+}
+
+#[binrw]
+#[brw(little)]
+#[derive(Debug, Clone)]
+// every section is stack, runtime, vertex buffer x3, edge geometry vertex buffer x3, index buffer x3
+struct ModelHeaderRaw {
+	_size: [u32; 11],
+	_compressed_size: [u32; 11],
+	offset: [u32; 11],
+	block_index: [u16; 11],
+	block_count: [u16; 11],
+	vertex_declaration_count: u16,
+	material_count: u16,
+	lod_count: u8,
+	index_buffer_streaming: u8,
+	edge_geometry: u8,
+	_padding: u8,
+}
@@ -0,0 +1,162 @@
+use std::{collections::HashMap, io::{Read, Seek, SeekFrom}, path::Path};
+use binrw::{binrw, BinRead};
+
+// the game uses crc32 without the final xor
+pub fn hash(data: &[u8]) -> u32 {
+	!crc32fast::hash(data)
+}
+
+/// Hash used by .index files, folder and file name hashed separately
+pub fn hash_index1(path: &str) -> u64 {
+	let (folder, file) = path.rsplit_once('/').unwrap_or(("", path));
+	((hash(folder.as_bytes()) as u64) << 32) | hash(file.as_bytes()) as u64
+}
+
+/// Hash used by .index2 files, the full path hashed
+pub fn hash_index2(path: &str) -> u64 {
+	hash(path.as_bytes()) as u64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexKind {
+	Index1,
+	Index2,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Location {
+	/// The .datN file
+	pub data_file: u8,
+	pub offset: u64,
+}
+
+impl Location {
+	fn from_raw(data: u32) -> Self {
+		Self {
+			data_file: ((data >> 1) & 0b111) as u8,
+			offset: (data & !0xF) as u64 * 8,
+		}
+	}
+}
+
+#[derive(Debug)]
+pub struct Index {
+	kind: IndexKind,
+	entries: HashMap<u64, u32>,
+	/// Paths with colliding hashes, these are looked up by the full path
+	synonyms: Vec<(String, u32)>,
+}
+
+impl Index {
+	pub fn load(path: &Path, kind: IndexKind) -> Result<Self, crate::Error> {
+		let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+		Self::read(&mut reader, kind)
+	}
+	
+	pub fn read<R: Read + Seek>(reader: &mut R, kind: IndexKind) -> Result<Self, crate::Error> {
+		let sqpack_header = SqPackHeaderRaw::read_le(reader)?;
+		reader.seek(SeekFrom::Start(sqpack_header.header_size as u64))?;
+		let header = IndexHeaderRaw::read_le(reader)?;
+		
+		let mut entries = HashMap::new();
+		reader.seek(SeekFrom::Start(header.index_offset as u64))?;
+		match kind {
+			IndexKind::Index1 => for _ in 0..header.index_size / 16 {
+				let entry = Index1EntryRaw::read_le(reader)?;
+				entries.insert(((entry.folder_hash as u64) << 32) | entry.file_hash as u64, entry.data);
+			}
+			
+			IndexKind::Index2 => for _ in 0..header.index_size / 8 {
+				let entry = Index2EntryRaw::read_le(reader)?;
+				entries.insert(entry.hash as u64, entry.data);
+			}
+		}
+		
+		let mut synonyms = Vec::new();
+		reader.seek(SeekFrom::Start(header.synonym_offset as u64))?;
+		for _ in 0..header.synonym_size / 0x100 {
+			let entry = SynonymEntryRaw::read_le(reader)?;
+			// the table is terminated by an entry without a path
+			let end = entry.path.iter().position(|v| *v == 0).unwrap_or(entry.path.len());
+			if end == 0 {break}
+			synonyms.push((String::from_utf8_lossy(&entry.path[..end]).to_string(), entry.data));
+		}
+		
+		Ok(Self {
+			kind,
+			entries,
+			synonyms,
+		})
+	}
+	
+	/// Path has to be lowercase
+	pub fn find(&self, path: &str) -> Option<Location> {
+		let hash = match self.kind {
+			IndexKind::Index1 => hash_index1(path),
+			IndexKind::Index2 => hash_index2(path),
+		};
+		
+		let data = *self.entries.get(&hash)?;
+		if data & 1 == 0 {
+			return Some(Location::from_raw(data));
+		}
+		
+		self.synonyms
+			.iter()
+			.find(|(v, _)| v == path)
+			.map(|(_, data)| Location::from_raw(*data))
+	}
+}
+
+// ----------
+
+#[binrw]
+#[brw(little, magic = b"SqPack\0\0")]
+#[derive(Debug, Clone)]
+struct SqPackHeaderRaw {
+	_platform: u32,
+	header_size: u32,
+}
+
+#[binrw]
+#[brw(little)]
+#[derive(Debug, Clone)]
+struct IndexHeaderRaw {
+	_size: u32,
+	_version: u32,
+	index_offset: u32,
+	index_size: u32,
+	_index_hash: [u8; 64],
+	_data_file_count: u32,
+	synonym_offset: u32,
+	synonym_size: u32,
+}
+
+#[binrw]
+#[brw(little)]
+#[derive(Debug, Clone)]
+struct Index1EntryRaw {
+	file_hash: u32,
+	folder_hash: u32,
+	data: u32,
+	_padding: u32,
+}
+
+#[binrw]
+#[brw(little)]
+#[derive(Debug, Clone)]
+struct Index2EntryRaw {
+	hash: u32,
+	data: u32,
+}
+
+// same size for both index kinds, index1 stores the folder and file hash where index2 stores the hash and padding
+#[binrw]
+#[brw(little)]
+#[derive(Debug, Clone)]
+struct SynonymEntryRaw {
+	_hash: [u32; 2],
+	data: u32,
+	_index: u32,
+	path: [u8; 0xF0],
+}